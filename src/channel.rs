@@ -1,5 +1,7 @@
 //! Channel types.
 
+pub mod shorts;
+
 use crate::{
     youtube::{
         self, browse,
@@ -11,9 +13,7 @@ use crate::{
 define_id! {
     24,
     "An Id describing a [`Channel`]",
-    [
-        "channel/",
-    ]
+    crate::link::YoutubeUrl::Channel(crate::link::Channel::Id(id)) => id
 }
 
 impl Id {
@@ -145,6 +145,15 @@ impl Channel {
         Ok(self.client.playlist(self.id().uploads()).await?.videos())
     }
 
+    /// The [`Shorts`](shorts::Short) on the Shorts tab of a channel.
+    ///
+    /// Failed requests are yielded and retried with a backoff up to five
+    /// times in a row. Client errors other than `429 Too Many Requests` and
+    /// [`Youtube`](crate::error::Youtube) errors end the stream right away.
+    pub fn shorts(&self) -> impl futures_core::Stream<Item = crate::Result<shorts::Short>> {
+        shorts::stream(self.client.clone(), self.id())
+    }
+
     // TODO: Playlist
     // TODO: Channels
 }
@@ -0,0 +1,151 @@
+//! Shorts of a channel.
+
+use crate::{
+    stream::{backoff, is_retryable},
+    youtube::{
+        browse::channel::shorts::{RichItem, Root},
+        innertube::{Browse, ChannelPage},
+        parse_subscribers,
+    },
+    Client, Thumbnail,
+};
+
+/// How often a failed request is retried in a row before the stream ends
+const RETRIES: u32 = 5;
+
+/// A [Short](https://www.youtube.com/shorts) of a [`Channel`](super::Channel)
+#[derive(Clone)]
+pub struct Short {
+    client: Client,
+    id: crate::video::Id,
+    title: String,
+    views: Option<String>,
+    thumbnails: Vec<Thumbnail>,
+}
+
+impl Short {
+    fn new(client: Client, item: RichItem) -> Option<Self> {
+        if let Some(reel) = item.reel_item_renderer {
+            Some(Self {
+                client,
+                id: reel.video_id,
+                title: reel.headline.text(),
+                views: reel.view_count_text.map(|x| x.text()),
+                thumbnails: reel.thumbnail.thumbnails,
+            })
+        } else {
+            let lockup = item.shorts_lockup_view_model?;
+            Some(Self {
+                client,
+                id: lockup.on_tap.innertube_command.reel_watch_endpoint.video_id,
+                title: lockup
+                    .overlay_metadata
+                    .primary_text
+                    .map(|x| x.content)
+                    .unwrap_or_default(),
+                views: lockup.overlay_metadata.secondary_text.map(|x| x.content),
+                thumbnails: lockup.thumbnail.map(|x| x.sources).unwrap_or_default(),
+            })
+        }
+    }
+
+    /// The [`Id`](crate::video::Id) of a [`Short`]
+    pub fn id(&self) -> crate::video::Id {
+        self.id
+    }
+
+    /// The title of a [`Short`]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The amount of views of a [`Short`]. These are rounded, e.g. `1.2M`
+    /// views are `1200000`. [`None`] if the amount uses a unknown or
+    /// localized modifier.
+    pub fn views(&self) -> Option<u64> {
+        // "1.2M views"
+        let views = self.views.as_deref()?.split_once(' ')?.0;
+        parse_subscribers(&views.replace(',', ""))
+    }
+
+    /// The [`Thumbnails`](Thumbnail) of a [`Short`]
+    pub fn thumbnails(&self) -> impl Iterator<Item = &Thumbnail> {
+        self.thumbnails.iter()
+    }
+
+    /// Refetch this short as a [`Video`](crate::Video) for more information
+    pub async fn upgrade(&self) -> crate::Result<crate::Video> {
+        self.client.video(self.id).await
+    }
+}
+
+impl std::fmt::Debug for Short {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Short")
+            .field("id", &self.id())
+            .field("title", &self.title())
+            .field("views", &self.views())
+            .field("thumbnails", &self.thumbnails)
+            .finish()
+    }
+}
+
+impl PartialEq for Short {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for Short {}
+
+/// The [`Shorts`](Short) on the Shorts tab of the channel `id`. Failed
+/// requests are yielded and retried with a backoff up to [`RETRIES`] times,
+/// while client errors and [`Youtube`](crate::error::Youtube) errors end the
+/// stream.
+pub(crate) fn stream(
+    client: Client,
+    id: super::Id,
+) -> impl futures_core::Stream<Item = crate::Result<Short>> {
+    async_stream::stream! {
+        let mut browse = Some(Browse::Channel {
+            id,
+            page: ChannelPage::Shorts,
+        });
+        let mut retry = 0;
+
+        while let Some(current) = browse.take() {
+            let response = client
+                .api
+                .browse::<crate::youtube::browse::Result<Root>>(current.clone())
+                .await
+                .and_then(|x| x.into_std());
+            let response = match response {
+                Ok(response) => {
+                    retry = 0;
+                    response
+                }
+                Err(err) if retry < RETRIES && is_retryable(&err) => {
+                    yield Err(err);
+                    tokio::time::sleep(backoff(retry)).await;
+                    retry = retry.saturating_add(1);
+                    browse = Some(current);
+                    continue;
+                }
+                Err(err) => {
+                    yield Err(err);
+                    break;
+                }
+            };
+
+            for item in response.into_items() {
+                if let Some(item) = item.rich_item_renderer {
+                    if let Some(short) = Short::new(client.clone(), item.content) {
+                        yield Ok(short);
+                    }
+                } else if let Some(continuation) = item.continuation_item_renderer {
+                    browse = Some(Browse::Continuation(continuation.get()));
+                }
+            }
+        }
+    }
+}
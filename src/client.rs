@@ -1,5 +1,6 @@
 use crate::{
-    channel, playlist, stream, video, youtube::innertube::Api, Channel, Playlist, Stream, Video,
+    channel, link, playlist, stream, video, youtube::innertube::Api, Channel, Playlist, Stream,
+    Video,
 };
 
 /// A Client capable of interacting with YouTube
@@ -18,6 +19,16 @@ impl Client {
         Self::default()
     }
 
+    /// Send the requests to the YouTube API to `url` instead, e.g. a local
+    /// server in the tests.
+    // Only enabled for the integration tests, it is not part of the stable API
+    #[cfg(feature = "test-api-url")]
+    #[doc(hidden)]
+    pub fn with_api_url(mut self, url: reqwest::Url) -> Self {
+        self.api.base_url = Some(url);
+        self
+    }
+
     /// Get a [`Video`] identified by a [`Id`](video::Id)
     pub async fn video(&self, id: video::Id) -> crate::Result<Video> {
         Video::get(self.clone(), id).await
@@ -28,6 +39,21 @@ impl Client {
         stream::get(self.clone(), id).await
     }
 
+    /// Wait for an upcoming livestream or premiere identified by a
+    /// [`Id`](video::Id) to go live.
+    ///
+    /// This polls YouTube with a backoff based on the scheduled start and
+    /// reports every [`Event`](video::live::Event) until the
+    /// [`Streams`](Stream) become available or it is cancelled. Failed
+    /// requests are yielded as errors and retried with a backoff a few times
+    /// in a row, while client errors end the stream.
+    pub fn wait_for_live(
+        &self,
+        id: video::Id,
+    ) -> impl futures_core::Stream<Item = crate::Result<video::live::Event>> {
+        video::live::wait(self.clone(), id)
+    }
+
     /// Get a [`Playlist`] identified by a [`Id`](playlist::Id)
     pub async fn playlist(&self, id: playlist::Id) -> crate::Result<Playlist> {
         Playlist::get(self.clone(), id).await
@@ -37,4 +63,13 @@ impl Client {
     pub async fn channel(&self, id: channel::Id) -> crate::Result<Channel> {
         Channel::get(self.clone(), id).await
     }
+
+    /// Resolve any YouTube link, like a `@handle`, `/c/name`, `/user/name`
+    /// or a clip, to the [`Resolved`](link::Resolved) entity it points to.
+    ///
+    /// Links that already contain a id, like a video, are resolved without
+    /// a request.
+    pub async fn resolve(&self, url: &str) -> crate::Result<link::Resolved> {
+        link::resolve(self.clone(), url).await
+    }
 }
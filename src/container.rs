@@ -0,0 +1,6 @@
+//! Low level parsing and writing of the containers used by YouTube
+//! ([MP4](mp4), [WebM](ebml) and [Ogg](ogg)).
+
+pub mod ebml;
+pub mod mp4;
+pub mod ogg;
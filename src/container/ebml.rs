@@ -0,0 +1,345 @@
+//! [EBML](https://en.wikipedia.org/wiki/Extensible_Binary_Meta_Language)
+//! elements, as used by WebM and Matroska
+
+use std::convert::TryInto;
+
+/// The `EBML` header element
+pub const EBML: u32 = 0x1A45DFA3;
+/// The `EBMLVersion` element
+pub const EBML_VERSION: u32 = 0x4286;
+/// The `EBMLReadVersion` element
+pub const EBML_READ_VERSION: u32 = 0x42F7;
+/// The `EBMLMaxIDLength` element
+pub const EBML_MAX_ID_LENGTH: u32 = 0x42F2;
+/// The `EBMLMaxSizeLength` element
+pub const EBML_MAX_SIZE_LENGTH: u32 = 0x42F3;
+/// The `DocType` element
+pub const DOC_TYPE: u32 = 0x4282;
+/// The `DocTypeVersion` element
+pub const DOC_TYPE_VERSION: u32 = 0x4287;
+/// The `DocTypeReadVersion` element
+pub const DOC_TYPE_READ_VERSION: u32 = 0x4285;
+/// The `Segment` element
+pub const SEGMENT: u32 = 0x18538067;
+/// The `SeekHead` element
+pub const SEEK_HEAD: u32 = 0x114D9B74;
+/// The `Seek` element
+pub const SEEK: u32 = 0x4DBB;
+/// The `SeekID` element
+pub const SEEK_ID: u32 = 0x53AB;
+/// The `SeekPosition` element
+pub const SEEK_POSITION: u32 = 0x53AC;
+/// The `Void` element, used to pad or blank out elements
+pub const VOID: u32 = 0xEC;
+/// The `Info` element
+pub const INFO: u32 = 0x1549A966;
+/// The `TimecodeScale` element
+pub const TIMECODE_SCALE: u32 = 0x2AD7B1;
+/// The `Duration` element
+pub const DURATION: u32 = 0x4489;
+/// The `Title` element
+pub const TITLE: u32 = 0x7BA9;
+/// The `MuxingApp` element
+pub const MUXING_APP: u32 = 0x4D80;
+/// The `WritingApp` element
+pub const WRITING_APP: u32 = 0x5741;
+/// The `Tracks` element
+pub const TRACKS: u32 = 0x1654AE6B;
+/// The `TrackEntry` element
+pub const TRACK_ENTRY: u32 = 0xAE;
+/// The `TrackNumber` element
+pub const TRACK_NUMBER: u32 = 0xD7;
+/// The `TrackUID` element
+pub const TRACK_UID: u32 = 0x73C5;
+/// The `TrackType` element
+pub const TRACK_TYPE: u32 = 0x83;
+/// The `FlagDefault` element
+pub const FLAG_DEFAULT: u32 = 0x88;
+/// The `Language` element
+pub const LANGUAGE: u32 = 0x22B59C;
+/// The `LanguageIETF` element
+pub const LANGUAGE_IETF: u32 = 0x22B59D;
+/// The `CodecID` element
+pub const CODEC_ID: u32 = 0x86;
+/// The `CodecPrivate` element
+pub const CODEC_PRIVATE: u32 = 0x63A2;
+/// The `CodecDelay` element
+pub const CODEC_DELAY: u32 = 0x56AA;
+/// The `SeekPreRoll` element
+pub const SEEK_PRE_ROLL: u32 = 0x56BB;
+/// The `Video` element
+pub const VIDEO: u32 = 0xE0;
+/// The `PixelWidth` element
+pub const PIXEL_WIDTH: u32 = 0xB0;
+/// The `PixelHeight` element
+pub const PIXEL_HEIGHT: u32 = 0xBA;
+/// The `Audio` element
+pub const AUDIO: u32 = 0xE1;
+/// The `SamplingFrequency` element
+pub const SAMPLING_FREQUENCY: u32 = 0xB5;
+/// The `Channels` element
+pub const CHANNELS: u32 = 0x9F;
+/// The `Cluster` element
+pub const CLUSTER: u32 = 0x1F43B675;
+/// The `Timecode` element of a `Cluster`
+pub const TIMECODE: u32 = 0xE7;
+/// The `SimpleBlock` element
+pub const SIMPLE_BLOCK: u32 = 0xA3;
+/// The `BlockGroup` element
+pub const BLOCK_GROUP: u32 = 0xA0;
+/// The `Block` element
+pub const BLOCK: u32 = 0xA1;
+/// The `ReferenceBlock` element
+pub const REFERENCE_BLOCK: u32 = 0xFB;
+/// The `Cues` element
+pub const CUES: u32 = 0x1C53BB6B;
+/// The `CuePoint` element
+pub const CUE_POINT: u32 = 0xBB;
+/// The `CueTime` element
+pub const CUE_TIME: u32 = 0xB3;
+/// The `CueTrackPositions` element
+pub const CUE_TRACK_POSITIONS: u32 = 0xB7;
+/// The `CueTrack` element
+pub const CUE_TRACK: u32 = 0xF7;
+/// The `CueClusterPosition` element
+pub const CUE_CLUSTER_POSITION: u32 = 0xF1;
+/// The `Chapters` element
+pub const CHAPTERS: u32 = 0x1043A770;
+/// The `EditionEntry` element
+pub const EDITION_ENTRY: u32 = 0x45B9;
+/// The `ChapterAtom` element
+pub const CHAPTER_ATOM: u32 = 0xB6;
+/// The `ChapterUID` element
+pub const CHAPTER_UID: u32 = 0x73C4;
+/// The `ChapterTimeStart` element
+pub const CHAPTER_TIME_START: u32 = 0x91;
+/// The `ChapterTimeEnd` element
+pub const CHAPTER_TIME_END: u32 = 0x92;
+/// The `ChapterDisplay` element
+pub const CHAPTER_DISPLAY: u32 = 0x80;
+/// The `ChapString` element
+pub const CHAP_STRING: u32 = 0x85;
+/// The `Tags` element
+pub const TAGS: u32 = 0x1254C367;
+/// The `Tag` element
+pub const TAG: u32 = 0x7373;
+/// The `Targets` element
+pub const TARGETS: u32 = 0x63C0;
+/// The `TargetTypeValue` element
+pub const TARGET_TYPE_VALUE: u32 = 0x68CA;
+/// The `SimpleTag` element
+pub const SIMPLE_TAG: u32 = 0x67C8;
+/// The `TagName` element
+pub const TAG_NAME: u32 = 0x45A3;
+/// The `TagString` element
+pub const TAG_STRING: u32 = 0x4487;
+
+/// A EBML element
+#[derive(Debug, Clone, Copy)]
+pub struct Element<'a> {
+    /// The id of the element, including the length marker
+    pub id: u32,
+    /// The offset of the element relative to the parsed buffer
+    pub offset: usize,
+    /// The length of the id and size of the element
+    pub header_len: usize,
+    /// The payload of the element
+    pub data: &'a [u8],
+}
+
+impl<'a> Element<'a> {
+    /// The child elements of a master element
+    pub fn children(&self) -> Elements<'a> {
+        elements(self.data)
+    }
+
+    /// The first child element with `id`
+    pub fn child(&self, id: u32) -> Option<Element<'a>> {
+        find(self.data, id)
+    }
+
+    /// The payload as a unsigned integer
+    pub fn uint(&self) -> Option<u64> {
+        if self.data.len() > 8 {
+            return None;
+        }
+        Some(
+            self.data
+                .iter()
+                .fold(0, |acc, x| (acc << 8) | u64::from(*x)),
+        )
+    }
+
+    /// The payload as a float
+    pub fn float(&self) -> Option<f64> {
+        match self.data.len() {
+            4 => Some(f32::from_be_bytes(self.data.try_into().ok()?).into()),
+            8 => Some(f64::from_be_bytes(self.data.try_into().ok()?)),
+            _ => None,
+        }
+    }
+}
+
+/// A iterator over sibling elements
+#[derive(Debug, Clone)]
+pub struct Elements<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = Element<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.data.get(self.offset..)?;
+        let (id, size, header_len) = read_header(rest)?;
+
+        // A element with a unknown size extends to the end of the buffer
+        let data = match size {
+            Some(size) => rest.get(header_len..header_len.checked_add(size.try_into().ok()?)?)?,
+            None => &rest[header_len..],
+        };
+
+        let ret = Element {
+            id,
+            offset: self.offset,
+            header_len,
+            data,
+        };
+        self.offset += header_len + data.len();
+
+        Some(ret)
+    }
+}
+
+/// Iterate over the elements in `data`
+pub fn elements(data: &[u8]) -> Elements<'_> {
+    Elements { data, offset: 0 }
+}
+
+/// Find the first element with `id` in `data`
+pub fn find(data: &[u8], id: u32) -> Option<Element<'_>> {
+    elements(data).find(|x| x.id == id)
+}
+
+/// Read the header of a element, returning its id, size and the length of
+/// the header. This does not require the payload to be part of `data`.
+pub fn read_header(data: &[u8]) -> Option<(u32, Option<u64>, usize)> {
+    let (id, id_len) = read_id(data)?;
+    let (size, size_len) = read_size(data.get(id_len..)?)?;
+    Some((id, size, id_len + size_len))
+}
+
+/// Find the `Segment` following the `EBML` header, returning its offset and
+/// header length. The payload of the `Segment` is usually not contained in
+/// `data`, as it spans the whole file.
+pub fn segment(data: &[u8]) -> Option<(usize, usize)> {
+    let header = find(data, EBML)?;
+    let offset = header.offset + header.header_len + header.data.len();
+
+    match read_header(&data[offset..])? {
+        (SEGMENT, _, header_len) => Some((offset, header_len)),
+        _ => None,
+    }
+}
+
+/// The length of a variable size integer, determined by its first byte
+fn vint_len(first: u8) -> Option<usize> {
+    match first.leading_zeros() {
+        zeros @ 0..=7 => Some(zeros as usize + 1),
+        _ => None,
+    }
+}
+
+/// Read a element id, keeping the length marker
+pub fn read_id(data: &[u8]) -> Option<(u32, usize)> {
+    let len = vint_len(*data.first()?)?;
+    if len > 4 {
+        return None;
+    }
+    let id = data
+        .get(..len)?
+        .iter()
+        .fold(0, |acc, x| (acc << 8) | u32::from(*x));
+    Some((id, len))
+}
+
+/// Read a element size. Returns [`None`] as the size if it is unknown.
+pub fn read_size(data: &[u8]) -> Option<(Option<u64>, usize)> {
+    let len = vint_len(*data.first()?)?;
+    let bytes = data.get(..len)?;
+
+    let marker = 0xFFu8.checked_shr(len as u32).unwrap_or(0);
+    let value = bytes[1..]
+        .iter()
+        .fold(u64::from(bytes[0] & marker), |acc, x| {
+            (acc << 8) | u64::from(*x)
+        });
+
+    // All value bits set means unknown size
+    let unknown = (1u64 << (7 * len)) - 1;
+    Some((if value == unknown { None } else { Some(value) }, len))
+}
+
+/// Encode a element size as a variable size integer of exactly `len` bytes.
+/// A size of [`None`] encodes a unknown size.
+pub fn encode_size(size: Option<u64>, len: usize) -> Option<Vec<u8>> {
+    if !(1..=8).contains(&len) {
+        return None;
+    }
+
+    let max = (1u64 << (7 * len)) - 1;
+    let value = match size {
+        Some(size) if size < max => size,
+        Some(_) => return None,
+        None => max,
+    };
+
+    let mut bytes = value.to_be_bytes()[8 - len..].to_vec();
+    bytes[0] |= 0x80 >> (len - 1);
+    Some(bytes)
+}
+
+/// Write a element with `data` as its payload
+pub fn write(out: &mut Vec<u8>, id: u32, data: &[u8]) {
+    write_id(out, id);
+    write_size(out, data.len() as u64);
+    out.extend_from_slice(data);
+}
+
+/// Write a element id
+pub fn write_id(out: &mut Vec<u8>, id: u32) {
+    let skip = (id.leading_zeros() / 8) as usize;
+    out.extend_from_slice(&id.to_be_bytes()[skip.min(3)..]);
+}
+
+/// Write a element size using the least amount of bytes
+pub fn write_size(out: &mut Vec<u8>, size: u64) {
+    let len = (1..=8)
+        .find(|len| size < (1 << (7 * len)) - 1)
+        .expect("Element size too large");
+    out.extend(encode_size(Some(size), len).expect("Element size too large"));
+}
+
+/// Write a unsigned integer element
+pub fn write_uint(out: &mut Vec<u8>, id: u32, value: u64) {
+    let skip = (value.leading_zeros() / 8) as usize;
+    write(out, id, &value.to_be_bytes()[skip.min(7)..]);
+}
+
+/// Write a float element
+pub fn write_float(out: &mut Vec<u8>, id: u32, value: f64) {
+    write(out, id, &value.to_be_bytes());
+}
+
+/// Write a string element
+pub fn write_str(out: &mut Vec<u8>, id: u32, value: &str) {
+    write(out, id, value.as_bytes());
+}
+
+/// Write a master element, whose children are written by `children`
+pub fn write_master(out: &mut Vec<u8>, id: u32, children: impl FnOnce(&mut Vec<u8>)) {
+    let mut data = Vec::new();
+    children(&mut data);
+    write(out, id, &data);
+}
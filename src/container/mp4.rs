@@ -0,0 +1,178 @@
+//! [ISO base media file format](https://en.wikipedia.org/wiki/ISO/IEC_base_media_file_format)
+//! boxes
+
+use std::convert::{TryFrom, TryInto};
+
+/// A box of a MP4 file
+#[derive(Debug, Clone, Copy)]
+pub struct Mp4Box<'a> {
+    /// The four character code of the box, e.g. `moov`
+    pub kind: [u8; 4],
+    /// The offset of the box header relative to the parsed buffer
+    pub offset: usize,
+    /// The size of the box including its header
+    pub size: usize,
+    /// The payload of the box
+    pub data: &'a [u8],
+}
+
+impl<'a> Mp4Box<'a> {
+    /// The length of the box header
+    pub fn header_len(&self) -> usize {
+        self.size - self.data.len()
+    }
+
+    /// The child boxes of a container box
+    pub fn children(&self) -> Boxes<'a> {
+        boxes(self.data)
+    }
+
+    /// The first child box of `kind`
+    pub fn child(&self, kind: &[u8; 4]) -> Option<Mp4Box<'a>> {
+        find(self.data, kind)
+    }
+}
+
+/// A iterator over sibling boxes
+#[derive(Debug, Clone)]
+pub struct Boxes<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for Boxes<'a> {
+    type Item = Mp4Box<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.data.get(self.offset..)?;
+        let mut reader = Reader::new(rest);
+
+        let size = reader.u32()?;
+        let kind = reader.bytes(4)?.try_into().ok()?;
+        let size = match size {
+            // The box extends to the end of the buffer
+            0 => rest.len(),
+            1 => reader.u64()?.try_into().ok()?,
+            size => size as usize,
+        };
+
+        let data = rest.get(reader.position()..size)?;
+        let ret = Mp4Box {
+            kind,
+            offset: self.offset,
+            size,
+            data,
+        };
+        self.offset += size;
+
+        Some(ret)
+    }
+}
+
+/// Iterate over the boxes in `data`
+pub fn boxes(data: &[u8]) -> Boxes<'_> {
+    Boxes { data, offset: 0 }
+}
+
+/// Find the first box of `kind` in `data`
+pub fn find<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<Mp4Box<'a>> {
+    boxes(data).find(|x| &x.kind == kind)
+}
+
+/// A big endian reader over a byte slice
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Create a new [`Reader`] at the start of `data`
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// The current position of the [`Reader`]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Read `len` bytes
+    pub fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let ret = self
+            .data
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(ret)
+    }
+
+    /// Read a [`u8`]
+    pub fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    /// Read a big endian [`u16`]
+    pub fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    /// Read a big endian 24-bit integer
+    pub fn u24(&mut self) -> Option<u32> {
+        let bytes = self.bytes(3)?;
+        Some(u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]))
+    }
+
+    /// Read a big endian [`u32`]
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+
+    /// Read a big endian [`u64`]
+    pub fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.bytes(8)?.try_into().ok()?))
+    }
+}
+
+/// Write a box with `data` as its payload
+pub fn write(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    write_header(out, kind, data.len() as u64);
+    out.extend_from_slice(data);
+}
+
+/// Write the header of a box with a payload of `len` bytes
+pub fn write_header(out: &mut Vec<u8>, kind: &[u8; 4], len: u64) {
+    let size = len + 8;
+    match u32::try_from(size) {
+        Ok(size) => {
+            out.extend_from_slice(&size.to_be_bytes());
+            out.extend_from_slice(kind);
+        }
+        Err(_) => {
+            out.extend_from_slice(&1u32.to_be_bytes());
+            out.extend_from_slice(kind);
+            out.extend_from_slice(&(size + 8).to_be_bytes());
+        }
+    }
+}
+
+/// Write a box, whose payload is written by `content`
+pub fn write_box(out: &mut Vec<u8>, kind: &[u8; 4], content: impl FnOnce(&mut Vec<u8>)) {
+    let mut data = Vec::new();
+    content(&mut data);
+    write(out, kind, &data);
+}
+
+/// Write a full box with a `version` and `flags`, whose remaining payload is
+/// written by `content`
+pub fn write_full_box(
+    out: &mut Vec<u8>,
+    kind: &[u8; 4],
+    version: u8,
+    flags: u32,
+    content: impl FnOnce(&mut Vec<u8>),
+) {
+    write_box(out, kind, |out| {
+        out.extend_from_slice(&(u32::from(version) << 24 | flags).to_be_bytes());
+        content(out);
+    });
+}
@@ -0,0 +1,137 @@
+//! [Ogg](https://en.wikipedia.org/wiki/Ogg) pages
+
+/// The CRC-32 lookup table of Ogg (polynomial `0x04C11DB7`, not reflected)
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u32) << 24;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                crc << 1 ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// The checksum of a Ogg page
+pub fn crc(data: &[u8]) -> u32 {
+    data.iter().fold(0, |crc, x| {
+        crc << 8 ^ CRC_TABLE[((crc >> 24) as u8 ^ x) as usize]
+    })
+}
+
+/// A writer of a single logical Ogg bitstream
+#[derive(Debug)]
+pub struct Writer {
+    out: Vec<u8>,
+    serial: u32,
+    sequence: u32,
+    segments: Vec<u8>,
+    data: Vec<u8>,
+    /// The granule position of the last packet completed on the current
+    /// page
+    granule: Option<u64>,
+    /// If the current page starts with the continuation of a packet
+    continued: bool,
+}
+
+impl Writer {
+    /// Create a new [`Writer`] for the bitstream with `serial`
+    pub fn new(serial: u32) -> Self {
+        Self {
+            out: Vec::new(),
+            serial,
+            sequence: 0,
+            segments: Vec::new(),
+            data: Vec::new(),
+            granule: None,
+            continued: false,
+        }
+    }
+
+    /// Append a `packet` ending at `granule`. Packets that do not fit into
+    /// the current page are continued on the next one.
+    pub fn packet(&mut self, packet: &[u8], granule: u64) {
+        // A lacing value of 255 for every full chunk, terminated by a
+        // smaller one, which can be 0
+        let lacing = std::iter::repeat_n(255, packet.len() / 255)
+            .chain(std::iter::once((packet.len() % 255) as u8));
+
+        let mut offset = 0;
+        for (i, value) in lacing.enumerate() {
+            if self.segments.len() == 255 {
+                self.page(false, i > 0);
+            }
+
+            let end = offset + usize::from(value);
+            self.segments.push(value);
+            self.data.extend_from_slice(&packet[offset..end]);
+            offset = end;
+        }
+
+        self.granule = Some(granule);
+    }
+
+    /// Write the current page, if it contains any data
+    pub fn flush(&mut self) {
+        if !self.segments.is_empty() {
+            self.page(false, false);
+        }
+    }
+
+    /// Take the pages written so far out of the [`Writer`]
+    pub fn take(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.out)
+    }
+
+    /// Write the remaining data as the last page and return the pages that
+    /// were not taken yet
+    pub fn finish(mut self) -> Vec<u8> {
+        self.page(true, false);
+        self.out
+    }
+
+    /// Write the current page. `continues` is `true` if the last packet of
+    /// the page continues on the next one.
+    fn page(&mut self, last: bool, continues: bool) {
+        let mut header_type = 0;
+        if self.continued {
+            header_type |= 0x01;
+        }
+        if self.sequence == 0 {
+            header_type |= 0x02;
+        }
+        if last {
+            header_type |= 0x04;
+        }
+
+        // A page on which no packet ends has no granule position
+        let granule = self.granule.take().unwrap_or(u64::MAX);
+
+        let start = self.out.len();
+        self.out.extend_from_slice(b"OggS");
+        self.out.push(0);
+        self.out.push(header_type);
+        self.out.extend_from_slice(&granule.to_le_bytes());
+        self.out.extend_from_slice(&self.serial.to_le_bytes());
+        self.out.extend_from_slice(&self.sequence.to_le_bytes());
+        self.out.extend_from_slice(&[0; 4]);
+        self.out.push(self.segments.len() as u8);
+        self.out.append(&mut self.segments);
+        self.out.append(&mut self.data);
+
+        let crc = crc(&self.out[start..]);
+        self.out[start + 22..start + 26].copy_from_slice(&crc.to_le_bytes());
+
+        self.sequence += 1;
+        self.continued = continues;
+    }
+}
@@ -7,9 +7,36 @@ pub enum Error {
     #[error("An Error occurred while requesting web content: {0}")]
     Request(#[from] reqwest::Error),
 
+    /// A Error that can occur when writing downloaded content
+    #[error("An Error occurred while writing downloaded content: {0}")]
+    Io(#[from] std::io::Error),
+
     /// A Error reported by YouTube
     #[error(transparent)]
     Youtube(#[from] Youtube),
+
+    /// A Error that occurred while parsing a [`YoutubeUrl`](crate::YoutubeUrl)
+    #[error(transparent)]
+    Url(#[from] Url),
+
+    /// A Error that occurred while handling a [`Stream`](crate::Stream)
+    #[error(transparent)]
+    Stream(#[from] Stream),
+
+    /// A Error that occurred while handling
+    /// [`Captions`](crate::video::captions::Captions)
+    #[error(transparent)]
+    Captions(#[from] Captions),
+
+    /// A Error that occurred while handling
+    /// [`Comments`](crate::video::comment::Comment)
+    #[error(transparent)]
+    Comments(#[from] Comments),
+
+    /// A Error that occurred while muxing [`Streams`](crate::Stream)
+    #[cfg(feature = "mux")]
+    #[error(transparent)]
+    Mux(#[from] Mux),
 }
 
 /// A Error reported by YouTube.
@@ -92,6 +119,92 @@ pub enum Youtube {
     },
 }
 
+/// A Error that occurred while handling a [`Stream`](crate::Stream).
+#[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Stream {
+    /// The length of a [`Stream`](crate::Stream) could neither be determined
+    /// from the `Content-Length` nor the `Content-Range` header.
+    #[error("the content length of a stream could not be determined")]
+    MissingContentLength,
+
+    /// A [`Stream`](crate::Stream) has no init or index range and can
+    /// therefore not be seeked.
+    #[error("a stream has no segment index")]
+    MissingIndex,
+
+    /// The segment index of a [`Stream`](crate::Stream) could not be parsed.
+    #[error("the segment index of a stream is invalid")]
+    InvalidIndex,
+
+    /// A requested time range of a [`Stream`](crate::Stream) does not
+    /// contain any segment.
+    #[error("the requested range does not contain any segment")]
+    EmptyRange,
+
+    /// The [`Container`](crate::stream::media_type::Container) of a
+    /// [`Stream`](crate::Stream) is not supported for the requested operation.
+    #[error("the container '{0}' is not supported")]
+    UnsupportedContainer(crate::stream::media_type::Container),
+
+    /// A [`Video`](crate::Video) has no HLS manifest to record, because it
+    /// is not live.
+    #[error("a video has no HLS manifest")]
+    MissingManifest,
+
+    /// A HLS playlist of a livestream could not be parsed.
+    #[error("a HLS playlist is invalid")]
+    InvalidPlaylist,
+
+    /// A [`Stream`](crate::Stream) is no longer offered by YouTube after its
+    /// url was refreshed.
+    #[error("a stream is no longer available")]
+    Unavailable,
+}
+
+/// A Error that occurred while handling
+/// [`Captions`](crate::video::captions::Captions).
+#[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Captions {
+    /// Captions could not be parsed in the requested
+    /// [`Format`](crate::video::captions::Format).
+    #[error("the captions are not in the expected format")]
+    InvalidFormat,
+}
+
+/// A Error that occurred while handling
+/// [`Comments`](crate::video::comment::Comment).
+#[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Comments {
+    /// The [`Comments`](crate::video::comment::Comment) could not be sorted
+    /// in the requested [`Sort`](crate::video::comment::Sort) order, because
+    /// YouTube did not offer it.
+    #[error("the comments can not be sorted by {0:?}")]
+    UnsupportedSort(crate::video::comment::Sort),
+}
+
+/// A Error that occurred while muxing [`Streams`](crate::Stream).
+#[cfg(feature = "mux")]
+#[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Mux {
+    /// A input was neither a fragmented MP4 nor a WebM file or was
+    /// malformed.
+    #[error("a input of the muxer is invalid")]
+    InvalidInput,
+
+    /// A input used a codec that is not supported.
+    #[error("the codec '{0}' is not supported")]
+    UnsupportedCodec(String),
+
+    /// The inputs can not be muxed into the requested
+    /// [`Format`](crate::mux::Format).
+    #[error("the inputs can not be muxed into the requested format")]
+    UnsupportedFormat,
+}
+
 /// The Error produced when a invalid Id is found
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum Id<const N: usize> {
@@ -111,3 +224,45 @@ pub enum Id<const N: usize> {
     #[error("Expected a id of length {N} but found a id of length {0}")]
     InvalidLength(usize),
 }
+
+/// The Error produced when a invalid [`YoutubeUrl`](crate::YoutubeUrl) is
+/// parsed
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Url {
+    /// A value could not be parsed as a URL
+    #[error("Found invalid url: '{0}'")]
+    InvalidUrl(String),
+
+    /// A URL does not belong to YouTube
+    #[error("Found a url that is not a YouTube url: '{0}'")]
+    NotYoutube(String),
+
+    /// A YouTube URL does not link to a video, playlist, channel or clip
+    #[error("Found unsupported YouTube url: '{0}'")]
+    Unsupported(String),
+
+    /// A URL contains a invalid id
+    #[error("Found invalid id in url: {0}")]
+    InvalidId(String),
+}
+
+/// The Error produced when a invalid [`Selector`](crate::stream::Selector)
+/// is parsed
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Selector {
+    /// A alternative of the selector was empty
+    #[error("Found a empty selector")]
+    Empty,
+
+    /// A unknown stream was requested. Known streams are:
+    ///
+    /// - `best`, `b`, `worst`, `w`
+    /// - `bestvideo`, `bv`, `worstvideo`, `wv`
+    /// - `bestaudio`, `ba`, `worstaudio`, `wa`
+    #[error("Found unknown stream: '{0}'")]
+    UnknownStream(String),
+
+    /// A filter was malformed or used a unknown field
+    #[error("Found invalid filter: '{0}'")]
+    InvalidFilter(String),
+}
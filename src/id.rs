@@ -1,7 +1,7 @@
 //! A YouTube Id
 
 macro_rules! define_id {
-    ($len:literal, $doc:literal, [$($prefix:literal),*,]) => {
+    ($len:literal, $doc:literal, $url:pat => $id:expr) => {
         #[derive(Clone, Copy, PartialEq, Eq, Hash)]
         #[doc = $doc]
         pub struct Id([u8; $len]);
@@ -66,29 +66,19 @@ macro_rules! define_id {
 
             fn from_str(value: &str) -> Result<Self, Self::Err> {
                 use std::convert::TryInto;
-                use crate::youtube;
 
-                const PREFIXES: &[&str] = &[$($prefix),*];
-
-                let value = youtube::strip_url_prefix(value);
-
-                let value = PREFIXES
-                    .iter()
-                    .find_map(|prefix| value.strip_prefix(prefix))
-                    // No Prefix matched. Possibly naked id. Length and
-                    // correctness will be checked later.
-                    .unwrap_or(value);
+                // Anything but a bare id is parsed as a URL
+                if !value.chars().all(crate::id::validate_char) {
+                    return match crate::link::parse_id_url(value) {
+                        Some($url) => Ok($id),
+                        _ => Err(crate::error::Id::InvalidId(value.to_string())),
+                    };
+                }
 
                 value
-                    .chars()
-                    .all(crate::id::validate_char)
-                    .then(|| value)
-                    .ok_or_else(|| crate::error::Id::InvalidId(value.to_string()))
-                    .and_then(|val| {
-                        val.as_bytes()
-                            .try_into()
-                            .map_err(|_| crate::error::Id::InvalidLength(val.len()))
-                    })
+                    .as_bytes()
+                    .try_into()
+                    .map_err(|_| crate::error::Id::InvalidLength(value.len()))
                     .map(Self)
             }
         }
@@ -11,7 +11,13 @@ pub(crate) mod id;
 
 pub mod channel;
 mod client;
+// Most of the containers are only used by the muxer
+#[cfg_attr(not(feature = "mux"), allow(dead_code))]
+pub(crate) mod container;
 pub mod error;
+pub mod link;
+#[cfg(feature = "mux")]
+pub mod mux;
 pub mod playlist;
 pub mod stream;
 mod thumbnail;
@@ -21,6 +27,7 @@ pub(crate) mod youtube;
 pub use channel::Channel;
 pub use client::Client;
 pub use error::Error;
+pub use link::YoutubeUrl;
 pub use playlist::Playlist;
 pub use stream::Stream;
 pub use thumbnail::Thumbnail;
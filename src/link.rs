@@ -0,0 +1,452 @@
+//! Parsing and building of YouTube URLs.
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use reqwest::Url;
+
+use crate::{error, youtube::resolve_url, Client};
+
+/// The hosts that serve YouTube pages
+const HOSTS: &[&str] = &[
+    "youtube.com",
+    "www.youtube.com",
+    "m.youtube.com",
+    "music.youtube.com",
+    "gaming.youtube.com",
+    "youtube-nocookie.com",
+    "www.youtube-nocookie.com",
+];
+
+/// The host of short video links
+const SHORT_HOST: &str = "youtu.be";
+
+/// The paths after which the [`Id`](crate::video::Id) of a video follows
+const VIDEO_PATHS: &[&str] = &["shorts", "live", "embed", "v", "e"];
+
+/// A parsed YouTube URL, classified by the entity it links to.
+///
+/// Any URL of `youtube.com`, `m.youtube.com`, `music.youtube.com`,
+/// `youtu.be` and `youtube-nocookie.com` is understood, including
+/// `attribution_link`s. A bare `@handle` is parsed as a
+/// [`Channel::Handle`].
+///
+/// [`Display`](fmt::Display) builds the canonical URL back.
+///
+/// # Example
+///
+/// ```rust
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+/// use ytextract::YoutubeUrl;
+///
+/// let url: YoutubeUrl = "https://youtu.be/7B2PIVSWtJA?t=1m30s".parse()?;
+///
+/// match &url {
+///     YoutubeUrl::Video { id, start, .. } => {
+///         assert_eq!(*id, "7B2PIVSWtJA".parse()?);
+///         assert_eq!(*start, Some(Duration::from_secs(90)));
+///     }
+///     _ => unreachable!(),
+/// }
+///
+/// assert_eq!(
+///     url.to_string(),
+///     "https://www.youtube.com/watch?v=7B2PIVSWtJA&t=90s"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum YoutubeUrl {
+    /// A [`Video`](crate::Video), optionally played in a
+    /// [`Playlist`](crate::Playlist)
+    Video {
+        /// The [`Id`](crate::video::Id) of the video
+        id: crate::video::Id,
+        /// The time the video starts playing at
+        start: Option<Duration>,
+        /// The [`Id`](crate::playlist::Id) of the playlist the video is
+        /// played in
+        playlist: Option<crate::playlist::Id>,
+        /// The 1-based position of the video in `playlist`
+        index: Option<u64>,
+    },
+
+    /// A [`Playlist`](crate::Playlist)
+    Playlist(crate::playlist::Id),
+
+    /// A [`Channel`](crate::Channel)
+    Channel(Channel),
+
+    /// A clip of a [`Video`](crate::Video), identified by its id like
+    /// `UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb`
+    Clip(String),
+}
+
+/// The ways a [`Channel`](crate::Channel) can be linked to. Only
+/// [`Channel::Id`] can be requested directly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Channel {
+    /// `/channel/UCdktGrgQlqxPsvHo6cHF0Ng`
+    Id(crate::channel::Id),
+    /// `/@LinusTechTips`, without the `@`
+    Handle(String),
+    /// A custom name like `/c/LinusTechTips`
+    Custom(String),
+    /// A legacy username like `/user/LinusTechTips`
+    User(String),
+}
+
+/// A entity a URL was resolved to by YouTube with
+/// [`Client::resolve`](crate::Client::resolve).
+///
+/// Unlike a [`YoutubeUrl`], every variant can be requested directly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Resolved {
+    /// A [`Video`](crate::Video), optionally played in a
+    /// [`Playlist`](crate::Playlist)
+    Video {
+        /// The [`Id`](crate::video::Id) of the video
+        id: crate::video::Id,
+        /// The time the video starts playing at
+        start: Option<Duration>,
+        /// The [`Id`](crate::playlist::Id) of the playlist the video is
+        /// played in
+        playlist: Option<crate::playlist::Id>,
+        /// The 1-based position of the video in `playlist`
+        index: Option<u64>,
+    },
+
+    /// A [`Playlist`](crate::Playlist)
+    Playlist(crate::playlist::Id),
+
+    /// A [`Channel`](crate::Channel)
+    Channel(crate::channel::Id),
+
+    /// A clip of a [`Video`](crate::Video)
+    Clip {
+        /// The id of the clip like `UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb`
+        id: String,
+        /// The [`Id`](crate::video::Id) of the clipped video
+        video: crate::video::Id,
+        /// The time the clip starts at
+        start: Option<Duration>,
+    },
+}
+
+impl YoutubeUrl {
+    /// Parse `url`, which has to be a [`Url`] of a YouTube host.
+    pub fn from_url(url: &Url) -> Result<Self, error::Url> {
+        let host = url
+            .host_str()
+            .ok_or_else(|| error::Url::NotYoutube(url.to_string()))?;
+        let host = host.to_ascii_lowercase();
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|x| x.filter(|x| !x.is_empty()).collect())
+            .unwrap_or_default();
+
+        let query = |key: &str| {
+            url.query_pairs()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.into_owned())
+        };
+
+        let unsupported = || error::Url::Unsupported(url.to_string());
+
+        if host == SHORT_HOST {
+            let id = segments.first().ok_or_else(unsupported)?;
+            return video(url, id, &query);
+        }
+
+        if !HOSTS.contains(&host.as_str()) {
+            return Err(error::Url::NotYoutube(url.to_string()));
+        }
+
+        match segments.as_slice() {
+            ["watch", ..] => video(url, &query("v").ok_or_else(unsupported)?, &query),
+            [path, id, ..] if VIDEO_PATHS.contains(path) => video(url, id, &query),
+            ["playlist", ..] => {
+                let list = query("list").ok_or_else(unsupported)?;
+                Ok(Self::Playlist(parse_id(&list)?))
+            }
+            ["clip", id, ..] => Ok(Self::Clip(clip(id)?)),
+            ["channel", id, ..] => Ok(Self::Channel(Channel::Id(parse_id(id)?))),
+            ["c", name, ..] => Ok(Self::Channel(Channel::Custom(decode(name)))),
+            ["user", name, ..] => Ok(Self::Channel(Channel::User(decode(name)))),
+            ["browse", id, ..] => match id.strip_prefix("VL") {
+                Some(list) => Ok(Self::Playlist(parse_id(list)?)),
+                None => Ok(Self::Channel(Channel::Id(parse_id(id)?))),
+            },
+            ["attribution_link", ..] => {
+                // The link target is a path like `/watch?v=...`
+                let target = query("u").ok_or_else(unsupported)?;
+                let target = Url::parse("https://www.youtube.com")
+                    .and_then(|x| x.join(&target))
+                    .map_err(|_| unsupported())?;
+                Self::from_url(&target)
+            }
+            [handle, ..] if handle.starts_with('@') => {
+                Ok(Self::Channel(Channel::Handle(decode(&handle[1..]))))
+            }
+            _ => Err(unsupported()),
+        }
+    }
+
+    /// The canonical [`Url`] of the linked entity
+    pub fn to_url(&self) -> Url {
+        let mut url = Url::parse("https://www.youtube.com").expect("Invalid base URL");
+
+        match self {
+            Self::Video {
+                id,
+                start,
+                playlist,
+                index,
+            } => {
+                url.set_path("watch");
+                let mut query = url.query_pairs_mut();
+                query.append_pair("v", id);
+                if let Some(playlist) = playlist {
+                    query.append_pair("list", &playlist.0);
+                }
+                if let Some(index) = index {
+                    query.append_pair("index", &index.to_string());
+                }
+                if let Some(start) = start {
+                    query.append_pair("t", &format!("{}s", start.as_secs()));
+                }
+            }
+            Self::Playlist(id) => {
+                url.set_path("playlist");
+                url.query_pairs_mut().append_pair("list", &id.0);
+            }
+            Self::Channel(channel) => match channel {
+                Channel::Id(id) => url.set_path(&format!("channel/{}", id)),
+                Channel::Handle(handle) => url.set_path(&format!("@{}", handle)),
+                Channel::Custom(name) => url.set_path(&format!("c/{}", name)),
+                Channel::User(name) => url.set_path(&format!("user/{}", name)),
+            },
+            Self::Clip(id) => url.set_path(&format!("clip/{}", id)),
+        }
+
+        url
+    }
+}
+
+impl FromStr for YoutubeUrl {
+    type Err = error::Url;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+
+        if let Some(handle) = value.strip_prefix('@') {
+            return Ok(Self::Channel(Channel::Handle(handle.to_string())));
+        }
+
+        Self::from_url(&parse_url(value)?)
+    }
+}
+
+impl fmt::Display for YoutubeUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_url().fmt(f)
+    }
+}
+
+impl From<crate::video::Id> for YoutubeUrl {
+    fn from(id: crate::video::Id) -> Self {
+        Self::Video {
+            id,
+            start: None,
+            playlist: None,
+            index: None,
+        }
+    }
+}
+
+impl From<crate::playlist::Id> for YoutubeUrl {
+    fn from(id: crate::playlist::Id) -> Self {
+        Self::Playlist(id)
+    }
+}
+
+impl From<crate::channel::Id> for YoutubeUrl {
+    fn from(id: crate::channel::Id) -> Self {
+        Self::Channel(Channel::Id(id))
+    }
+}
+
+pub(crate) async fn resolve(client: Client, value: &str) -> crate::Result<Resolved> {
+    let (url, clip) = match value.parse::<YoutubeUrl>() {
+        // Links that already contain a id do not need to be resolved
+        Ok(YoutubeUrl::Video {
+            id,
+            start,
+            playlist,
+            index,
+        }) => {
+            return Ok(Resolved::Video {
+                id,
+                start,
+                playlist,
+                index,
+            })
+        }
+        Ok(YoutubeUrl::Playlist(id)) => return Ok(Resolved::Playlist(id)),
+        Ok(YoutubeUrl::Channel(Channel::Id(id))) => return Ok(Resolved::Channel(id)),
+        Ok(YoutubeUrl::Clip(id)) => (YoutubeUrl::Clip(id.clone()).to_url(), Some(id)),
+        Ok(url) => (url.to_url(), None),
+        // Legacy vanity names like `/LinusTechTips` are only known to YouTube
+        Err(error::Url::Unsupported(_)) => (parse_url(value.trim())?, None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let unsupported = || error::Url::Unsupported(url.to_string()).into();
+
+    let endpoint = client
+        .api
+        .resolve_url(url.to_string())
+        .await?
+        .endpoint
+        .ok_or_else(unsupported)?;
+
+    if let Some(watch) = endpoint.watch_endpoint {
+        let video = watch.video_id.ok_or_else(unsupported)?;
+        let start = watch.start_time_seconds.map(Duration::from_secs);
+        return Ok(match clip {
+            Some(id) => Resolved::Clip { id, video, start },
+            None => Resolved::Video {
+                id: video,
+                start,
+                playlist: watch.playlist_id.and_then(|x| x.parse().ok()),
+                index: watch.index,
+            },
+        });
+    }
+
+    if let Some(reel) = endpoint.reel_watch_endpoint {
+        return Ok(Resolved::Video {
+            id: reel.video_id.ok_or_else(unsupported)?,
+            start: None,
+            playlist: None,
+            index: None,
+        });
+    }
+
+    let resolve_url::BrowseEndpoint { browse_id } =
+        endpoint.browse_endpoint.ok_or_else(unsupported)?;
+
+    // Other pages like `FEtrending` are browsed as well
+    match browse_id.strip_prefix("VL") {
+        Some(list) => list.parse().map(Resolved::Playlist).ok(),
+        None => browse_id.parse().map(Resolved::Channel).ok(),
+    }
+    .ok_or_else(unsupported)
+}
+
+/// Parse the URL in the [`FromStr`] of an id. Paths like `watch?v=...` are
+/// relative to `youtube.com`.
+pub(crate) fn parse_id_url(value: &str) -> Option<YoutubeUrl> {
+    let value = value.trim();
+    value.parse().ok().or_else(|| {
+        let url = Url::parse("https://www.youtube.com")
+            .ok()?
+            .join(value)
+            .ok()?;
+        YoutubeUrl::from_url(&url).ok()
+    })
+}
+
+/// Parse a URL that may be missing its scheme
+fn parse_url(value: &str) -> Result<Url, error::Url> {
+    // Links are often shared without their scheme
+    let url = if value.contains("://") {
+        Url::parse(value)
+    } else {
+        Url::parse(&format!("https://{}", value))
+    };
+
+    url.map_err(|_| error::Url::InvalidUrl(value.to_string()))
+}
+
+/// A [`YoutubeUrl::Video`] with the id `id` and the context in the query of
+/// `url`
+fn video(
+    url: &Url,
+    id: &str,
+    query: &dyn Fn(&str) -> Option<String>,
+) -> Result<YoutubeUrl, error::Url> {
+    let start = query("t")
+        .or_else(|| query("start"))
+        .or_else(|| url.fragment()?.strip_prefix("t=").map(String::from))
+        .and_then(|x| parse_time(&x));
+
+    Ok(YoutubeUrl::Video {
+        id: parse_id(id)?,
+        start,
+        // Some lists like the watch history can not be requested
+        playlist: query("list").and_then(|x| x.parse().ok()),
+        index: query("index").and_then(|x| x.parse().ok()),
+    })
+}
+
+/// Parse a bare id, keeping the error message of the id
+fn parse_id<T>(id: &str) -> Result<T, error::Url>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    id.parse()
+        .map_err(|err: T::Err| error::Url::InvalidId(err.to_string()))
+}
+
+/// Validate the id of a clip
+fn clip(id: &str) -> Result<String, error::Url> {
+    if !id.is_empty() && id.chars().all(crate::id::validate_char) {
+        Ok(id.to_string())
+    } else {
+        Err(error::Url::InvalidId(id.to_string()))
+    }
+}
+
+/// Percent-decode a path segment like a handle
+fn decode(segment: &str) -> String {
+    // A path segment does not contain `&`, so it is parsed as a single key
+    url::form_urlencoded::parse(segment.as_bytes())
+        .next()
+        .map(|(key, _)| key.into_owned())
+        .unwrap_or_default()
+}
+
+/// Parse a time like `90`, `90s` or `1h2m30s`
+fn parse_time(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.parse::<f64>() {
+        return (seconds >= 0.0).then(|| Duration::from_secs(seconds as u64));
+    }
+
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let factor = match c {
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        // Links are untrusted input
+        seconds = std::mem::take(&mut number)
+            .parse::<u64>()
+            .ok()?
+            .checked_mul(factor)?
+            .checked_add(seconds)?;
+    }
+
+    number.is_empty().then(|| Duration::from_secs(seconds))
+}
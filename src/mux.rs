@@ -0,0 +1,1100 @@
+//! Pure-Rust muxing of adaptive [`Streams`](crate::Stream).
+//!
+//! YouTube serves the audio and video of adaptive streams separately. A
+//! [`Mux`] combines a [`Video`](crate::stream::Video) and a
+//! [`Audio`](crate::stream::Audio) stream into a single Matroska or MP4 file,
+//! without the need for external tools like FFmpeg.
+//!
+//! A single [`Audio`](crate::stream::Audio) stream can also be remuxed into a
+//! `.opus` or `.m4a` file with [`Mux::audio`], including the cover art and
+//! tags of its [`Video`](crate::Video).
+//!
+//! This module is only available with the `mux` feature.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! use ytextract::{
+//!     mux::{Metadata, Mux},
+//!     stream::{Download, Selector},
+//! };
+//!
+//! let client = ytextract::Client::new();
+//!
+//! let video = client.video("nI2e-J6fsuk".parse()?).await?;
+//! let (video_stream, audio_stream) = Selector::new()
+//!     .select(video.streams().await?)
+//!     .unwrap()
+//!     .into_parts();
+//!
+//! let mut file = tokio::fs::File::create("video.mkv").await?;
+//! Mux::new()
+//!     .metadata(Metadata::from(&video))
+//!     .download(&video_stream.unwrap(), &audio_stream.unwrap(), &mut file, Download::new())
+//!     .await?;
+//! # tokio::fs::remove_file("video.mkv").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod demux;
+mod matroska;
+mod mp4;
+mod ogg;
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Cursor, Read, Seek, Write},
+};
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{error, stream::Download, video::chapters::Chapter};
+
+use self::demux::{Input, Kind};
+
+/// The container format written by a [`Mux`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// MP4 if both inputs are fragmented MP4 files, Matroska otherwise.
+    ///
+    /// For a single audio stream Ogg for Opus, M4A for MP4 files and Matroska
+    /// otherwise.
+    #[default]
+    Auto,
+    /// [Matroska](https://www.matroska.org/), which supports all codecs used
+    /// by YouTube
+    Matroska,
+    /// Fragmented [MP4](https://en.wikipedia.org/wiki/MPEG-4_Part_14), which
+    /// is only possible if both inputs are fragmented MP4 files
+    Mp4,
+    /// [Ogg Opus](https://en.wikipedia.org/wiki/Ogg), which is only possible
+    /// for a single Opus audio stream
+    Ogg,
+    /// A [MP4](https://en.wikipedia.org/wiki/MPEG-4_Part_14) audio file,
+    /// which is only possible for a single MP4 audio stream
+    M4a,
+}
+
+impl Format {
+    /// The file extension of a [`Format`], e.g. `mkv` or `mp4`.
+    ///
+    /// [`Format::Auto`] has no extension, use the [`Format`] returned by
+    /// [`Mux::mux`] instead.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::Matroska => Some("mkv"),
+            Self::Mp4 => Some("mp4"),
+            Self::Ogg => Some("opus"),
+            Self::M4a => Some("m4a"),
+        }
+    }
+}
+
+/// The metadata written by a [`Mux`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metadata {
+    title: Option<String>,
+    artist: Option<String>,
+    date: Option<chrono::NaiveDate>,
+    cover: Option<Cover>,
+    chapters: Vec<Chapter>,
+}
+
+impl Metadata {
+    /// Create empty [`Metadata`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The title of the file
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// The artist of the file
+    pub fn artist(mut self, artist: impl Into<String>) -> Self {
+        self.artist = Some(artist.into());
+        self
+    }
+
+    /// The release date of the file
+    pub fn date(mut self, date: chrono::NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    /// The cover art of the file
+    pub fn cover(mut self, cover: Cover) -> Self {
+        self.cover = Some(cover);
+        self
+    }
+
+    /// The [`Chapters`](Chapter) of the file
+    pub fn chapters(mut self, chapters: Vec<Chapter>) -> Self {
+        self.chapters = chapters;
+        self
+    }
+
+    /// The [`Metadata`] of a [`Video`](crate::Video), including its largest
+    /// [`Thumbnail`](crate::Thumbnail) as the cover art.
+    pub async fn fetch(video: &crate::Video) -> crate::Result<Self> {
+        let metadata = Self::from(video);
+
+        let thumbnail = match video.thumbnails().iter().max_by_key(|x| x.width * x.height) {
+            Some(thumbnail) => thumbnail,
+            None => return Ok(metadata),
+        };
+
+        let data = video
+            .client()
+            .api
+            .http
+            .get(thumbnail.url.clone())
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+
+        Ok(metadata.cover(Cover::new(data.to_vec())))
+    }
+}
+
+/// The cover art of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cover {
+    data: Vec<u8>,
+}
+
+impl Cover {
+    /// Create a [`Cover`] from the bytes of a image
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        Self { data: data.into() }
+    }
+
+    /// The bytes of a [`Cover`]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// The [mime type](https://en.wikipedia.org/wiki/Media_type) of a
+    /// [`Cover`], detected from its content. `image/jpeg` if unknown.
+    pub fn mime_type(&self) -> &'static str {
+        match self.data.as_slice() {
+            [0x89, b'P', b'N', b'G', ..] => "image/png",
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+            [b'G', b'I', b'F', b'8', ..] => "image/gif",
+            _ => "image/jpeg",
+        }
+    }
+}
+
+impl From<&crate::Video> for Metadata {
+    fn from(video: &crate::Video) -> Self {
+        let mut metadata = Self::new()
+            .title(video.title())
+            .artist(video.channel().name())
+            .chapters(video.chapters());
+        metadata.date = video.date();
+        metadata
+    }
+}
+
+/// Options for muxing a video and a audio stream into a single file.
+///
+/// For more information see the [module level documentation](self)
+#[derive(Debug, Clone, Default)]
+pub struct Mux {
+    format: Format,
+    metadata: Metadata,
+    language: Option<String>,
+}
+
+impl Mux {
+    /// Create the default [`Mux`] options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The [`Format`] to write
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The [`Metadata`] to write
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// The language of the audio track as a
+    /// [BCP 47](https://en.wikipedia.org/wiki/IETF_language_tag) tag, e.g.
+    /// `en` or `de-DE`.
+    ///
+    /// [`Mux::download`] uses the language of the
+    /// [`AudioTrack`](crate::stream::AudioTrack) if this is not set.
+    pub fn language(mut self, language: impl Into<String>) -> Self {
+        self.language = Some(language.into());
+        self
+    }
+
+    /// Mux the downloaded `video` and `audio` streams into `writer`.
+    ///
+    /// Both have to be either fragmented MP4 or WebM files, as served by
+    /// YouTube. Returns the [`Format`] that was written.
+    pub fn mux<W: Write>(
+        &self,
+        video: &[u8],
+        audio: &[u8],
+        writer: &mut W,
+    ) -> crate::Result<Format> {
+        self.mux_with(
+            Cursor::new(video),
+            Cursor::new(audio),
+            self.language.as_deref(),
+            writer,
+        )
+    }
+
+    /// Download the `video` and `audio` streams and mux them into `writer`.
+    ///
+    /// Both streams and the muxed file are written into temporary files
+    /// instead of memory. Returns the [`Format`] that was written.
+    pub async fn download<W: AsyncWrite + Unpin>(
+        &self,
+        video: &crate::stream::Video,
+        audio: &crate::stream::Audio,
+        writer: &mut W,
+        options: Download,
+    ) -> crate::Result<Format> {
+        let video_file = TempFile::new()?;
+        let audio_file = TempFile::new()?;
+        futures_util::future::try_join(
+            video.download_into_file(video_file.writer()?, options),
+            audio.download_into_file(audio_file.writer()?, options),
+        )
+        .await?;
+
+        let language = self
+            .language
+            .clone()
+            .or_else(|| audio.audio_track().map(|x| x.language().to_string()));
+
+        let output = TempFile::new()?;
+        let mux = self.clone();
+        let mut out = BufWriter::new(output.0.try_clone()?);
+        let format = blocking(move || {
+            let format = mux.mux_with(
+                video_file.open()?,
+                audio_file.open()?,
+                language.as_deref(),
+                &mut out,
+            )?;
+            out.flush()?;
+            Ok(format)
+        })
+        .await?;
+
+        output.copy(writer).await?;
+        Ok(format)
+    }
+
+    /// Remux the downloaded `audio` stream into `writer`, without a video.
+    ///
+    /// Opus is written into a Ogg file and AAC into a M4A file with the
+    /// default [`Format::Auto`]. Returns the [`Format`] that was written.
+    pub fn audio<W: Write>(&self, audio: &[u8], writer: &mut W) -> crate::Result<Format> {
+        self.audio_with(Cursor::new(audio), self.language.as_deref(), writer)
+    }
+
+    /// Download the `audio` stream and remux it into `writer`, without a
+    /// video.
+    ///
+    /// The stream and the remuxed file are written into temporary files
+    /// instead of memory. Returns the [`Format`] that was written.
+    pub async fn download_audio<W: AsyncWrite + Unpin>(
+        &self,
+        audio: &crate::stream::Audio,
+        writer: &mut W,
+        options: Download,
+    ) -> crate::Result<Format> {
+        let audio_file = TempFile::new()?;
+        audio
+            .download_into_file(audio_file.writer()?, options)
+            .await?;
+
+        let language = self
+            .language
+            .clone()
+            .or_else(|| audio.audio_track().map(|x| x.language().to_string()));
+
+        let output = TempFile::new()?;
+        let mux = self.clone();
+        let mut out = BufWriter::new(output.0.try_clone()?);
+        let format = blocking(move || {
+            let format = mux.audio_with(audio_file.open()?, language.as_deref(), &mut out)?;
+            out.flush()?;
+            Ok(format)
+        })
+        .await?;
+
+        output.copy(writer).await?;
+        Ok(format)
+    }
+
+    fn audio_with<R: Read + Seek, W: Write>(
+        &self,
+        audio: R,
+        language: Option<&str>,
+        writer: &mut W,
+    ) -> crate::Result<Format> {
+        let mut audio = Input::new(audio, language)?;
+        if audio.track.kind != Kind::Audio {
+            return Err(error::Mux::InvalidInput.into());
+        }
+
+        let format = match self.format {
+            Format::Auto if audio.track.codec == "A_OPUS" => Format::Ogg,
+            Format::Auto if audio.track.mp4.is_some() => Format::M4a,
+            Format::Auto => Format::Matroska,
+            format => format,
+        };
+
+        match format {
+            Format::Ogg => ogg::write(writer, &mut audio, &self.metadata)?,
+            Format::M4a => mp4::write_audio(writer, &mut audio, &self.metadata)?,
+            Format::Mp4 => mp4::write(writer, &mut [audio], &self.metadata, b"isom")?,
+            Format::Matroska | Format::Auto => {
+                matroska::write(writer, &mut [audio], &self.metadata)?
+            }
+        }
+
+        Ok(format)
+    }
+
+    fn mux_with<R: Read + Seek, W: Write>(
+        &self,
+        video: R,
+        audio: R,
+        language: Option<&str>,
+        writer: &mut W,
+    ) -> crate::Result<Format> {
+        let video = Input::new(video, None)?;
+        let audio = Input::new(audio, language)?;
+        if video.track.kind != Kind::Video || audio.track.kind != Kind::Audio {
+            return Err(error::Mux::InvalidInput.into());
+        }
+
+        let mut inputs = [video, audio];
+        let format = match self.format {
+            Format::Ogg | Format::M4a => return Err(error::Mux::UnsupportedFormat.into()),
+            Format::Auto if mp4::copyable(&inputs) => Format::Mp4,
+            Format::Auto => Format::Matroska,
+            format => format,
+        };
+
+        match format {
+            Format::Mp4 => mp4::write(writer, &mut inputs, &self.metadata, b"isom")?,
+            _ => matroska::write(writer, &mut inputs, &self.metadata)?,
+        }
+
+        Ok(format)
+    }
+}
+
+/// Run the blocking `mux` on a thread reserved for blocking work, as it
+/// reads and writes whole files.
+async fn blocking<T: Send + 'static>(
+    mux: impl FnOnce() -> crate::Result<T> + Send + 'static,
+) -> crate::Result<T> {
+    match tokio::task::spawn_blocking(mux).await {
+        Ok(res) => res,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => Err(std::io::Error::other(err).into()),
+    }
+}
+
+/// A anonymous file in the temporary directory, which is removed once all
+/// handles to it are closed.
+///
+/// It has no name, so no other process can replace it.
+#[derive(Debug)]
+struct TempFile(File);
+
+impl TempFile {
+    fn new() -> std::io::Result<Self> {
+        Ok(Self(tempfile::tempfile()?))
+    }
+
+    /// A handle for downloading into the file
+    fn writer(&self) -> std::io::Result<tokio::fs::File> {
+        Ok(tokio::fs::File::from_std(self.0.try_clone()?))
+    }
+
+    /// Open the file for demuxing from its start
+    fn open(&self) -> std::io::Result<BufReader<File>> {
+        let mut file = self.0.try_clone()?;
+        file.rewind()?;
+        Ok(BufReader::new(file))
+    }
+
+    /// Copy the content of the file into `writer`
+    async fn copy<W: AsyncWrite + Unpin>(&self, writer: &mut W) -> std::io::Result<()> {
+        let mut file = tokio::fs::File::from_std(self.open()?.into_inner());
+        tokio::io::copy(&mut file, writer).await?;
+        writer.flush().await
+    }
+}
+
+/// Convert a BCP 47 language tag into a ISO 639-2 code, as used by Matroska
+/// and MP4. Returns `und` for unknown languages.
+fn iso639_2(language: &str) -> &'static str {
+    let primary = language.split(['-', '_']).next().unwrap_or(language);
+
+    match primary.to_ascii_lowercase().as_str() {
+        "ar" => "ara",
+        "bn" => "ben",
+        "cs" => "ces",
+        "da" => "dan",
+        "de" => "deu",
+        "el" => "ell",
+        "en" => "eng",
+        "es" => "spa",
+        "fa" => "fas",
+        "fi" => "fin",
+        "fr" => "fra",
+        "he" | "iw" => "heb",
+        "hi" => "hin",
+        "hu" => "hun",
+        "id" => "ind",
+        "it" => "ita",
+        "ja" => "jpn",
+        "ko" => "kor",
+        "ml" => "mal",
+        "ms" => "msa",
+        "nl" => "nld",
+        "no" | "nb" => "nor",
+        "pl" => "pol",
+        "pt" => "por",
+        "ro" => "ron",
+        "ru" => "rus",
+        "sv" => "swe",
+        "ta" => "tam",
+        "te" => "tel",
+        "th" => "tha",
+        "tr" => "tur",
+        "uk" => "ukr",
+        "ur" => "urd",
+        "vi" => "vie",
+        "zh" => "zho",
+        _ => "und",
+    }
+}
+
+/// Muxing of the small files in `tests/fixtures/mux`. Each has a single
+/// track with 4 frames, 500ms apart, whose data is filled with a byte
+/// identifying the track and the frame:
+///
+/// - `video.mp4`: AVC in 2 fragments, with keyframes at 0ms and 1000ms
+/// - `audio.m4a`: AAC in 2 fragments
+/// - `video.webm`: VP9 in a single cluster, with keyframes at 0ms and 1000ms
+/// - `audio.webm`: Opus in a single cluster
+#[cfg(test)]
+mod tests {
+    use std::{convert::TryInto, time::Duration};
+
+    use super::{Cover, Format, Metadata, Mux};
+    use crate::{
+        container::{
+            ebml,
+            mp4::{self, Reader},
+            ogg,
+        },
+        video::chapters::Chapter,
+    };
+
+    fn fixture(name: &str) -> Vec<u8> {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/mux")
+            .join(name);
+        std::fs::read(path).expect("Missing fixture")
+    }
+
+    fn mux(format: Format, video: &str, audio: &str) -> (Format, Vec<u8>) {
+        let mut out = Vec::new();
+        let written = Mux::new()
+            .format(format)
+            .metadata(Metadata::new().title("Fixture").artist("ytextract"))
+            .mux(&fixture(video), &fixture(audio), &mut out)
+            .expect("Muxing failed");
+        (written, out)
+    }
+
+    /// The payloads of the top level boxes of `kind`
+    fn mp4_boxes<'a>(data: &'a [u8], kind: &'a [u8; 4]) -> impl Iterator<Item = &'a [u8]> {
+        mp4::boxes(data)
+            .filter(move |x| &x.kind == kind)
+            .map(|x| x.data)
+    }
+
+    #[test]
+    fn mp4() {
+        let (format, out) = mux(Format::Auto, "video.mp4", "audio.m4a");
+        assert_eq!(format, Format::Mp4);
+
+        let kinds: Vec<[u8; 4]> = mp4::boxes(&out).map(|x| x.kind).collect();
+        assert_eq!(&kinds[..2], &[*b"ftyp", *b"moov"]);
+        assert_eq!(kinds[2..], [*b"moof", *b"mdat"].repeat(4)[..]);
+
+        let moov = mp4::find(&out, b"moov").unwrap();
+
+        // The duration in ms and the next track id
+        let mvhd = moov.child(b"mvhd").unwrap().data;
+        let mut reader = Reader::new(&mvhd[12..]);
+        assert_eq!(reader.u32(), Some(1000));
+        assert_eq!(reader.u32(), Some(1500));
+        assert_eq!(Reader::new(&mvhd[96..]).u32(), Some(3));
+
+        let tracks: Vec<(u32, &[u8])> = moov
+            .children()
+            .filter(|x| &x.kind == b"trak")
+            .map(|trak| {
+                let id = Reader::new(&trak.child(b"tkhd").unwrap().data[12..]).u32();
+                let hdlr = trak.child(b"mdia").unwrap().child(b"hdlr").unwrap();
+                (id.unwrap(), &hdlr.data[8..12])
+            })
+            .collect();
+        assert_eq!(tracks, [(1, &b"vide"[..]), (2, &b"soun"[..])]);
+
+        let trex: Vec<_> = moov
+            .child(b"mvex")
+            .unwrap()
+            .children()
+            .map(|x| Reader::new(&x.data[4..]).u32().unwrap())
+            .collect();
+        assert_eq!(trex, [1, 2]);
+
+        // The `ilst` follows the `hdlr` of the `meta` full box
+        let meta = moov.child(b"udta").unwrap().child(b"meta").unwrap();
+        let title = mp4::find(&meta.data[4..], b"ilst")
+            .unwrap()
+            .child(b"\xA9nam")
+            .unwrap()
+            .child(b"data")
+            .unwrap();
+        assert_eq!(&title.data[8..], b"Fixture");
+
+        // The fragments are interleaved by time and numbered in order
+        let fragments: Vec<(u32, u32)> = mp4_boxes(&out, b"moof")
+            .map(|moof| {
+                let sequence = mp4::find(moof, b"mfhd").unwrap().data;
+                let tfhd = mp4::find(moof, b"traf").unwrap().child(b"tfhd").unwrap();
+                (
+                    Reader::new(&sequence[4..]).u32().unwrap(),
+                    Reader::new(&tfhd.data[4..]).u32().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(fragments, [(1, 1), (2, 2), (3, 1), (4, 2)]);
+
+        // The data of the frames is copied unchanged
+        let video = fixture("video.mp4");
+        let audio = fixture("audio.m4a");
+        let expected: Vec<&[u8]> = mp4_boxes(&video, b"mdat")
+            .zip(mp4_boxes(&audio, b"mdat"))
+            .flat_map(|(video, audio)| [video, audio])
+            .collect();
+        assert_eq!(mp4_boxes(&out, b"mdat").collect::<Vec<_>>(), expected);
+    }
+
+    /// The `Segment` of a Matroska file and its top level elements
+    fn segment(data: &[u8]) -> (&[u8], Vec<ebml::Element<'_>>) {
+        let header = ebml::find(data, ebml::EBML).unwrap();
+        let doc_type = header.child(ebml::DOC_TYPE).unwrap();
+        assert_eq!(doc_type.data, b"matroska");
+
+        let segment = ebml::find(data, ebml::SEGMENT).unwrap();
+        assert_eq!(
+            segment.offset + segment.header_len + segment.data.len(),
+            data.len()
+        );
+        (segment.data, ebml::elements(segment.data).collect())
+    }
+
+    /// The `CodecID` and `CodecPrivate` of all tracks
+    fn tracks<'a>(elements: &[ebml::Element<'a>]) -> Vec<(&'a [u8], Option<&'a [u8]>)> {
+        let tracks = elements.iter().find(|x| x.id == ebml::TRACKS).unwrap();
+        tracks
+            .children()
+            .enumerate()
+            .map(|(i, entry)| {
+                let number = entry.child(ebml::TRACK_NUMBER).unwrap().uint();
+                assert_eq!(number, Some(i as u64 + 1));
+                (
+                    entry.child(ebml::CODEC_ID).unwrap().data,
+                    entry.child(ebml::CODEC_PRIVATE).map(|x| x.data),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matroska() {
+        let (format, out) = mux(Format::Auto, "video.webm", "audio.webm");
+        assert_eq!(format, Format::Matroska);
+
+        let (segment, elements) = segment(&out);
+
+        let info = elements.iter().find(|x| x.id == ebml::INFO).unwrap();
+        let duration = info.child(ebml::DURATION).unwrap().float();
+        assert_eq!(duration, Some(1500.0));
+        assert_eq!(info.child(ebml::TITLE).unwrap().data, b"Fixture");
+
+        let tracks = tracks(&elements);
+        assert_eq!(tracks[0], (&b"V_VP9"[..], None));
+        assert_eq!(tracks[1].0, b"A_OPUS");
+        assert!(tracks[1].1.unwrap().starts_with(b"OpusHead"));
+
+        // Every video keyframe starts a new cluster
+        let clusters: Vec<_> = elements.iter().filter(|x| x.id == ebml::CLUSTER).collect();
+        assert_eq!(clusters.len(), 2);
+
+        let mut blocks = Vec::new();
+        for cluster in &clusters {
+            let time = cluster.child(ebml::TIMECODE).unwrap().uint().unwrap() as i64;
+            for block in cluster.children().filter(|x| x.id == ebml::SIMPLE_BLOCK) {
+                let relative = i64::from(Reader::new(&block.data[1..]).u16().unwrap() as i16);
+                let keyframe = block.data[3] & 0x80 != 0;
+                blocks.push((block.data[0] & 0x7F, time + relative, keyframe));
+            }
+        }
+        assert_eq!(
+            blocks,
+            [
+                (1, 0, true),
+                (2, 0, true),
+                (1, 500, false),
+                (2, 500, true),
+                (1, 1000, true),
+                (2, 1000, true),
+                (1, 1500, false),
+                (2, 1500, true),
+            ]
+        );
+
+        // The cues point to the clusters
+        let cues = elements.iter().find(|x| x.id == ebml::CUES).unwrap();
+        let points: Vec<(u64, usize)> = cues
+            .children()
+            .map(|point| {
+                let positions = point.child(ebml::CUE_TRACK_POSITIONS).unwrap();
+                (
+                    point.child(ebml::CUE_TIME).unwrap().uint().unwrap(),
+                    positions
+                        .child(ebml::CUE_CLUSTER_POSITION)
+                        .unwrap()
+                        .uint()
+                        .unwrap() as usize,
+                )
+            })
+            .collect();
+        let positions: Vec<_> = clusters.iter().map(|x| x.offset).collect();
+        assert_eq!(points, [(0, positions[0]), (1000, positions[1])]);
+        assert_eq!(
+            ebml::read_id(&segment[positions[1]..]).map(|x| x.0),
+            Some(ebml::CLUSTER)
+        );
+
+        let tags = elements.iter().find(|x| x.id == ebml::TAGS).unwrap();
+        let tags: Vec<(&[u8], &[u8])> = tags
+            .child(ebml::TAG)
+            .unwrap()
+            .children()
+            .filter(|x| x.id == ebml::SIMPLE_TAG)
+            .map(|x| {
+                (
+                    x.child(ebml::TAG_NAME).unwrap().data,
+                    x.child(ebml::TAG_STRING).unwrap().data,
+                )
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                (&b"TITLE"[..], &b"Fixture"[..]),
+                (&b"ARTIST"[..], &b"ytextract"[..])
+            ]
+        );
+    }
+
+    #[test]
+    fn mp4_into_matroska() {
+        let (format, out) = mux(Format::Matroska, "video.mp4", "audio.m4a");
+        assert_eq!(format, Format::Matroska);
+
+        let (_, elements) = segment(&out);
+        let tracks = tracks(&elements);
+
+        let video = fixture("video.mp4");
+        let avcc = [b"moov", b"trak", b"mdia", b"minf", b"stbl", b"stsd"]
+            .iter()
+            .try_fold(&video[..], |data, kind| {
+                mp4::find(data, kind).map(|x| x.data)
+            })
+            .and_then(|stsd| mp4::find(&stsd[8..], b"avc1"))
+            .and_then(|avc1| mp4::find(&avc1.data[78..], b"avcC"))
+            .unwrap();
+        assert_eq!(tracks[0], (&b"V_MPEG4/ISO/AVC"[..], Some(avcc.data)));
+        assert_eq!(tracks[1], (&b"A_AAC"[..], Some(&[0x11, 0x90][..])));
+
+        let blocks = elements
+            .iter()
+            .filter(|x| x.id == ebml::CLUSTER)
+            .flat_map(|x| x.children())
+            .filter(|x| x.id == ebml::SIMPLE_BLOCK)
+            .count();
+        assert_eq!(blocks, 8);
+    }
+
+    #[test]
+    fn mixed() {
+        // Only fragmented MP4 files can be copied into a MP4 file
+        let (format, out) = mux(Format::Auto, "video.webm", "audio.m4a");
+        assert_eq!(format, Format::Matroska);
+        assert_eq!(tracks(&segment(&out).1)[1].0, b"A_AAC");
+
+        let result = Mux::new().format(Format::Mp4).mux(
+            &fixture("video.webm"),
+            &fixture("audio.m4a"),
+            &mut Vec::new(),
+        );
+        assert!(result.is_err());
+
+        // The inputs are swapped
+        let result = Mux::new().mux(
+            &fixture("audio.webm"),
+            &fixture("video.webm"),
+            &mut Vec::new(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        // A fragment whose decode time overflows once its frames are added
+        let mut video = fixture("video.mp4");
+        let tfdt = video
+            .windows(4)
+            .position(|x| x == b"tfdt")
+            .expect("No tfdt");
+        video[tfdt + 8..tfdt + 16].copy_from_slice(&(u64::MAX - 1).to_be_bytes());
+
+        let result = Mux::new().mux(&video, &fixture("audio.m4a"), &mut Vec::new());
+        assert!(matches!(
+            result,
+            Err(crate::Error::Mux(crate::error::Mux::InvalidInput))
+        ));
+    }
+
+    /// A PNG cover, large enough that the `OpusTags` span two Ogg pages
+    fn cover() -> Cover {
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        data.resize(70_000, 0xCC);
+        Cover::new(data)
+    }
+
+    fn audio(audio: &[u8]) -> (Format, Vec<u8>) {
+        let metadata = Metadata::new()
+            .title("Fixture")
+            .artist("ytextract")
+            .date(chrono::NaiveDate::from_ymd_opt(2021, 3, 4).unwrap())
+            .chapters(vec![Chapter::new(
+                "Second",
+                Duration::from_millis(1500),
+                Duration::from_secs(2),
+            )])
+            .cover(cover());
+
+        let mut out = Vec::new();
+        let format = Mux::new()
+            .metadata(metadata)
+            .language("en")
+            .audio(audio, &mut out)
+            .expect("Muxing failed");
+        (format, out)
+    }
+
+    /// A Ogg page: its header type, granule position, sequence number and
+    /// segment table and data
+    struct Page<'a> {
+        header_type: u8,
+        granule: u64,
+        sequence: u32,
+        segments: &'a [u8],
+        data: &'a [u8],
+    }
+
+    /// Split `data` into Ogg pages, checking their structure and checksum
+    fn ogg_pages(mut data: &[u8]) -> Vec<Page<'_>> {
+        let mut pages = Vec::new();
+        let mut serial = None;
+        while !data.is_empty() {
+            assert_eq!(&data[..4], b"OggS");
+            assert_eq!(data[4], 0);
+
+            let le = |range: std::ops::Range<usize>| {
+                data[range]
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, x| acc << 8 | u64::from(*x))
+            };
+            let page_serial = le(14..18);
+            assert_eq!(*serial.get_or_insert(page_serial), page_serial);
+
+            let count = usize::from(data[26]);
+            let segments = &data[27..27 + count];
+            let len = 27 + count + segments.iter().map(|x| usize::from(*x)).sum::<usize>();
+
+            let mut page = data[..len].to_vec();
+            page[22..26].copy_from_slice(&[0; 4]);
+            assert_eq!(u64::from(ogg::crc(&page)), le(22..26), "Wrong CRC");
+
+            pages.push(Page {
+                header_type: data[5],
+                granule: le(6..14),
+                sequence: le(18..22) as u32,
+                segments,
+                data: &data[27 + count..len],
+            });
+            data = &data[len..];
+        }
+        pages
+    }
+
+    /// The packets of Ogg `pages`
+    fn ogg_packets(pages: &[Page<'_>]) -> Vec<Vec<u8>> {
+        let mut packets = Vec::new();
+        let mut packet = Vec::new();
+        for page in pages {
+            let mut offset = 0;
+            for segment in page.segments {
+                let end = offset + usize::from(*segment);
+                packet.extend_from_slice(&page.data[offset..end]);
+                offset = end;
+                if *segment < 255 {
+                    packets.push(std::mem::take(&mut packet));
+                }
+            }
+        }
+        assert!(packet.is_empty(), "Unfinished packet");
+        packets
+    }
+
+    #[test]
+    fn ogg() {
+        let (format, out) = audio(&fixture("audio.webm"));
+        assert_eq!(format, Format::Ogg);
+
+        let pages = ogg_pages(&out);
+        let sequences: Vec<u32> = pages.iter().map(|x| x.sequence).collect();
+        assert_eq!(sequences, [0, 1, 2, 3]);
+
+        // The `OpusTags` continue on the third page, which is the only page
+        // without a finished packet. The TOC bytes of the frames are `0x10`
+        // to `0x13`, which are 1, 2, 2 and 19 frames of 40ms.
+        let headers: Vec<(u8, u64)> = pages.iter().map(|x| (x.header_type, x.granule)).collect();
+        assert_eq!(
+            headers,
+            [(0x02, 0), (0x00, u64::MAX), (0x01, 0), (0x04, 46_080)]
+        );
+
+        let packets = ogg_packets(&pages);
+        assert_eq!(packets.len(), 6);
+        assert!(packets[0].starts_with(b"OpusHead"));
+        assert_eq!(
+            packets[2..],
+            [vec![0x10; 8], vec![0x11; 9], vec![0x12; 10], vec![0x13; 11]]
+        );
+
+        // The vendor string and the comments are prefixed with their length
+        let tags = &packets[1];
+        assert_eq!(&tags[..8], b"OpusTags");
+        fn take<'a>(reader: &mut &'a [u8], len: usize) -> &'a [u8] {
+            let (value, rest) = reader.split_at(len);
+            *reader = rest;
+            value
+        }
+        fn u32_le(reader: &mut &[u8]) -> usize {
+            u32::from_le_bytes(take(reader, 4).try_into().unwrap()) as usize
+        }
+        fn string<'a>(reader: &mut &'a [u8]) -> &'a [u8] {
+            let len = u32_le(reader);
+            take(reader, len)
+        }
+
+        let mut reader = &tags[8..];
+        assert!(string(&mut reader).starts_with(b"ytextract "));
+        let comments: Vec<String> = (0..u32_le(&mut reader))
+            .map(|_| String::from_utf8(string(&mut reader).to_vec()).unwrap())
+            .collect();
+        assert!(reader.is_empty());
+
+        let (picture, comments) = comments.split_last().unwrap();
+        assert_eq!(
+            comments,
+            [
+                "TITLE=Fixture",
+                "ARTIST=ytextract",
+                "DATE=2021-03-04",
+                "LANGUAGE=en",
+                "CHAPTER001=00:00:01.500",
+                "CHAPTER001NAME=Second",
+            ]
+        );
+
+        // A FLAC picture block of the front cover
+        let picture =
+            base64::decode(picture.strip_prefix("METADATA_BLOCK_PICTURE=").unwrap()).unwrap();
+        let cover = cover();
+        let mut expected = 3u32.to_be_bytes().to_vec();
+        expected.extend_from_slice(&9u32.to_be_bytes());
+        expected.extend_from_slice(b"image/png");
+        expected.extend_from_slice(&[0; 20]);
+        expected.extend_from_slice(&(cover.data().len() as u32).to_be_bytes());
+        expected.extend_from_slice(cover.data());
+        assert_eq!(picture, expected);
+    }
+
+    #[test]
+    fn opus_samples() {
+        use super::ogg::samples;
+
+        // SILK 10ms, Hybrid 20ms and CELT 2.5ms and 20ms with a single frame
+        assert_eq!(samples(&[0x00]), Some(480));
+        assert_eq!(samples(&[15 << 3]), Some(960));
+        assert_eq!(samples(&[16 << 3]), Some(120));
+        assert_eq!(samples(&[31 << 3]), Some(960));
+        // Two frames, and a arbitrary amount of frames of SILK 60ms
+        assert_eq!(samples(&[3 << 3 | 1]), Some(5760));
+        assert_eq!(samples(&[3 << 3 | 3, 0x80 | 4]), Some(11_520));
+        assert_eq!(samples(&[3 << 3 | 3]), None);
+        assert_eq!(samples(&[]), None);
+    }
+
+    /// The child of `data` at the path of box `kinds`
+    fn mp4_path<'a>(data: &'a [u8], kinds: &[&[u8; 4]]) -> &'a [u8] {
+        kinds
+            .iter()
+            .try_fold(data, |data, kind| mp4::find(data, kind).map(|x| x.data))
+            .expect("Missing box")
+    }
+
+    /// The version and duration of a `mvhd`, `tkhd` or `mdhd`, whose
+    /// duration follows `between` bytes after the times
+    fn mp4_duration(data: &[u8], between: usize) -> (u8, u64) {
+        match data[0] {
+            0 => (
+                0,
+                u64::from(Reader::new(&data[12 + between..]).u32().unwrap()),
+            ),
+            _ => (1, Reader::new(&data[20 + between..]).u64().unwrap()),
+        }
+    }
+
+    #[test]
+    fn m4a() {
+        let source = fixture("audio.m4a");
+        let (format, out) = audio(&source);
+        assert_eq!(format, Format::M4a);
+
+        let kinds: Vec<[u8; 4]> = mp4::boxes(&out).map(|x| x.kind).collect();
+        assert_eq!(kinds, [*b"ftyp", *b"moov", *b"mdat"]);
+        assert_eq!(&mp4::find(&out, b"ftyp").unwrap().data[..4], b"M4A ");
+
+        let moov = mp4::find(&out, b"moov").unwrap().data;
+        assert_eq!(mp4_duration(mp4_path(moov, &[b"mvhd"]), 4), (0, 1500));
+        assert_eq!(
+            mp4_duration(mp4_path(moov, &[b"trak", b"tkhd"]), 8),
+            (0, 2000)
+        );
+        let mdhd = mp4_path(moov, &[b"trak", b"mdia", b"mdhd"]);
+        assert_eq!(mp4_duration(mdhd, 4), (0, 96_000));
+        // `eng` packed into 5 bits per character
+        assert_eq!(Reader::new(&mdhd[20..]).u16(), Some(0x15C7));
+
+        let stbl = mp4_path(moov, &[b"trak", b"mdia", b"minf", b"stbl"]);
+        let table = |kind| {
+            let data = mp4::find(stbl, kind).unwrap().data;
+            let mut reader = Reader::new(&data[4..]);
+            std::iter::from_fn(move || reader.u32()).collect::<Vec<u32>>()
+        };
+        // A single run of 4 samples of 500ms
+        assert_eq!(table(b"stts"), [1, 4, 24_000]);
+        // A single chunk of 4 samples
+        assert_eq!(table(b"stsc"), [1, 1, 4, 1]);
+        assert_eq!(table(b"stsz"), [0, 4, 8, 9, 10, 11]);
+
+        // The chunk offset points to the copied frames in the `mdat`
+        let stco = table(b"stco");
+        assert_eq!(stco[0], 1);
+        let mdat = mp4::boxes(&out).find(|x| &x.kind == b"mdat").unwrap();
+        assert_eq!(stco[1] as usize, mdat.offset + mdat.header_len());
+        let frames: Vec<u8> = mp4::boxes(&source)
+            .filter(|x| &x.kind == b"mdat")
+            .flat_map(|x| x.data.iter().copied())
+            .collect();
+        assert_eq!(mdat.data, &frames[..]);
+
+        // The `ilst` follows the `hdlr` of the `meta` full box
+        let meta = mp4_path(moov, &[b"udta", b"meta"]);
+        let ilst = mp4::find(&meta[4..], b"ilst").unwrap();
+        let item = |kind| {
+            let data = ilst.child(kind).unwrap().child(b"data").unwrap().data;
+            (Reader::new(data).u32().unwrap(), &data[8..])
+        };
+        assert_eq!(item(b"\xA9nam"), (1, &b"Fixture"[..]));
+        assert_eq!(item(b"\xA9ART"), (1, &b"ytextract"[..]));
+        assert_eq!(item(b"\xA9day"), (1, &b"2021-03-04"[..]));
+        assert_eq!(item(b"covr"), (14, cover().data()));
+
+        // Nero chapters in units of 100ns
+        let chpl = mp4_path(moov, &[b"udta", b"chpl"]);
+        assert_eq!(chpl[8], 1);
+        assert_eq!(Reader::new(&chpl[9..]).u64(), Some(15_000_000));
+        assert_eq!(&chpl[17..], b"\x06Second");
+    }
+
+    #[test]
+    fn m4a_long() {
+        // Samples of 2^30 seconds, whose durations do not fit in 32 bits
+        let mut source = fixture("audio.m4a");
+        source[268..272].copy_from_slice(&1u32.to_be_bytes());
+        for offset in [672, 680, 805, 813] {
+            source[offset..offset + 4].copy_from_slice(&(1u32 << 30).to_be_bytes());
+        }
+        source[773..781].copy_from_slice(&(1u64 << 31).to_be_bytes());
+
+        let (_, out) = audio(&source);
+        let moov = mp4::find(&out, b"moov").unwrap().data;
+        assert_eq!(
+            mp4_duration(mp4_path(moov, &[b"mvhd"]), 4),
+            (1, (3 << 30) * 1000)
+        );
+
+        // The boxes are converted to version 1, keeping the other fields
+        let tkhd = mp4_path(moov, &[b"trak", b"tkhd"]);
+        assert_eq!(mp4_duration(tkhd, 8), (1, (1 << 32) * 1000));
+        assert_eq!(Reader::new(&tkhd[20..]).u32(), Some(1));
+        assert_eq!(tkhd.len(), 96);
+
+        let mdhd = mp4_path(moov, &[b"trak", b"mdia", b"mdhd"]);
+        assert_eq!(mp4_duration(mdhd, 4), (1, 1 << 32));
+        assert_eq!(Reader::new(&mdhd[20..]).u32(), Some(1));
+        assert_eq!(Reader::new(&mdhd[32..]).u16(), Some(0x15C7));
+    }
+}
@@ -0,0 +1,648 @@
+//! Reading of the fragmented MP4 and WebM files served by YouTube
+//!
+//! Only the headers and indices of a input are held in memory. The data of
+//! the frames is read from the input when it is written.
+
+use std::{
+    convert::TryFrom,
+    io::{self, Read, Seek, SeekFrom, Write},
+};
+
+use crate::container::{
+    ebml,
+    mp4::{self, Reader},
+};
+
+/// The kind of a [`Track`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    Video,
+    Audio,
+}
+
+/// A demuxed [`Track`] and the input its frames are read from
+#[derive(Debug)]
+pub(super) struct Input<'a, R> {
+    pub track: Track,
+    /// The BCP 47 language of the track
+    pub language: Option<&'a str>,
+    reader: R,
+}
+
+impl<'a, R: Read + Seek> Input<'a, R> {
+    /// Demux the single track of a adaptive stream
+    pub fn new(mut reader: R, language: Option<&'a str>) -> crate::Result<Self> {
+        let track = demux(&mut reader)?;
+        Ok(Self {
+            track,
+            language,
+            reader,
+        })
+    }
+
+    /// Read `len` bytes at `offset` of the input, e.g. the data of a
+    /// [`Frame`]
+    pub fn read(&mut self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+        read_at(&mut self.reader, offset, len)
+    }
+
+    /// Copy `len` bytes at `offset` of the input into `out`
+    pub fn copy<W: Write>(&mut self, offset: u64, len: u64, out: &mut W) -> io::Result<()> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        if io::copy(&mut (&mut self.reader).take(len), out)? < len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(())
+    }
+}
+
+/// The single track of a adaptive stream
+#[derive(Debug)]
+pub(super) struct Track {
+    pub kind: Kind,
+    /// The Matroska `CodecID`, e.g. `V_VP9` or `A_OPUS`
+    pub codec: String,
+    /// The Matroska `CodecPrivate`, e.g. the `OpusHead` or the
+    /// `AudioSpecificConfig` of AAC
+    pub codec_private: Option<Vec<u8>>,
+    /// The Matroska `TrackEntry` children describing the codec in detail,
+    /// like the `Video` and `Audio` elements
+    pub settings: Vec<u8>,
+    pub frames: Vec<Frame>,
+    /// The structure of the input, if it was a MP4 file
+    pub mp4: Option<Mp4>,
+}
+
+impl Track {
+    /// The latest presentation time of a [`Frame`] in nanoseconds, which is
+    /// the duration without the last [`Frame`]
+    pub fn duration(&self) -> i64 {
+        self.frames.iter().map(|x| x.time).max().unwrap_or(0)
+    }
+}
+
+/// A single frame of a [`Track`]
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Frame {
+    /// The presentation time in nanoseconds
+    pub time: i64,
+    pub keyframe: bool,
+    /// The lacing flags of a Matroska block
+    pub lacing: u8,
+    /// The position of the data in the input
+    pub offset: u64,
+    pub len: usize,
+}
+
+/// The boxes and samples of a fragmented MP4 file
+#[derive(Debug)]
+pub(super) struct Mp4 {
+    /// The payload of the `trak` box
+    pub trak: Vec<u8>,
+    /// The payload of the `trex` box
+    pub trex: Option<Vec<u8>>,
+    pub timescale: u32,
+    /// The ISO 639-2/T language code of the track
+    pub language: [u8; 3],
+    pub fragments: Vec<Fragment>,
+    /// If the `fragments` can be copied into a new file
+    pub copyable: bool,
+    /// The duration of every frame in units of the `timescale`
+    pub durations: Vec<u32>,
+}
+
+/// A `moof` and its `mdat` box
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Fragment {
+    /// The decode time of the first sample in units of the timescale
+    pub time: u64,
+    /// The position of the `moof` box in the input
+    pub offset: u64,
+    /// The size of the `moof` box including its header
+    pub moof_size: usize,
+    /// The size of the `moof` and `mdat` box including their headers
+    pub size: u64,
+}
+
+/// The header of a top level box
+#[derive(Debug, Clone, Copy)]
+struct Header {
+    kind: [u8; 4],
+    offset: u64,
+    len: u64,
+    /// The size of the box including its header
+    size: u64,
+}
+
+/// Read `len` bytes at `offset` of `reader`
+fn read_at<R: Read + Seek>(reader: &mut R, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    let mut data = vec![0; len];
+    reader.seek(SeekFrom::Start(offset))?;
+    reader.read_exact(&mut data)?;
+    Ok(data)
+}
+
+/// A input being demuxed. Reads outside of the input are malformed input,
+/// while other IO errors are kept to be reported instead.
+struct Source<'r, R> {
+    reader: &'r mut R,
+    len: u64,
+    error: Option<io::Error>,
+}
+
+impl<R: Read + Seek> Source<'_, R> {
+    /// Read `len` bytes at `offset`. Returns [`None`] if they are not part
+    /// of the input.
+    fn read(&mut self, offset: u64, len: u64) -> Option<Vec<u8>> {
+        if offset.checked_add(len)? > self.len {
+            return None;
+        }
+        match read_at(self.reader, offset, usize::try_from(len).ok()?) {
+            Ok(data) => Some(data),
+            Err(error) => {
+                self.error = Some(error);
+                None
+            }
+        }
+    }
+
+    /// Read up to `len` bytes at `offset`, which are less at the end of the
+    /// input
+    fn read_up_to(&mut self, offset: u64, len: u64) -> Option<Vec<u8>> {
+        self.read(offset, len.min(self.len.checked_sub(offset)?))
+    }
+
+    /// The headers of the top level boxes
+    fn boxes(&mut self) -> Option<Vec<Header>> {
+        let mut boxes = Vec::new();
+        let mut offset = 0;
+        while offset < self.len {
+            let header = self.read_up_to(offset, 16)?;
+            let mut reader = Reader::new(&header);
+
+            let size = reader.u32()?;
+            let kind = <[u8; 4]>::try_from(reader.bytes(4)?).ok()?;
+            let size = match size {
+                // The box extends to the end of the input
+                0 => self.len - offset,
+                1 => reader.u64()?,
+                size => u64::from(size),
+            };
+            let len = reader.position() as u64;
+
+            // A truncated box ends the input
+            if size < len || offset.checked_add(size)? > self.len {
+                break;
+            }
+            boxes.push(Header {
+                kind,
+                offset,
+                len,
+                size,
+            });
+            offset += size;
+        }
+        Some(boxes)
+    }
+
+    /// The id, header length and payload length of the element at `offset`.
+    /// A element with a unknown size extends to `end`.
+    fn element(&mut self, offset: u64, end: u64) -> Option<(u32, u64, u64)> {
+        let header = self.read_up_to(offset, 12)?;
+        let (id, size, header_len) = ebml::read_header(&header)?;
+        let header_len = header_len as u64;
+        let len = match size {
+            Some(size) => size,
+            None => end.checked_sub(offset + header_len)?,
+        };
+        Some((id, header_len, len))
+    }
+}
+
+/// Read the single track of a adaptive stream
+fn demux<R: Read + Seek>(reader: &mut R) -> crate::Result<Track> {
+    let len = reader.seek(SeekFrom::End(0))?;
+    let mut source = Source {
+        reader,
+        len,
+        error: None,
+    };
+
+    let track = match source.read_up_to(0, 8) {
+        Some(head) if head.get(4..8) == Some(b"ftyp") => self::mp4(&mut source),
+        Some(head) if ebml::read_id(&head).map(|x| x.0) == Some(ebml::EBML) => webm(&mut source),
+        _ => None,
+    };
+
+    if let Some(error) = source.error {
+        return Err(error.into());
+    }
+    track.unwrap_or_else(|| Err(crate::error::Mux::InvalidInput.into()))
+}
+
+/// Read a fragmented MP4 file
+fn mp4<R: Read + Seek>(source: &mut Source<'_, R>) -> Option<crate::Result<Track>> {
+    let top = source.boxes()?;
+
+    let header = top.iter().find(|x| &x.kind == b"moov")?;
+    let moov = source.read(header.offset + header.len, header.size - header.len)?;
+    let trak = mp4::find(&moov, b"trak")?;
+
+    let mut tkhd = Reader::new(trak.child(b"tkhd")?.data);
+    let version = tkhd.u8()?;
+    tkhd.bytes(if version == 1 { 3 + 16 } else { 3 + 8 })?;
+    let track_id = tkhd.u32()?;
+
+    let mdia = trak.child(b"mdia")?;
+    let mut mdhd = Reader::new(mdia.child(b"mdhd")?.data);
+    let version = mdhd.u8()?;
+    mdhd.bytes(if version == 1 { 3 + 16 } else { 3 + 8 })?;
+    let timescale = mdhd.u32()?;
+    mdhd.bytes(if version == 1 { 8 } else { 4 })?;
+    let packed = mdhd.u16()?;
+    let language = [
+        (packed >> 10 & 0x1F) as u8 + 0x60,
+        (packed >> 5 & 0x1F) as u8 + 0x60,
+        (packed & 0x1F) as u8 + 0x60,
+    ];
+
+    let stsd = mdia.child(b"minf")?.child(b"stbl")?.child(b"stsd")?;
+    let entry = mp4::boxes(stsd.data.get(8..)?).next()?;
+
+    let mut settings = Vec::new();
+    let (kind, codec, codec_private) = match &entry.kind {
+        b"avc1" | b"avc3" | b"av01" | b"vp09" => {
+            // VisualSampleEntry
+            let mut reader = Reader::new(entry.data);
+            reader.bytes(24)?;
+            let width = reader.u16()?;
+            let height = reader.u16()?;
+            let children = entry.data.get(78..)?;
+
+            ebml::write_master(&mut settings, ebml::VIDEO, |out| {
+                ebml::write_uint(out, ebml::PIXEL_WIDTH, width.into());
+                ebml::write_uint(out, ebml::PIXEL_HEIGHT, height.into());
+            });
+
+            match &entry.kind {
+                b"av01" => (
+                    Kind::Video,
+                    "V_AV1",
+                    Some(mp4::find(children, b"av1C")?.data.to_vec()),
+                ),
+                b"vp09" => (Kind::Video, "V_VP9", None),
+                _ => (
+                    Kind::Video,
+                    "V_MPEG4/ISO/AVC",
+                    Some(mp4::find(children, b"avcC")?.data.to_vec()),
+                ),
+            }
+        }
+        b"mp4a" => {
+            // AudioSampleEntry
+            let mut reader = Reader::new(entry.data);
+            reader.bytes(16)?;
+            let channels = reader.u16()?;
+            reader.bytes(6)?;
+            let sample_rate = reader.u32()? >> 16;
+            let esds = mp4::find(entry.data.get(28..)?, b"esds")?;
+
+            ebml::write_master(&mut settings, ebml::AUDIO, |out| {
+                ebml::write_float(out, ebml::SAMPLING_FREQUENCY, sample_rate.into());
+                ebml::write_uint(out, ebml::CHANNELS, channels.into());
+            });
+
+            (
+                Kind::Audio,
+                "A_AAC",
+                Some(audio_specific_config(esds.data)?.to_vec()),
+            )
+        }
+        other => {
+            let codec = String::from_utf8_lossy(other).into_owned();
+            return Some(Err(crate::error::Mux::UnsupportedCodec(codec).into()));
+        }
+    };
+
+    // Defaults of the track fragments
+    let trex = mp4::find(&moov, b"mvex").and_then(|mvex| {
+        mvex.children()
+            .filter(|x| &x.kind == b"trex")
+            .find(|x| x.data.get(4..8) == Some(&track_id.to_be_bytes()[..]))
+    });
+    let (default_duration, default_size, default_flags) = match trex {
+        Some(trex) => {
+            let mut reader = Reader::new(trex.data.get(12..)?);
+            (reader.u32()?, reader.u32()?, reader.u32()?)
+        }
+        None => (0, 0, 0),
+    };
+
+    let mut frames = Vec::new();
+    let mut durations = Vec::new();
+    let mut fragments = Vec::new();
+    let mut copyable = true;
+
+    let mut top = top.into_iter().peekable();
+    while let Some(header) = top.next() {
+        if &header.kind != b"moof" {
+            continue;
+        }
+
+        let moof = source.read(header.offset + header.len, header.size - header.len)?;
+        let traf = mp4::find(&moof, b"traf")?;
+
+        let mut tfhd = Reader::new(traf.child(b"tfhd")?.data);
+        let flags = tfhd.u32()? & 0x00FF_FFFF;
+        if tfhd.u32()? != track_id {
+            continue;
+        }
+        let base = if flags & 0x01 != 0 {
+            // Absolute offsets break when the fragment is copied
+            copyable = false;
+            tfhd.u64()?
+        } else {
+            header.offset
+        };
+        if flags & 0x02 != 0 {
+            tfhd.u32()?;
+        }
+        let duration = if flags & 0x08 != 0 {
+            tfhd.u32()?
+        } else {
+            default_duration
+        };
+        let size = if flags & 0x10 != 0 {
+            tfhd.u32()?
+        } else {
+            default_size
+        };
+        let sample_flags = if flags & 0x20 != 0 {
+            tfhd.u32()?
+        } else {
+            default_flags
+        };
+
+        let mut tfdt = Reader::new(traf.child(b"tfdt")?.data);
+        let mut time = if tfdt.u32()? >> 24 == 1 {
+            tfdt.u64()?
+        } else {
+            u64::from(tfdt.u32()?)
+        };
+
+        if let Some(mdat) = top.peek().filter(|x| &x.kind == b"mdat") {
+            if mdat.offset != header.offset + header.size {
+                copyable = false;
+            }
+            fragments.push(Fragment {
+                time,
+                offset: header.offset,
+                moof_size: usize::try_from(header.size).ok()?,
+                size: mdat.offset + mdat.size - header.offset,
+            });
+        }
+
+        for trun in traf.children().filter(|x| &x.kind == b"trun") {
+            let mut reader = Reader::new(trun.data);
+            let version_flags = reader.u32()?;
+            let (version, flags) = (version_flags >> 24, version_flags & 0x00FF_FFFF);
+            let count = reader.u32()?;
+
+            let mut offset = base;
+            if flags & 0x01 != 0 {
+                offset = offset.checked_add_signed(i64::from(reader.u32()? as i32))?;
+            }
+            let first_flags = if flags & 0x04 != 0 {
+                Some(reader.u32()?)
+            } else {
+                None
+            };
+
+            for i in 0..count {
+                let duration = if flags & 0x100 != 0 {
+                    reader.u32()?
+                } else {
+                    duration
+                };
+                let size = if flags & 0x200 != 0 {
+                    reader.u32()?
+                } else {
+                    size
+                };
+                let sample_flags = if flags & 0x400 != 0 {
+                    reader.u32()?
+                } else {
+                    first_flags.filter(|_| i == 0).unwrap_or(sample_flags)
+                };
+                let composition_offset = if flags & 0x800 == 0 {
+                    0
+                } else if version == 1 {
+                    i64::from(reader.u32()? as i32)
+                } else {
+                    i64::from(reader.u32()?)
+                };
+
+                if offset.checked_add(u64::from(size))? > source.len {
+                    return None;
+                }
+
+                let pts = i64::try_from(time).ok()?.checked_add(composition_offset)?;
+                let pts = i128::from(pts) * 1_000_000_000 / i128::from(timescale.max(1));
+                frames.push(Frame {
+                    time: i64::try_from(pts).ok()?,
+                    keyframe: kind == Kind::Audio || sample_flags & 0x0001_0000 == 0,
+                    lacing: 0,
+                    offset,
+                    len: size as usize,
+                });
+
+                durations.push(duration);
+
+                time = time.checked_add(u64::from(duration))?;
+                offset += u64::from(size);
+            }
+        }
+    }
+
+    Some(Ok(Track {
+        kind,
+        codec: codec.to_string(),
+        codec_private,
+        settings,
+        frames,
+        mp4: Some(Mp4 {
+            trak: trak.data.to_vec(),
+            trex: trex.map(|x| x.data.to_vec()),
+            timescale,
+            language,
+            fragments,
+            copyable,
+            durations,
+        }),
+    }))
+}
+
+/// Extract the `AudioSpecificConfig` from the `esds` box
+fn audio_specific_config(esds: &[u8]) -> Option<&[u8]> {
+    /// Read the tag and length of a descriptor
+    fn descriptor<'a>(reader: &mut Reader<'a>) -> Option<(u8, usize)> {
+        let tag = reader.u8()?;
+        let mut len = 0;
+        for _ in 0..4 {
+            let byte = reader.u8()?;
+            len = len << 7 | usize::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        Some((tag, len))
+    }
+
+    let mut reader = Reader::new(esds);
+    reader.u32()?;
+
+    // ES_Descriptor
+    if descriptor(&mut reader)?.0 != 0x03 {
+        return None;
+    }
+    reader.u16()?;
+    let flags = reader.u8()?;
+    if flags & 0x80 != 0 {
+        reader.u16()?;
+    }
+    if flags & 0x40 != 0 {
+        let len = reader.u8()?;
+        reader.bytes(len.into())?;
+    }
+    if flags & 0x20 != 0 {
+        reader.u16()?;
+    }
+
+    // DecoderConfigDescriptor
+    if descriptor(&mut reader)?.0 != 0x04 {
+        return None;
+    }
+    reader.bytes(13)?;
+
+    // DecoderSpecificInfo
+    match descriptor(&mut reader)? {
+        (0x05, len) => reader.bytes(len),
+        _ => None,
+    }
+}
+
+/// Read a WebM file
+fn webm<R: Read + Seek>(source: &mut Source<'_, R>) -> Option<crate::Result<Track>> {
+    let (_, header_len, len) = source.element(0, source.len)?;
+    let segment = header_len + len;
+    let (id, header_len, len) = source.element(segment, source.len)?;
+    if id != ebml::SEGMENT {
+        return None;
+    }
+    let start = segment + header_len;
+    let end = start.saturating_add(len).min(source.len);
+
+    let mut timecode_scale = 1_000_000;
+    let mut track = None;
+    let mut number = 1;
+    let mut frames = Vec::new();
+
+    let mut offset = start;
+    while offset < end {
+        let (id, header_len, len) = source.element(offset, end)?;
+        let payload = offset + header_len;
+        // A truncated element ends the input
+        if payload.checked_add(len)? > end {
+            break;
+        }
+        offset = payload + len;
+
+        match id {
+            ebml::INFO => {
+                let info = source.read(payload, len)?;
+                if let Some(scale) = ebml::find(&info, ebml::TIMECODE_SCALE).and_then(|x| x.uint())
+                {
+                    timecode_scale = scale;
+                }
+            }
+            ebml::TRACKS => {
+                let tracks = source.read(payload, len)?;
+                let entry = ebml::find(&tracks, ebml::TRACK_ENTRY)?;
+                number = entry.child(ebml::TRACK_NUMBER)?.uint()?;
+                track = Some(entry.data.to_vec());
+            }
+            ebml::CLUSTER => {
+                let data = source.read(payload, len)?;
+                let cluster = ebml::find(&data, ebml::TIMECODE)
+                    .and_then(|x| x.uint())
+                    .unwrap_or(0);
+                let cluster = i64::try_from(cluster).ok()?;
+
+                for block in ebml::elements(&data) {
+                    // The block and its position relative to the cluster
+                    let (data, position, keyframe) = match block.id {
+                        ebml::SIMPLE_BLOCK => (block.data, block.offset + block.header_len, None),
+                        ebml::BLOCK_GROUP => {
+                            let inner = block.child(ebml::BLOCK)?;
+                            (
+                                inner.data,
+                                block.offset + block.header_len + inner.offset + inner.header_len,
+                                Some(block.child(ebml::REFERENCE_BLOCK).is_none()),
+                            )
+                        }
+                        _ => continue,
+                    };
+
+                    // Track number, relative timecode and flags
+                    let (block_number, len) = ebml::read_size(data)?;
+                    if block_number != Some(number) {
+                        continue;
+                    }
+                    let mut reader = Reader::new(data.get(len..)?);
+                    let relative = i64::from(reader.u16()? as i16);
+                    let flags = reader.u8()?;
+
+                    frames.push(Frame {
+                        time: cluster
+                            .checked_add(relative)?
+                            .checked_mul(i64::try_from(timecode_scale).ok()?)?,
+                        keyframe: keyframe.unwrap_or(flags & 0x80 != 0),
+                        lacing: flags & 0x06,
+                        offset: payload + (position + len + 3) as u64,
+                        len: data.get(len + 3..)?.len(),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let track = track?;
+    let kind = match ebml::find(&track, ebml::TRACK_TYPE)?.uint()? {
+        1 => Kind::Video,
+        2 => Kind::Audio,
+        _ => return None,
+    };
+    let codec = std::str::from_utf8(ebml::find(&track, ebml::CODEC_ID)?.data)
+        .ok()?
+        .to_string();
+
+    let mut settings = Vec::new();
+    for child in ebml::elements(&track) {
+        if matches!(
+            child.id,
+            ebml::VIDEO | ebml::AUDIO | ebml::CODEC_DELAY | ebml::SEEK_PRE_ROLL
+        ) {
+            ebml::write(&mut settings, child.id, child.data);
+        }
+    }
+
+    Some(Ok(Track {
+        kind,
+        codec,
+        codec_private: ebml::find(&track, ebml::CODEC_PRIVATE).map(|x| x.data.to_vec()),
+        settings,
+        frames,
+        mp4: None,
+    }))
+}
@@ -0,0 +1,352 @@
+//! Writing of Matroska files
+
+use std::{
+    convert::TryFrom,
+    io::{Read, Seek, Write},
+};
+
+use crate::container::ebml;
+
+use super::{
+    demux::{Input, Kind},
+    Metadata,
+};
+
+/// The `TimecodeScale` of the written file: 1ms
+const TIMECODE_SCALE: i64 = 1_000_000;
+
+/// The maximum length of a cluster when no video keyframe starts a new one
+const MAX_CLUSTER_LENGTH: i64 = 5_000;
+
+/// Write `inputs` into a single Matroska file
+pub(super) fn write<R: Read + Seek, W: Write>(
+    out: &mut W,
+    inputs: &mut [Input<'_, R>],
+    metadata: &Metadata,
+) -> crate::Result<()> {
+    let mut header = Vec::new();
+    ebml::write_master(&mut header, ebml::EBML, |out| {
+        ebml::write_uint(out, ebml::EBML_VERSION, 1);
+        ebml::write_uint(out, ebml::EBML_READ_VERSION, 1);
+        ebml::write_uint(out, ebml::EBML_MAX_ID_LENGTH, 4);
+        ebml::write_uint(out, ebml::EBML_MAX_SIZE_LENGTH, 8);
+        ebml::write_str(out, ebml::DOC_TYPE, "matroska");
+        ebml::write_uint(out, ebml::DOC_TYPE_VERSION, 4);
+        ebml::write_uint(out, ebml::DOC_TYPE_READ_VERSION, 2);
+    });
+
+    // Shift all timestamps so that the first frame starts at 0 or later
+    let shift = inputs
+        .iter()
+        .flat_map(|input| input.track.frames.iter().map(|x| x.time))
+        .min()
+        .unwrap_or(0)
+        .min(0);
+    let duration = inputs
+        .iter()
+        .map(|input| input.track.duration() - shift)
+        .max()
+        .unwrap_or(0);
+
+    // The top level elements before the clusters
+    let mut elements = vec![
+        (ebml::INFO, info(metadata, duration)),
+        (ebml::TRACKS, track_entries(inputs)),
+    ];
+    if !metadata.chapters.is_empty() {
+        elements.push((ebml::CHAPTERS, chapters(metadata)));
+    }
+    elements.push((ebml::TAGS, tags(metadata)));
+
+    // The `SeekHead` has a constant size, as its positions are written with
+    // a fixed width
+    let mut entries: Vec<(u32, u64)> = elements.iter().map(|(id, _)| (*id, 0)).collect();
+    entries.push((ebml::CUES, 0));
+    let seek_head_len = seek_head(&entries).len();
+
+    let mut position = seek_head_len as u64;
+    for (entry, (_, element)) in entries.iter_mut().zip(&elements) {
+        entry.1 = position;
+        position += element.len() as u64;
+    }
+    let (clusters, cues) = clusters(inputs, shift, position);
+    let clusters_len: u64 = clusters.iter().map(Cluster::len).sum();
+    if let Some(entry) = entries.last_mut() {
+        entry.1 = position + clusters_len;
+    }
+
+    // The clusters are only planned, so the `Segment` is written with its
+    // size up front and the blocks are read while writing
+    ebml::write_id(&mut header, ebml::SEGMENT);
+    ebml::write_size(&mut header, position + clusters_len + cues.len() as u64);
+    header.extend(seek_head(&entries));
+    for (_, element) in elements {
+        header.extend(element);
+    }
+    out.write_all(&header)?;
+
+    for cluster in &clusters {
+        out.write_all(&cluster.write(inputs, shift)?)?;
+    }
+    out.write_all(&cues)?;
+
+    Ok(())
+}
+
+/// The `SeekHead` pointing to the top level elements with their position
+fn seek_head(entries: &[(u32, u64)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    ebml::write_master(&mut out, ebml::SEEK_HEAD, |out| {
+        for (id, position) in entries {
+            ebml::write_master(out, ebml::SEEK, |out| {
+                ebml::write(out, ebml::SEEK_ID, &id.to_be_bytes());
+                ebml::write(out, ebml::SEEK_POSITION, &position.to_be_bytes());
+            });
+        }
+    });
+    out
+}
+
+fn info(metadata: &Metadata, duration: i64) -> Vec<u8> {
+    let mut out = Vec::new();
+    ebml::write_master(&mut out, ebml::INFO, |out| {
+        ebml::write_uint(out, ebml::TIMECODE_SCALE, TIMECODE_SCALE as u64);
+        ebml::write_float(out, ebml::DURATION, duration as f64 / TIMECODE_SCALE as f64);
+        if let Some(title) = &metadata.title {
+            ebml::write_str(out, ebml::TITLE, title);
+        }
+        ebml::write_str(out, ebml::MUXING_APP, env!("CARGO_PKG_NAME"));
+        ebml::write_str(out, ebml::WRITING_APP, env!("CARGO_PKG_NAME"));
+    });
+    out
+}
+
+fn track_entries<R>(inputs: &[Input<'_, R>]) -> Vec<u8> {
+    let mut out = Vec::new();
+    ebml::write_master(&mut out, ebml::TRACKS, |out| {
+        for (
+            number,
+            Input {
+                track, language, ..
+            },
+        ) in (1..).zip(inputs)
+        {
+            ebml::write_master(out, ebml::TRACK_ENTRY, |out| {
+                ebml::write_uint(out, ebml::TRACK_NUMBER, number);
+                ebml::write_uint(out, ebml::TRACK_UID, number);
+                ebml::write_uint(
+                    out,
+                    ebml::TRACK_TYPE,
+                    match track.kind {
+                        Kind::Video => 1,
+                        Kind::Audio => 2,
+                    },
+                );
+                ebml::write_uint(out, ebml::FLAG_DEFAULT, 1);
+                if let Some(language) = language {
+                    ebml::write_str(out, ebml::LANGUAGE, super::iso639_2(language));
+                    ebml::write_str(out, ebml::LANGUAGE_IETF, language);
+                }
+                ebml::write_str(out, ebml::CODEC_ID, &track.codec);
+                if let Some(codec_private) = &track.codec_private {
+                    ebml::write(out, ebml::CODEC_PRIVATE, codec_private);
+                }
+                out.extend_from_slice(&track.settings);
+            });
+        }
+    });
+    out
+}
+
+fn chapters(metadata: &Metadata) -> Vec<u8> {
+    let mut out = Vec::new();
+    ebml::write_master(&mut out, ebml::CHAPTERS, |out| {
+        ebml::write_master(out, ebml::EDITION_ENTRY, |out| {
+            for (uid, chapter) in (1..).zip(&metadata.chapters) {
+                ebml::write_master(out, ebml::CHAPTER_ATOM, |out| {
+                    ebml::write_uint(out, ebml::CHAPTER_UID, uid);
+                    ebml::write_uint(
+                        out,
+                        ebml::CHAPTER_TIME_START,
+                        chapter.start().as_nanos() as u64,
+                    );
+                    ebml::write_uint(out, ebml::CHAPTER_TIME_END, chapter.end().as_nanos() as u64);
+                    ebml::write_master(out, ebml::CHAPTER_DISPLAY, |out| {
+                        ebml::write_str(out, ebml::CHAP_STRING, chapter.title());
+                    });
+                });
+            }
+        });
+    });
+    out
+}
+
+fn tags(metadata: &Metadata) -> Vec<u8> {
+    let date = metadata.date.map(|x| x.format("%Y-%m-%d").to_string());
+    let tags = [
+        ("TITLE", metadata.title.as_deref()),
+        ("ARTIST", metadata.artist.as_deref()),
+        ("DATE_RELEASED", date.as_deref()),
+    ];
+
+    let mut out = Vec::new();
+    ebml::write_master(&mut out, ebml::TAGS, |out| {
+        ebml::write_master(out, ebml::TAG, |out| {
+            ebml::write_master(out, ebml::TARGETS, |out| {
+                // The whole movie
+                ebml::write_uint(out, ebml::TARGET_TYPE_VALUE, 50);
+            });
+            for (name, value) in tags.iter() {
+                if let Some(value) = value {
+                    ebml::write_master(out, ebml::SIMPLE_TAG, |out| {
+                        ebml::write_str(out, ebml::TAG_NAME, name);
+                        ebml::write_str(out, ebml::TAG_STRING, value);
+                    });
+                }
+            }
+        });
+    });
+    out
+}
+
+/// A cluster, whose blocks are read from the inputs when it is written
+#[derive(Debug)]
+struct Cluster {
+    /// The timecode in units of the `TimecodeScale`
+    time: i64,
+    /// The input and the index of the frame of every block
+    blocks: Vec<(usize, usize)>,
+    /// The length of the payload
+    payload_len: u64,
+}
+
+impl Cluster {
+    fn new(time: i64) -> Self {
+        let mut timecode = Vec::new();
+        ebml::write_uint(&mut timecode, ebml::TIMECODE, time as u64);
+
+        Self {
+            time,
+            blocks: Vec::new(),
+            payload_len: timecode.len() as u64,
+        }
+    }
+
+    /// The length of the cluster including its header
+    fn len(&self) -> u64 {
+        element_len(ebml::CLUSTER, self.payload_len)
+    }
+
+    /// Add the `frame` of the `input` with `len` bytes of data
+    fn push(&mut self, input: usize, frame: usize, len: usize) {
+        self.blocks.push((input, frame));
+        self.payload_len += element_len(
+            ebml::SIMPLE_BLOCK,
+            block_header(input, 0, 0).len() as u64 + len as u64,
+        );
+    }
+
+    /// Read the blocks of the cluster from the `inputs` and write it
+    fn write<R: Read + Seek>(
+        &self,
+        inputs: &mut [Input<'_, R>],
+        shift: i64,
+    ) -> std::io::Result<Vec<u8>> {
+        let mut payload = Vec::with_capacity(self.payload_len as usize);
+        ebml::write_uint(&mut payload, ebml::TIMECODE, self.time as u64);
+        for &(input, index) in &self.blocks {
+            let frame = inputs[input].track.frames[index];
+            let time = (frame.time - shift) / TIMECODE_SCALE;
+            let flags = if frame.keyframe { 0x80 } else { 0 } | frame.lacing;
+
+            let mut block = block_header(input, time - self.time, flags);
+            block.extend(inputs[input].read(frame.offset, frame.len)?);
+            ebml::write(&mut payload, ebml::SIMPLE_BLOCK, &block);
+        }
+
+        let mut out = Vec::with_capacity(self.len() as usize);
+        ebml::write(&mut out, ebml::CLUSTER, &payload);
+        Ok(out)
+    }
+}
+
+/// The track number, relative timecode and flags of a `SimpleBlock`
+fn block_header(input: usize, relative: i64, flags: u8) -> Vec<u8> {
+    let mut out = Vec::with_capacity(4);
+    ebml::write_size(&mut out, input as u64 + 1);
+    out.extend_from_slice(&(relative as i16).to_be_bytes());
+    out.push(flags);
+    out
+}
+
+/// The length of a element with a payload of `len` bytes, including its
+/// header
+fn element_len(id: u32, len: u64) -> u64 {
+    let mut header = Vec::new();
+    ebml::write_id(&mut header, id);
+    ebml::write_size(&mut header, len);
+    header.len() as u64 + len
+}
+
+/// Interleave the frames of all `inputs` into clusters. Returns the clusters
+/// and the `Cues` pointing to them.
+fn clusters<R>(inputs: &[Input<'_, R>], shift: i64, offset: u64) -> (Vec<Cluster>, Vec<u8>) {
+    let video = inputs
+        .iter()
+        .position(|input| input.track.kind == Kind::Video);
+
+    let mut clusters: Vec<Cluster> = Vec::new();
+    let mut cues = Vec::new();
+    let mut position = offset;
+
+    let mut next = vec![0; inputs.len()];
+    loop {
+        // The input with the earliest next frame
+        let current = (0..inputs.len())
+            .filter_map(|i| Some((i, inputs[i].track.frames.get(next[i])?)))
+            .min_by_key(|(_, frame)| frame.time);
+        let (i, frame) = match current {
+            Some(current) => current,
+            None => break,
+        };
+        let index = next[i];
+        next[i] += 1;
+
+        let time = (frame.time - shift) / TIMECODE_SCALE;
+        let cluster_time = clusters.last().map_or(0, |x| x.time);
+        let starts_cluster = if video == Some(i) {
+            frame.keyframe
+        } else {
+            video.is_none() && time - cluster_time >= MAX_CLUSTER_LENGTH
+        };
+        let relative = time - cluster_time;
+
+        if clusters.is_empty()
+            || (starts_cluster && relative > 0)
+            || i16::try_from(relative).is_err()
+        {
+            if let Some(cluster) = clusters.last() {
+                position += cluster.len();
+            }
+
+            if starts_cluster || video.is_none() {
+                ebml::write_master(&mut cues, ebml::CUE_POINT, |cues| {
+                    ebml::write_uint(cues, ebml::CUE_TIME, time as u64);
+                    ebml::write_master(cues, ebml::CUE_TRACK_POSITIONS, |cues| {
+                        ebml::write_uint(cues, ebml::CUE_TRACK, video.unwrap_or(0) as u64 + 1);
+                        ebml::write_uint(cues, ebml::CUE_CLUSTER_POSITION, position);
+                    });
+                });
+            }
+            clusters.push(Cluster::new(time));
+        }
+
+        if let Some(cluster) = clusters.last_mut() {
+            cluster.push(i, index, frame.len);
+        }
+    }
+
+    let mut ret = Vec::new();
+    ebml::write(&mut ret, ebml::CUES, &cues);
+    (clusters, ret)
+}
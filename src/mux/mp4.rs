@@ -0,0 +1,504 @@
+//! Writing of MP4 files
+
+use std::{
+    convert::TryFrom,
+    io::{Read, Seek, Write},
+};
+
+use crate::{
+    container::mp4::{self, Mp4Box},
+    error,
+};
+
+use super::{
+    demux::{Fragment, Input, Mp4, Track},
+    Metadata,
+};
+
+/// The timescale of the `mvhd`: 1ms
+const TIMESCALE: u32 = 1000;
+
+/// The single chunk of a progressive file
+#[derive(Debug, Clone, Copy)]
+struct Chunk<'a> {
+    track: &'a Track,
+    /// The duration of the track in units of its timescale
+    duration: u64,
+    /// The duration of the track in units of the [`TIMESCALE`]
+    movie_duration: u64,
+    /// The offset of the chunk in the file
+    offset: u32,
+}
+
+/// If `inputs` are fragmented MP4 files, whose fragments can be copied by
+/// [`write`]
+pub(super) fn copyable<R>(inputs: &[Input<'_, R>]) -> bool {
+    !inputs.is_empty()
+        && inputs
+            .iter()
+            .all(|x| x.track.mp4.as_ref().is_some_and(|x| x.copyable))
+}
+
+/// Write `inputs` into a single fragmented MP4 file, by copying their
+/// fragments. The `inputs` have to be [`copyable`].
+pub(super) fn write<R: Read + Seek, W: Write>(
+    out: &mut W,
+    inputs: &mut [Input<'_, R>],
+    metadata: &Metadata,
+    brand: &[u8; 4],
+) -> crate::Result<()> {
+    if !copyable(inputs) {
+        return Err(error::Mux::UnsupportedFormat.into());
+    }
+    let mp4s: Vec<(&Mp4, Option<&str>)> = inputs
+        .iter()
+        .filter_map(|x| Some((x.track.mp4.as_ref()?, x.language)))
+        .collect();
+
+    let duration = inputs.iter().map(|x| x.track.duration()).max().unwrap_or(0);
+
+    let mut header = Vec::new();
+    mp4::write_box(&mut header, b"ftyp", |out| {
+        out.extend_from_slice(brand);
+        out.extend_from_slice(&0x200u32.to_be_bytes());
+        for compatible in [brand, b"isom", b"iso6", b"mp41"] {
+            out.extend_from_slice(compatible);
+        }
+    });
+
+    let mut moov = Vec::new();
+    mvhd(&mut moov, duration, mp4s.len() as u32 + 1);
+    for (id, (input, language)) in (1..).zip(&mp4s) {
+        trak(&mut moov, input, id, *language, None).ok_or(error::Mux::InvalidInput)?;
+    }
+    mp4::write_box(&mut moov, b"mvex", |out| {
+        for (id, (input, _)) in (1..).zip(&mp4s) {
+            trex(out, input.trex.as_deref(), id);
+        }
+    });
+    udta(&mut moov, metadata);
+    mp4::write(&mut header, b"moov", &moov);
+    out.write_all(&header)?;
+
+    // Interleave the fragments by their start time
+    let mut fragments: Vec<(usize, &Fragment, f64)> = mp4s
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (input, _))| {
+            input.fragments.iter().map(move |fragment| {
+                let time = fragment.time as f64 / f64::from(input.timescale.max(1));
+                (i, fragment, time)
+            })
+        })
+        .collect();
+    fragments.sort_by(|a, b| a.2.total_cmp(&b.2));
+    let fragments: Vec<(usize, Fragment)> = fragments
+        .into_iter()
+        .map(|(i, fragment, _)| (i, *fragment))
+        .collect();
+
+    for (sequence, (i, fragment)) in (1..).zip(fragments) {
+        let input = &mut inputs[i];
+        let mut moof = input.read(fragment.offset, fragment.moof_size)?;
+        patch_fragment(&mut moof, i as u32 + 1, sequence).ok_or(error::Mux::InvalidInput)?;
+        out.write_all(&moof)?;
+
+        let moof_size = fragment.moof_size as u64;
+        input.copy(fragment.offset + moof_size, fragment.size - moof_size, out)?;
+    }
+
+    Ok(())
+}
+
+/// Write a single audio `input` into a progressive (not fragmented) M4A
+/// file. The `input` has to be a MP4 file.
+pub(super) fn write_audio<R: Read + Seek, W: Write>(
+    out: &mut W,
+    input: &mut Input<'_, R>,
+    metadata: &Metadata,
+) -> crate::Result<()> {
+    let track = &input.track;
+    let mp4 = track.mp4.as_ref().ok_or(error::Mux::UnsupportedFormat)?;
+
+    let mut header = Vec::new();
+    mp4::write_box(&mut header, b"ftyp", |out| {
+        out.extend_from_slice(b"M4A ");
+        out.extend_from_slice(&0x200u32.to_be_bytes());
+        for compatible in [b"M4A ", b"isom", b"iso2", b"mp41"] {
+            out.extend_from_slice(compatible);
+        }
+    });
+
+    let duration = mp4.durations.iter().map(|x| u64::from(*x)).sum::<u64>();
+    let movie_duration = duration
+        .checked_mul(u64::from(TIMESCALE))
+        .ok_or(error::Mux::InvalidInput)?
+        / u64::from(mp4.timescale.max(1));
+
+    // The samples are written as a single chunk directly after the `moov`,
+    // whose size does not depend on the chunk offset
+    let moov = |offset: u32| {
+        let chunk = Chunk {
+            track,
+            duration,
+            movie_duration,
+            offset,
+        };
+
+        let mut moov = Vec::new();
+        mvhd(&mut moov, track.duration(), 2);
+        trak(&mut moov, mp4, 1, input.language, Some(chunk))?;
+        udta(&mut moov, metadata);
+
+        let mut out = Vec::new();
+        mp4::write(&mut out, b"moov", &moov);
+        Some(out)
+    };
+
+    let mdat_len: u64 = track.frames.iter().map(|x| x.len as u64).sum();
+    let mut mdat = Vec::new();
+    mp4::write_header(&mut mdat, b"mdat", mdat_len);
+
+    let moov_len = moov(0).ok_or(error::Mux::InvalidInput)?.len();
+    let chunk_offset = u32::try_from(header.len() + moov_len + mdat.len())
+        .map_err(|_| error::Mux::UnsupportedFormat)?;
+    header.extend(moov(chunk_offset).ok_or(error::Mux::InvalidInput)?);
+    header.extend(mdat);
+    out.write_all(&header)?;
+
+    for i in 0..input.track.frames.len() {
+        let frame = input.track.frames[i];
+        out.write_all(&input.read(frame.offset, frame.len)?)?;
+    }
+
+    Ok(())
+}
+
+/// Write the `mvhd` of a movie lasting `duration` nanoseconds
+fn mvhd(out: &mut Vec<u8>, duration: i64, next_track_id: u32) {
+    // Dividing instead of multiplying by the timescale can not overflow
+    let duration = u64::try_from(duration).unwrap_or(0) / (1_000_000_000 / u64::from(TIMESCALE));
+    // Version 1 has 64 bit times and durations
+    let v1 = u32::try_from(duration).is_err();
+
+    mp4::write_full_box(out, b"mvhd", u8::from(v1), 0, |out| {
+        // Creation and modification time
+        out.extend_from_slice(if v1 { &[0; 16] } else { &[0; 8] });
+        out.extend_from_slice(&TIMESCALE.to_be_bytes());
+        if v1 {
+            out.extend_from_slice(&duration.to_be_bytes());
+        } else {
+            out.extend_from_slice(&(duration as u32).to_be_bytes());
+        }
+        // Rate 1.0, volume 1.0 and reserved
+        out.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+        out.extend_from_slice(&0x0100u16.to_be_bytes());
+        out.extend_from_slice(&[0; 10]);
+        // Unity matrix
+        for value in [0x0001_0000u32, 0, 0, 0, 0x0001_0000, 0, 0, 0, 0x4000_0000] {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        // Pre-defined
+        out.extend_from_slice(&[0; 24]);
+        out.extend_from_slice(&next_track_id.to_be_bytes());
+    });
+}
+
+/// Copy the `trak` of a input, changing its track id and language. If the
+/// track is written as a single `chunk`, its duration and sample table are
+/// rebuilt. Returns [`None`] if the `trak` is malformed.
+fn trak(
+    out: &mut Vec<u8>,
+    input: &Mp4,
+    id: u32,
+    language: Option<&str>,
+    chunk: Option<Chunk<'_>>,
+) -> Option<()> {
+    let mut trak = Vec::new();
+    for child in mp4::boxes(&input.trak) {
+        match &child.kind {
+            b"tkhd" => {
+                let duration = chunk.map(|x| x.movie_duration);
+                mp4::write(&mut trak, b"tkhd", &tkhd(child.data, id, duration)?);
+            }
+            b"mdia" => mdia(&mut trak, child, input, language, chunk)?,
+            kind => mp4::write(&mut trak, kind, child.data),
+        }
+    }
+    mp4::write(out, b"trak", &trak);
+    Some(())
+}
+
+/// Copy the `tkhd` of a input, changing its track id and optionally its
+/// `duration`
+fn tkhd(data: &[u8], id: u32, duration: Option<u64>) -> Option<Vec<u8>> {
+    let mut data = data.to_vec();
+    if *data.first()? == 0 && duration.is_some_and(|x| u32::try_from(x).is_err()) {
+        // The track id and a reserved field are between the times and the
+        // duration
+        data = version_1(&data, 8)?;
+    }
+    let v1 = *data.first()? == 1;
+
+    let offset = if v1 { 20 } else { 12 };
+    data.get_mut(offset..offset + 4)?
+        .copy_from_slice(&id.to_be_bytes());
+
+    if let Some(duration) = duration {
+        if v1 {
+            data.get_mut(28..36)?
+                .copy_from_slice(&duration.to_be_bytes());
+        } else {
+            data.get_mut(20..24)?
+                .copy_from_slice(&(duration as u32).to_be_bytes());
+        }
+    }
+
+    Some(data)
+}
+
+/// Copy the `mdia` of a input, changing its language. If the track is
+/// written as a single `chunk`, its duration and sample table are rebuilt.
+fn mdia(
+    out: &mut Vec<u8>,
+    mdia: Mp4Box<'_>,
+    input: &Mp4,
+    language: Option<&str>,
+    chunk: Option<Chunk<'_>>,
+) -> Option<()> {
+    let language = language
+        .and_then(|x| <[u8; 3]>::try_from(super::iso639_2(x).as_bytes()).ok())
+        .unwrap_or(input.language);
+
+    let mut data = Vec::new();
+    for child in mdia.children() {
+        match &child.kind {
+            b"mdhd" => {
+                let duration = chunk.map(|x| x.duration);
+                mp4::write(&mut data, b"mdhd", &mdhd(child.data, language, duration)?);
+            }
+            b"minf" => match chunk {
+                Some(chunk) => mp4::write_box(&mut data, b"minf", |out| {
+                    for child in child.children() {
+                        if &child.kind == b"stbl" {
+                            stbl(out, child, input, chunk);
+                        } else {
+                            mp4::write(out, &child.kind, child.data);
+                        }
+                    }
+                }),
+                None => mp4::write(&mut data, b"minf", child.data),
+            },
+            kind => mp4::write(&mut data, kind, child.data),
+        }
+    }
+    mp4::write(out, b"mdia", &data);
+    Some(())
+}
+
+/// Copy the `mdhd` of a input, changing its `language` and optionally its
+/// `duration`
+fn mdhd(data: &[u8], language: [u8; 3], duration: Option<u64>) -> Option<Vec<u8>> {
+    let mut data = data.to_vec();
+    if *data.first()? == 0 && duration.is_some_and(|x| u32::try_from(x).is_err()) {
+        // The timescale is between the times and the duration
+        data = version_1(&data, 4)?;
+    }
+    let v1 = *data.first()? == 1;
+
+    if let Some(duration) = duration {
+        if v1 {
+            data.get_mut(24..32)?
+                .copy_from_slice(&duration.to_be_bytes());
+        } else {
+            data.get_mut(16..20)?
+                .copy_from_slice(&(duration as u32).to_be_bytes());
+        }
+    }
+
+    let packed = language.iter().fold(0u16, |acc, x| {
+        acc << 5 | u16::from(x.wrapping_sub(0x60)) & 0x1F
+    });
+    let offset = if v1 { 32 } else { 20 };
+    data.get_mut(offset..offset + 2)?
+        .copy_from_slice(&packed.to_be_bytes());
+
+    Some(data)
+}
+
+/// Convert the payload of a version 0 `tkhd` or `mdhd` into version 1, whose
+/// creation time, modification time and duration are 64 bits. The duration
+/// follows the times after `between` bytes of other fields.
+fn version_1(data: &[u8], between: usize) -> Option<Vec<u8>> {
+    let mut reader = mp4::Reader::new(data);
+    let flags = reader.u32()? & 0x00FF_FFFF;
+    let creation = reader.u32()?;
+    let modification = reader.u32()?;
+    let fields = reader.bytes(between)?;
+    let duration = reader.u32()?;
+    let rest = data.get(reader.position()..)?;
+
+    let mut out = (1 << 24 | flags).to_be_bytes().to_vec();
+    out.extend_from_slice(&u64::from(creation).to_be_bytes());
+    out.extend_from_slice(&u64::from(modification).to_be_bytes());
+    out.extend_from_slice(fields);
+    out.extend_from_slice(&u64::from(duration).to_be_bytes());
+    out.extend_from_slice(rest);
+    Some(out)
+}
+
+/// Rebuild the sample table of a input, with all samples in a single chunk
+fn stbl(out: &mut Vec<u8>, stbl: Mp4Box<'_>, input: &Mp4, chunk: Chunk<'_>) {
+    let count = input.durations.len() as u32;
+
+    mp4::write_box(out, b"stbl", |out| {
+        if let Some(stsd) = stbl.child(b"stsd") {
+            mp4::write(out, b"stsd", stsd.data);
+        }
+
+        // Run length encoded sample durations
+        let mut runs: Vec<(u32, u32)> = Vec::new();
+        for duration in &input.durations {
+            match runs.last_mut() {
+                Some((count, last)) if last == duration => *count += 1,
+                _ => runs.push((1, *duration)),
+            }
+        }
+        mp4::write_full_box(out, b"stts", 0, 0, |out| {
+            out.extend_from_slice(&(runs.len() as u32).to_be_bytes());
+            for (count, duration) in runs {
+                out.extend_from_slice(&count.to_be_bytes());
+                out.extend_from_slice(&duration.to_be_bytes());
+            }
+        });
+
+        mp4::write_full_box(out, b"stsc", 0, 0, |out| {
+            // First chunk, samples per chunk and sample description index
+            out.extend_from_slice(&1u32.to_be_bytes());
+            out.extend_from_slice(&1u32.to_be_bytes());
+            out.extend_from_slice(&count.to_be_bytes());
+            out.extend_from_slice(&1u32.to_be_bytes());
+        });
+
+        mp4::write_full_box(out, b"stsz", 0, 0, |out| {
+            // No constant sample size
+            out.extend_from_slice(&0u32.to_be_bytes());
+            out.extend_from_slice(&count.to_be_bytes());
+            for frame in &chunk.track.frames {
+                out.extend_from_slice(&(frame.len as u32).to_be_bytes());
+            }
+        });
+
+        mp4::write_full_box(out, b"stco", 0, 0, |out| {
+            out.extend_from_slice(&1u32.to_be_bytes());
+            out.extend_from_slice(&chunk.offset.to_be_bytes());
+        });
+    });
+}
+
+/// Copy the `trex` of a input, changing its track id
+fn trex(out: &mut Vec<u8>, trex: Option<&[u8]>, id: u32) {
+    let mut data = match trex {
+        Some(trex) => trex.to_vec(),
+        // Version, flags, track id, sample description index and defaults
+        None => [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+        ]
+        .to_vec(),
+    };
+    data[4..8].copy_from_slice(&id.to_be_bytes());
+    mp4::write(out, b"trex", &data);
+}
+
+/// The iTunes style metadata of the file
+fn udta(out: &mut Vec<u8>, metadata: &Metadata) {
+    let date = metadata.date.map(|x| x.format("%Y-%m-%d").to_string());
+    let tags = [
+        (b"\xA9nam", metadata.title.as_deref()),
+        (b"\xA9ART", metadata.artist.as_deref()),
+        (b"\xA9day", date.as_deref()),
+    ];
+
+    mp4::write_box(out, b"udta", |out| {
+        mp4::write_full_box(out, b"meta", 0, 0, |out| {
+            mp4::write_full_box(out, b"hdlr", 0, 0, |out| {
+                out.extend_from_slice(&[0; 4]);
+                out.extend_from_slice(b"mdir");
+                out.extend_from_slice(b"appl");
+                out.extend_from_slice(&[0; 9]);
+            });
+            mp4::write_box(out, b"ilst", |out| {
+                for (kind, value) in tags.iter() {
+                    if let Some(value) = value {
+                        ilst_item(out, kind, 1, value.as_bytes());
+                    }
+                }
+                // Only JPEG and PNG are well-known types of a cover
+                match metadata.cover.as_ref().map(|x| (x.mime_type(), x.data())) {
+                    Some(("image/jpeg", data)) => ilst_item(out, b"covr", 13, data),
+                    Some(("image/png", data)) => ilst_item(out, b"covr", 14, data),
+                    _ => {}
+                }
+            });
+        });
+
+        // Nero chapters, which are the most widely supported in MP4 files
+        if !metadata.chapters.is_empty() {
+            mp4::write_full_box(out, b"chpl", 1, 0, |out| {
+                out.extend_from_slice(&[0; 4]);
+                out.push(metadata.chapters.len().min(255) as u8);
+                for chapter in metadata.chapters.iter().take(255) {
+                    // The start in units of 100ns
+                    let start = chapter.start().as_nanos() / 100;
+                    out.extend_from_slice(&(start as u64).to_be_bytes());
+                    let title = truncate(chapter.title(), 255);
+                    out.push(title.len() as u8);
+                    out.extend_from_slice(title.as_bytes());
+                }
+            });
+        }
+    });
+}
+
+/// Truncate `value` to at most `len` bytes, at a character boundary
+fn truncate(value: &str, len: usize) -> &str {
+    let end = (0..=len.min(value.len()))
+        .rev()
+        .find(|x| value.is_char_boundary(*x))
+        .unwrap_or(0);
+    &value[..end]
+}
+
+/// A item of a `ilst` with a `data` box of the well-known `kind`
+fn ilst_item(out: &mut Vec<u8>, name: &[u8; 4], kind: u32, value: &[u8]) {
+    mp4::write_box(out, name, |out| {
+        mp4::write_full_box(out, b"data", 0, kind, |out| {
+            // Locale
+            out.extend_from_slice(&[0; 4]);
+            out.extend_from_slice(value);
+        });
+    });
+}
+
+/// Change the sequence number and track id of a copied `moof`
+fn patch_fragment(moof: &mut [u8], id: u32, sequence: u32) -> Option<()> {
+    let (sequence_offset, id_offset) = {
+        let moof = mp4::boxes(moof).next()?;
+        let header = moof.header_len();
+
+        let mfhd = moof.child(b"mfhd")?;
+        let traf = moof.child(b"traf")?;
+        let tfhd = traf.child(b"tfhd")?;
+        (
+            header + mfhd.offset + mfhd.header_len() + 4,
+            header + traf.offset + traf.header_len() + tfhd.offset + tfhd.header_len() + 4,
+        )
+    };
+
+    moof.get_mut(sequence_offset..sequence_offset + 4)?
+        .copy_from_slice(&sequence.to_be_bytes());
+    moof.get_mut(id_offset..id_offset + 4)?
+        .copy_from_slice(&id.to_be_bytes());
+
+    Some(())
+}
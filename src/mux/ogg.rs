@@ -0,0 +1,150 @@
+//! Writing of Ogg Opus files, as described in
+//! [RFC 7845](https://datatracker.ietf.org/doc/html/rfc7845)
+
+use std::io::{Read, Seek, Write};
+
+use crate::{container::ogg, error};
+
+use super::{demux::Input, Metadata};
+
+/// The serial number of the written bitstream
+const SERIAL: u32 = 0x7974_6578;
+
+/// The maximum duration of a page in samples at 48kHz: 1 second
+const PAGE_DURATION: u64 = 48_000;
+
+/// Write a Opus `input` into a Ogg Opus file
+pub(super) fn write<R: Read + Seek, W: Write>(
+    out: &mut W,
+    input: &mut Input<'_, R>,
+    metadata: &Metadata,
+) -> crate::Result<()> {
+    let track = &input.track;
+    if track.codec != "A_OPUS" {
+        return Err(error::Mux::UnsupportedCodec(track.codec.clone()).into());
+    }
+    let head = track
+        .codec_private
+        .as_deref()
+        .filter(|x| x.starts_with(b"OpusHead"))
+        .ok_or(error::Mux::InvalidInput)?;
+    // Laced blocks contain multiple packets
+    if track.frames.iter().any(|x| x.lacing != 0) {
+        return Err(error::Mux::UnsupportedFormat.into());
+    }
+
+    let mut writer = ogg::Writer::new(SERIAL);
+
+    // The identification and comment header each have their own pages
+    writer.packet(head, 0);
+    writer.flush();
+    writer.packet(&tags(metadata, input.language), 0);
+    writer.flush();
+    out.write_all(&writer.take())?;
+
+    let mut granule = 0;
+    let mut page_start = 0;
+    for i in 0..input.track.frames.len() {
+        let frame = input.track.frames[i];
+        let data = input.read(frame.offset, frame.len)?;
+
+        granule += samples(&data).ok_or(error::Mux::InvalidInput)?;
+        if granule - page_start > PAGE_DURATION {
+            writer.flush();
+            out.write_all(&writer.take())?;
+            page_start = granule;
+        }
+        writer.packet(&data, granule);
+    }
+
+    out.write_all(&writer.finish())?;
+    Ok(())
+}
+
+/// The `OpusTags` header with the Vorbis comments
+fn tags(metadata: &Metadata, language: Option<&str>) -> Vec<u8> {
+    let mut comments = Vec::new();
+    if let Some(title) = &metadata.title {
+        comments.push(format!("TITLE={}", title));
+    }
+    if let Some(artist) = &metadata.artist {
+        comments.push(format!("ARTIST={}", artist));
+    }
+    if let Some(date) = metadata.date {
+        comments.push(format!("DATE={}", date.format("%Y-%m-%d")));
+    }
+    if let Some(language) = language {
+        comments.push(format!("LANGUAGE={}", language));
+    }
+    for (number, chapter) in (1..).zip(&metadata.chapters) {
+        let start = chapter.start().as_millis();
+        comments.push(format!(
+            "CHAPTER{:03}={:02}:{:02}:{:02}.{:03}",
+            number,
+            start / 3_600_000,
+            start / 60_000 % 60,
+            start / 1000 % 60,
+            start % 1000
+        ));
+        comments.push(format!("CHAPTER{:03}NAME={}", number, chapter.title()));
+    }
+    if let Some(cover) = &metadata.cover {
+        comments.push(format!(
+            "METADATA_BLOCK_PICTURE={}",
+            base64::encode(picture(cover))
+        ));
+    }
+
+    let vendor = concat!(env!("CARGO_PKG_NAME"), " ", env!("CARGO_PKG_VERSION"));
+
+    let mut out = b"OpusTags".to_vec();
+    out.extend_from_slice(&(vendor.len() as u32).to_le_bytes());
+    out.extend_from_slice(vendor.as_bytes());
+    out.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+    for comment in comments {
+        out.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+        out.extend_from_slice(comment.as_bytes());
+    }
+    out
+}
+
+/// A FLAC picture block of the front cover
+fn picture(cover: &super::Cover) -> Vec<u8> {
+    let mime = cover.mime_type();
+
+    let mut out = Vec::new();
+    // Front cover
+    out.extend_from_slice(&3u32.to_be_bytes());
+    out.extend_from_slice(&(mime.len() as u32).to_be_bytes());
+    out.extend_from_slice(mime.as_bytes());
+    // Description, width, height, color depth and amount of colors
+    out.extend_from_slice(&[0; 20]);
+    out.extend_from_slice(&(cover.data().len() as u32).to_be_bytes());
+    out.extend_from_slice(cover.data());
+    out
+}
+
+/// The amount of samples at 48kHz in a Opus packet, determined by its TOC
+/// byte
+pub(super) fn samples(packet: &[u8]) -> Option<u64> {
+    let toc = *packet.first()?;
+    let config = toc >> 3;
+
+    // The frame size in units of 2.5ms (120 samples)
+    let frame_size = match config {
+        // SILK: 10, 20, 40 and 60ms
+        0..=11 => [4, 8, 16, 24][usize::from(config % 4)],
+        // Hybrid: 10 and 20ms
+        12..=15 => [4, 8][usize::from(config % 2)],
+        // CELT: 2.5, 5, 10 and 20ms
+        _ => [1, 2, 4, 8][usize::from(config % 4)],
+    };
+
+    let frames = match toc & 0x03 {
+        0 => 1,
+        1 | 2 => 2,
+        _ => u64::from(*packet.get(1)? & 0x3F),
+    };
+
+    Some(frames * frame_size * 120)
+}
@@ -6,7 +6,6 @@ pub use self::video::Video;
 
 use crate::{
     youtube::{
-        self,
         browse::{
             self,
             playlist::{PlaylistSidebarPrimaryInfoRenderer, PlaylistSidebarSecondaryInfoRenderer},
@@ -17,7 +16,7 @@ use crate::{
 };
 
 /// A Id describing a Playlist.
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub struct Id(pub(crate) String);
 
 impl std::str::FromStr for Id {
@@ -26,14 +25,16 @@ impl std::str::FromStr for Id {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         const ID_PREFIXES: &[&str] = &["PL", "RD", "UL", "UU", "PU", "OL", "LL", "FL", "WL"];
 
-        let id = youtube::strip_url_prefix(s)
-            .strip_prefix("playlist?list=")
-            .unwrap_or(s);
+        // Anything but a bare id is parsed as a URL
+        if !s.chars().all(crate::id::validate_char) {
+            return match crate::link::parse_id_url(s) {
+                Some(crate::link::YoutubeUrl::Playlist(id)) => Ok(id),
+                _ => Err(crate::error::Id::InvalidId(s.to_string())),
+            };
+        }
 
-        if id.chars().all(crate::id::validate_char)
-            && ID_PREFIXES.iter().any(|prefix| id.starts_with(prefix))
-        {
-            Ok(Self(id.to_string()))
+        if ID_PREFIXES.iter().any(|prefix| s.starts_with(prefix)) {
+            Ok(Self(s.to_string()))
         } else {
             Err(crate::error::Id::InvalidId(s.to_string()))
         }
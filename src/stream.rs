@@ -18,11 +18,23 @@
 
 mod audio;
 mod common;
+mod download;
+mod index;
+pub mod media_type;
+mod select;
 mod video;
 
-pub use self::audio::Stream as Audio;
-pub use self::common::Stream as Common;
-pub use self::video::Stream as Video;
+pub use self::audio::{AudioTrack, Stream as Audio};
+pub use self::common::{Probe, Stream as Common};
+pub use self::download::Download;
+pub(crate) use self::download::{backoff, is_retryable};
+pub use self::index::{Index, Segment};
+pub use self::media_type::MediaType;
+pub use self::select::{Query, Selection, Selector};
+pub use self::video::{
+    ColorInfo, ColorPrimaries, MatrixCoefficients, Projection, Stream as Video,
+    TransferCharacteristics,
+};
 use crate::{youtube::player_response::FormatType, Client};
 
 pub(crate) async fn get(
@@ -36,7 +48,26 @@ pub(crate) async fn get(
         .streaming_data
         .adaptive_formats
         .into_iter()
-        .map(move |stream| Stream::new(stream, client.clone())))
+        .map(move |stream| Stream::new(stream, id, client.clone())))
+}
+
+pub(crate) async fn audio_tracks(
+    client: Client,
+    id: crate::video::Id,
+) -> crate::Result<Vec<AudioTrack>> {
+    let mut tracks = Vec::new();
+
+    for stream in get(client, id).await? {
+        if let Stream::Audio(audio) = stream {
+            if let Some(track) = audio.audio_track() {
+                if !tracks.contains(track) {
+                    tracks.push(track.clone());
+                }
+            }
+        }
+    }
+
+    Ok(tracks)
 }
 
 /// A Stream of a YouTube video
@@ -49,22 +80,21 @@ pub enum Stream {
 }
 
 impl Stream {
-    pub(crate) fn new(format: crate::youtube::player_response::Format, client: Client) -> Self {
+    pub(crate) fn new(
+        format: crate::youtube::player_response::Format,
+        video_id: crate::video::Id,
+        client: Client,
+    ) -> Self {
+        let common = Common {
+            source: self::common::Source::new(format.base.url.clone()),
+            format: format.base,
+            video_id,
+            client,
+        };
+
         match format.ty {
-            FormatType::Audio(audio) => Self::Audio(Audio {
-                common: Common {
-                    format: format.base,
-                    client,
-                },
-                audio,
-            }),
-            FormatType::Video(video) => Self::Video(Video {
-                common: Common {
-                    format: format.base,
-                    client,
-                },
-                video,
-            }),
+            FormatType::Audio(audio) => Self::Audio(Audio { common, audio }),
+            FormatType::Video(video) => Self::Video(Video { common, video }),
         }
     }
 
@@ -26,10 +26,37 @@ impl Stream {
         self.audio.audio_channels
     }
 
+    /// The audio quality of a [`Stream`], e.g. `AUDIO_QUALITY_MEDIUM`
+    pub fn audio_quality(&self) -> &str {
+        &self.audio.audio_quality
+    }
+
+    /// The loudness of a [`Stream`] in dB
+    pub fn loudness_db(&self) -> Option<f64> {
+        self.audio.loudness_db
+    }
+
+    /// If a [`Stream`] had dynamic range compression applied
+    pub fn is_drc(&self) -> bool {
+        self.audio.is_drc
+    }
+
+    /// The [`AudioTrack`] of a [`Stream`].
+    ///
+    /// This is only available for videos with multiple audio tracks (e.g.
+    /// dubbed videos).
+    pub fn audio_track(&self) -> Option<&AudioTrack> {
+        self.audio.audio_track.as_ref()
+    }
+
     pub(super) fn debug(&self, debug: &mut std::fmt::DebugStruct<'_, '_>) {
         debug
             .field("sample_rate", &self.sample_rate())
-            .field("channels", &self.channels());
+            .field("channels", &self.channels())
+            .field("audio_quality", &self.audio_quality())
+            .field("loudness_db", &self.loudness_db())
+            .field("is_drc", &self.is_drc())
+            .field("audio_track", &self.audio_track());
     }
 }
 
@@ -41,3 +68,35 @@ impl std::fmt::Debug for Stream {
         debug.finish()
     }
 }
+
+/// A audio track of a video with multiple audio tracks (e.g. a dubbed video)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioTrack {
+    id: String,
+    display_name: String,
+    #[serde(default)]
+    audio_is_default: bool,
+}
+
+impl AudioTrack {
+    /// The id of a [`AudioTrack`], e.g. `en.4` or `de-DE.3`
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The language code of a [`AudioTrack`], e.g. `en` or `de-DE`
+    pub fn language(&self) -> &str {
+        self.id.split('.').next().unwrap_or(&self.id)
+    }
+
+    /// The human readable name of a [`AudioTrack`], e.g. `English original`
+    pub fn name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// If a [`AudioTrack`] is the default track of a video
+    pub fn is_default(&self) -> bool {
+        self.audio_is_default
+    }
+}
@@ -1,20 +1,136 @@
 use crate::{youtube::player_response::CommonFormat, Client};
 
-use reqwest::Url;
+use reqwest::{StatusCode, Url};
 
-use std::time::Duration;
+use std::{
+    ops::Range,
+    sync::{Arc, RwLock},
+    time::{Duration, SystemTime},
+};
+
+/// How long before the actual expiry a [`Url`] is already considered expired.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
 
 /// A [`Stream`](super::Stream) containing video or audio data.
 #[derive(Clone)]
 pub struct Stream {
     pub(super) format: CommonFormat,
+    pub(super) video_id: crate::video::Id,
+    pub(super) source: Arc<Source>,
     pub(super) client: Client,
 }
 
+/// The current [`Url`] of a [`Stream`], which is shared between all clones
+/// so that a refreshed [`Url`] is seen by all of them.
+pub(super) struct Source {
+    url: RwLock<Url>,
+    refresh: tokio::sync::Mutex<()>,
+}
+
+impl Source {
+    pub(super) fn new(url: Url) -> Arc<Self> {
+        Arc::new(Self {
+            url: RwLock::new(url),
+            refresh: tokio::sync::Mutex::new(()),
+        })
+    }
+}
+
 impl Stream {
+    /// The [itag](https://gist.github.com/AgentOak/34d47c65b1d28829bb17c24c04a0096f)
+    /// of a [`Stream`]
+    pub fn itag(&self) -> u64 {
+        self.format.itag
+    }
+
+    /// The [`Id`](crate::video::Id) of the [`Video`](crate::Video) this
+    /// [`Stream`] belongs to
+    pub fn video_id(&self) -> crate::video::Id {
+        self.video_id
+    }
+
     /// The [`Url`] of a [`Stream`]
+    ///
+    /// This [`Url`] changes once it expired and the [`Stream`] was refreshed
+    /// by one of the download functions.
     pub fn url(&self) -> Url {
-        self.format.url.clone()
+        self.source
+            .url
+            .read()
+            .expect("Stream url lock was poisoned")
+            .clone()
+    }
+
+    /// The point in time the [`Url`] of a [`Stream`] expires
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        let url = self.url();
+        let (_, expire) = url.query_pairs().find(|(key, _)| key == "expire")?;
+        let expire = expire.parse().ok()?;
+        Some(SystemTime::UNIX_EPOCH + Duration::from_secs(expire))
+    }
+
+    /// Returns `true` if the [`Url`] of a [`Stream`] expired or is about to
+    /// expire.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at()
+            .is_some_and(|expires_at| expires_at <= SystemTime::now() + EXPIRY_MARGIN)
+    }
+
+    /// Send a request to the [`Url`] of a [`Stream`].
+    ///
+    /// The [`Url`] is refreshed if it expired or YouTube responded with `403
+    /// Forbidden`.
+    pub(super) async fn send(
+        &self,
+        request: impl Fn(Url) -> reqwest::RequestBuilder,
+    ) -> crate::Result<reqwest::Response> {
+        if self.is_expired() {
+            log::warn!("Stream url of '{}' expired, refreshing...", self.video_id);
+            self.refresh(&self.url()).await?;
+        }
+
+        let url = self.url();
+        let response = request(url.clone()).send().await?;
+
+        if response.status() == StatusCode::FORBIDDEN {
+            log::warn!(
+                "Stream url of '{}' was forbidden, refreshing...",
+                self.video_id
+            );
+            self.refresh(&url).await?;
+            Ok(request(self.url()).send().await?.error_for_status()?)
+        } else {
+            Ok(response.error_for_status()?)
+        }
+    }
+
+    /// Re-request the [`Url`] of a [`Stream`] from YouTube, unless it was
+    /// already changed from `stale` by another request.
+    async fn refresh(&self, stale: &Url) -> crate::Result<()> {
+        let _guard = self.source.refresh.lock().await;
+
+        if self.url() != *stale {
+            return Ok(());
+        }
+
+        let format = self
+            .client
+            .api
+            .streams(self.video_id)
+            .await?
+            .streaming_data
+            .adaptive_formats
+            .into_iter()
+            .find(|x| x.base.itag == self.format.itag && x.base.xtags == self.format.xtags)
+            .ok_or(crate::error::Stream::Unavailable)?;
+
+        *self
+            .source
+            .url
+            .write()
+            .expect("Stream url lock was poisoned") = format.base.url;
+
+        Ok(())
     }
 
     /// The length of a [`Stream`] in bytes
@@ -22,19 +138,86 @@ impl Stream {
         if let Some(content_length) = self.format.content_length {
             Ok(content_length)
         } else {
-            let res = self
-                .client
-                .api
-                .http
-                .head(self.url())
-                .send()
-                .await?
-                .error_for_status()?;
-
-            Ok(res
-                .content_length()
-                .expect("HEAD request did not have a content-length"))
+            Ok(self.probe().await?.content_length())
+        }
+    }
+
+    /// Probe a [`Stream`] for its content length, last modification time
+    /// and whether it supports range requests.
+    ///
+    /// This sends a `HEAD` request and, if that fails or does not report a
+    /// content length, falls back to requesting the first byte and reading
+    /// the `Content-Range` header, or the `Content-Length` header if the
+    /// whole [`Stream`] was returned.
+    pub async fn probe(&self) -> crate::Result<Probe> {
+        use reqwest::header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, LAST_MODIFIED, RANGE};
+
+        fn header(res: &reqwest::Response, name: reqwest::header::HeaderName) -> Option<&str> {
+            res.headers().get(name)?.to_str().ok()
+        }
+
+        fn last_modified(res: &reqwest::Response) -> Option<chrono::DateTime<chrono::Utc>> {
+            header(res, LAST_MODIFIED)
+                .and_then(|x| chrono::DateTime::parse_from_rfc2822(x).ok())
+                .map(|x| x.with_timezone(&chrono::Utc))
+        }
+
+        fn content_length(res: &reqwest::Response) -> Option<u64> {
+            header(res, CONTENT_LENGTH)?.parse().ok()
+        }
+
+        let head = match self.send(|url| self.client.api.http.head(url)).await {
+            Ok(head) => {
+                let probe = Probe {
+                    content_length: 0,
+                    last_modified: last_modified(&head),
+                    accepts_ranges: header(&head, ACCEPT_RANGES) == Some("bytes"),
+                };
+
+                if let Some(content_length) = content_length(&head) {
+                    return Ok(Probe {
+                        content_length,
+                        ..probe
+                    });
+                }
+
+                log::warn!(
+                    "HEAD request did not have a content-length, requesting the first byte..."
+                );
+                Some(probe)
+            }
+            Err(err) => {
+                log::warn!(
+                    "HEAD request failed ({}), requesting the first byte...",
+                    err
+                );
+                None
+            }
+        };
+
+        let get = self
+            .send(|url| self.client.api.http.get(url).header(RANGE, "bytes=0-0"))
+            .await?;
+
+        let content_length = if get.status() == StatusCode::PARTIAL_CONTENT {
+            // `bytes 0-0/<LENGTH>`
+            header(&get, CONTENT_RANGE)
+                .and_then(|x| x.rsplit_once('/'))
+                .and_then(|(_, length)| length.parse().ok())
+        } else {
+            // The range was ignored and the whole stream is returned
+            content_length(&get)
         }
+        .ok_or(crate::error::Stream::MissingContentLength)?;
+
+        Ok(Probe {
+            content_length,
+            last_modified: head
+                .and_then(|x| x.last_modified)
+                .or_else(|| last_modified(&get)),
+            accepts_ranges: head.is_some_and(|x| x.accepts_ranges)
+                || get.status() == StatusCode::PARTIAL_CONTENT,
+        })
     }
 
     /// Get the [`Stream`] as a [`AsyncStream`](futures_core::Stream) of [`Bytes`](bytes::Bytes)
@@ -42,13 +225,8 @@ impl Stream {
         &self,
     ) -> crate::Result<impl futures_core::Stream<Item = Result<bytes::Bytes, reqwest::Error>>> {
         Ok(self
-            .client
-            .api
-            .http
-            .get(self.url())
-            .send()
+            .send(|url| self.client.api.http.get(url))
             .await?
-            .error_for_status()?
             .bytes_stream())
     }
 
@@ -57,20 +235,70 @@ impl Stream {
         &self.format.mime_type
     }
 
+    /// The parsed [`MediaType`](super::MediaType) of a [`Stream`]
+    pub fn media_type(&self) -> super::MediaType {
+        super::MediaType::from(self.mime_type())
+    }
+
     /// The bitrate of a [`Stream`]
     pub fn bitrate(&self) -> u64 {
         self.format.bitrate
     }
 
+    /// The average bitrate of a [`Stream`]
+    pub fn average_bitrate(&self) -> Option<u64> {
+        self.format.average_bitrate
+    }
+
     /// The [`Duration`] of a [`Stream`]
     pub fn duration(&self) -> Option<Duration> {
         self.format.duration
     }
 
+    /// The quality of a [`Stream`], e.g. `hd1080`, `medium` or `tiny`.
+    /// Empty if YouTube did not send it.
+    pub fn quality(&self) -> &str {
+        &self.format.quality
+    }
+
+    /// The byte range of the initialization segment of a [`Stream`]
+    pub fn init_range(&self) -> Option<Range<u64>> {
+        self.format.init_range.as_ref().map(|x| x.range())
+    }
+
+    /// The byte range of the segment index of a [`Stream`]
+    pub fn index_range(&self) -> Option<Range<u64>> {
+        self.format.index_range.as_ref().map(|x| x.range())
+    }
+
+    /// The date and time a [`Stream`] was last modified
+    pub fn last_modified(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        chrono::DateTime::from_timestamp_micros(self.format.last_modified?)
+    }
+
+    /// If a [`Stream`] is replicated on more servers, because it is
+    /// requested frequently
+    pub fn high_replication(&self) -> bool {
+        self.format.high_replication
+    }
+
+    /// The `xtags` of a [`Stream`], which further describe a variant (e.g.
+    /// a dubbed or DRC audio track)
+    pub fn xtags(&self) -> Option<&str> {
+        self.format.xtags.as_deref()
+    }
+
+    /// The DRM families a [`Stream`] is protected with, e.g. `WIDEVINE`
+    pub fn drm_families(&self) -> &[String] {
+        &self.format.drm_families
+    }
+
     pub(super) fn debug(&self, debug: &mut std::fmt::DebugStruct<'_, '_>) {
         debug
+            .field("itag", &self.itag())
             .field("url", &self.url())
             .field("mime_type", &self.mime_type())
+            .field("quality", &self.quality())
             .field("bitrate", &self.bitrate())
             .field("duration", &self.duration());
     }
@@ -83,3 +311,29 @@ impl std::fmt::Debug for Stream {
         debug.finish()
     }
 }
+
+/// The result of [probing](Stream::probe) a [`Stream`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Probe {
+    content_length: u64,
+    last_modified: Option<chrono::DateTime<chrono::Utc>>,
+    accepts_ranges: bool,
+}
+
+impl Probe {
+    /// The length of a [`Stream`] in bytes
+    pub fn content_length(&self) -> u64 {
+        self.content_length
+    }
+
+    /// The date and time a [`Stream`] was last modified, as reported by the
+    /// server
+    pub fn last_modified(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_modified
+    }
+
+    /// If the server accepts range requests for a [`Stream`]
+    pub fn accepts_ranges(&self) -> bool {
+        self.accepts_ranges
+    }
+}
@@ -0,0 +1,561 @@
+//! Segmented downloading of [`Streams`](super::Stream)
+
+use std::{
+    convert::{TryFrom, TryInto},
+    ops::Range,
+    path::Path,
+    time::Duration,
+};
+
+use futures_util::{StreamExt, TryStreamExt};
+use tokio::io::{AsyncSeekExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    container::{ebml, mp4},
+    error,
+};
+
+use super::media_type::Container;
+
+/// The time to wait before the first retry of a failed request. It doubles
+/// with every further retry.
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// The longest time to wait before retrying a failed request
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// The time to wait before the `retry`th retry of a failed request
+pub(crate) fn backoff(retry: u32) -> Duration {
+    RETRY_DELAY
+        .saturating_mul(2u32.saturating_pow(retry))
+        .min(MAX_RETRY_DELAY)
+}
+
+/// Whether a failed request could succeed when retried. Client errors other
+/// than `429 Too Many Requests` are permanent.
+pub(crate) fn is_retryable(err: &crate::Error) -> bool {
+    match err {
+        crate::Error::Request(err) => err.status().is_none_or(|status| {
+            !status.is_client_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        }),
+        _ => false,
+    }
+}
+
+/// Options for downloading a [`Stream`](super::Stream) over multiple
+/// connections.
+///
+/// The [`Stream`](super::Stream) is split into segments of
+/// [`segment_size`](Self::segment_size) bytes, of which at most
+/// [`connections`](Self::connections) are requested at the same time. This
+/// keeps the memory usage bounded to roughly `connections * segment_size`.
+///
+/// If the [`Url`](reqwest::Url) of the [`Stream`](super::Stream) expires
+/// during the download, it is refreshed and the download continues.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use ytextract::stream::Download;
+///
+/// let client = ytextract::Client::new();
+///
+/// let stream = client.streams("nI2e-J6fsuk".parse()?).await?.next().unwrap();
+///
+/// let mut data = Vec::new();
+/// stream.download(&mut data, Download::new().connections(8)).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Download {
+    connections: usize,
+    segment_size: u64,
+    retries: u32,
+}
+
+impl Default for Download {
+    fn default() -> Self {
+        Self {
+            connections: 4,
+            segment_size: 10 * 1024 * 1024,
+            retries: 5,
+        }
+    }
+}
+
+impl Download {
+    /// Create the default [`Download`] options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The amount of segments that are requested concurrently.
+    pub fn connections(mut self, connections: usize) -> Self {
+        self.connections = connections.max(1);
+        self
+    }
+
+    /// The size of a single segment in bytes.
+    pub fn segment_size(mut self, segment_size: u64) -> Self {
+        self.segment_size = segment_size.max(1);
+        self
+    }
+
+    /// How often a single failed segment is retried before giving up.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    fn segments(&self, range: Range<u64>) -> impl Iterator<Item = Range<u64>> {
+        let segment_size = self.segment_size;
+        let end = range.end;
+        range
+            .step_by(segment_size as usize)
+            .map(move |start| start..(start + segment_size).min(end))
+    }
+}
+
+impl super::Common {
+    /// Download a [`Stream`](super::Stream) into a [`AsyncWrite`] sink.
+    ///
+    /// The segments are requested concurrently, but written in order.
+    /// Returns the amount of bytes written.
+    pub async fn download<W: AsyncWrite + Unpin>(
+        &self,
+        writer: &mut W,
+        options: Download,
+    ) -> crate::Result<u64> {
+        let content_length = self.content_length().await?;
+
+        let mut segments = futures_util::stream::iter(options.segments(0..content_length))
+            .map(|range| self.segment(range, options.retries))
+            .buffered(options.connections);
+
+        while let Some(bytes) = segments.try_next().await? {
+            writer.write_all(&bytes).await?;
+        }
+        writer.flush().await?;
+
+        Ok(content_length)
+    }
+
+    /// Download a [`Stream`](super::Stream) into the file at `path`.
+    ///
+    /// The segments are written at their position in the file as soon as
+    /// they arrive. Returns the amount of bytes written.
+    pub async fn download_to_file(
+        &self,
+        path: impl AsRef<Path>,
+        options: Download,
+    ) -> crate::Result<u64> {
+        let file = tokio::fs::File::create(path).await?;
+        self.download_into_file(file, options).await
+    }
+
+    /// Download a [`Stream`](super::Stream) into the already opened `file`.
+    pub(crate) async fn download_into_file(
+        &self,
+        mut file: tokio::fs::File,
+        options: Download,
+    ) -> crate::Result<u64> {
+        let content_length = self.content_length().await?;
+        file.set_len(content_length).await?;
+
+        let mut segments = futures_util::stream::iter(options.segments(0..content_length))
+            .map(|range| async move {
+                let bytes = self.segment(range.clone(), options.retries).await?;
+                crate::Result::Ok((range.start, bytes))
+            })
+            .buffer_unordered(options.connections);
+
+        while let Some((offset, bytes)) = segments.try_next().await? {
+            file.seek(std::io::SeekFrom::Start(offset)).await?;
+            file.write_all(&bytes).await?;
+        }
+        file.flush().await?;
+
+        Ok(content_length)
+    }
+
+    /// Download the part of a [`Stream`](super::Stream) covering the time
+    /// `range` into a [`AsyncWrite`] sink.
+    ///
+    /// Only the init segment and the [`Segments`](super::Segment)
+    /// overlapping `range` are requested, and written as a fragmented MP4 or
+    /// WebM. The clip therefore starts at the last segment boundary before
+    /// `range.start` and ends at the first one after `range.end`. The
+    /// duration in the init segment is changed to the one of the clip.
+    ///
+    /// The segments are copied as they are, so the clip keeps the timestamps
+    /// of the whole [`Stream`](super::Stream) and its first frame is at the
+    /// start of its first segment, not at zero. Most players handle this,
+    /// but others may show a offset or a gap at the beginning.
+    ///
+    /// Returns the amount of bytes written.
+    pub async fn download_range<W: AsyncWrite + Unpin>(
+        &self,
+        range: Range<Duration>,
+        writer: &mut W,
+        options: Download,
+    ) -> crate::Result<u64> {
+        let index = self.index().await?;
+
+        let segments = index.segments_between(range);
+        let (bytes, duration) = match (segments.first(), segments.last()) {
+            (Some(first), Some(last)) => (
+                first.range().start..last.range().end,
+                last.end() - first.start(),
+            ),
+            _ => return Err(error::Stream::EmptyRange.into()),
+        };
+
+        let mut init = self
+            .segment(index.init_range(), options.retries)
+            .await?
+            .to_vec();
+        match self.media_type().container() {
+            Container::WebM => clip_webm_init(&mut init, duration),
+            _ => clip_mp4_init(&mut init, duration),
+        }
+        .ok_or(error::Stream::InvalidIndex)?;
+        writer.write_all(&init).await?;
+        let mut written = init.len() as u64;
+
+        let mut segments = futures_util::stream::iter(options.segments(bytes))
+            .map(|range| self.segment(range, options.retries))
+            .buffered(options.connections);
+
+        while let Some(bytes) = segments.try_next().await? {
+            writer.write_all(&bytes).await?;
+            written += bytes.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+
+    /// Request a single segment, retrying it up to `retries` times unless the
+    /// error is permanent.
+    async fn segment(&self, range: Range<u64>, retries: u32) -> crate::Result<bytes::Bytes> {
+        let mut retry = 0;
+
+        loop {
+            let err = match self.request_range(range.clone()).await {
+                Ok(bytes) if bytes.len() as u64 == range.end - range.start => break Ok(bytes),
+                Ok(bytes) => crate::Error::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!(
+                        "expected {} bytes but received {}",
+                        range.end - range.start,
+                        bytes.len()
+                    ),
+                )),
+                Err(err) => err,
+            };
+
+            // A short response may succeed when retried, like a failed request
+            let retryable = matches!(err, crate::Error::Io(_)) || is_retryable(&err);
+            if !retryable {
+                log::error!("Segment {:?} failed: {}. Stopping...", range, err);
+                break Err(err);
+            } else if retry == retries {
+                log::error!("Segment {:?} failed {} times. Stopping...", range, retries);
+                break Err(err);
+            } else {
+                log::warn!("Segment {:?} failed: {}, retrying...", range, err);
+                tokio::time::sleep(backoff(retry)).await;
+                retry += 1;
+            }
+        }
+    }
+
+    pub(super) async fn request_range(&self, range: Range<u64>) -> crate::Result<bytes::Bytes> {
+        let range = format!("bytes={}-{}", range.start, range.end - 1);
+
+        Ok(self
+            .send(|url| {
+                self.client
+                    .api
+                    .http
+                    .get(url)
+                    .header(reqwest::header::RANGE, &range)
+            })
+            .await?
+            .bytes()
+            .await?)
+    }
+}
+
+/// Prepare the init segment of a WebM [`Stream`](super::Stream) for a clip
+/// of `duration`.
+///
+/// The size of the `Segment` is changed to unknown and the `SeekHead` is
+/// replaced by a `Void` element, as both describe the whole
+/// [`Stream`](super::Stream). The `Duration` of the `Info` is overwritten in
+/// place, while the timecodes of the clusters are left unchanged.
+fn clip_webm_init(init: &mut [u8], duration: Duration) -> Option<()> {
+    let (segment, header_len) = ebml::segment(init)?;
+    let (_, id_len) = ebml::read_id(&init[segment..])?;
+    let size = ebml::encode_size(None, header_len - id_len)?;
+    init[segment + id_len..segment + header_len].copy_from_slice(&size);
+
+    let data = segment + header_len;
+    let seek_head = ebml::find(&init[data..], ebml::SEEK_HEAD)
+        .map(|x| (data + x.offset, x.header_len + x.data.len()));
+
+    if let Some((offset, len)) = seek_head {
+        // Keep the total length, so that the following offsets stay valid.
+        // The `Void` has a shorter id, so its size may need more bytes.
+        let size = (1..=8).find_map(|size_len| {
+            ebml::encode_size(Some((len - 1).checked_sub(size_len)? as u64), size_len)
+        })?;
+        init[offset] = ebml::VOID as u8;
+        init[offset + 1..offset + 1 + size.len()].copy_from_slice(&size);
+    }
+
+    let info = ebml::find(&init[data..], ebml::INFO)?;
+    let timecode_scale = info
+        .child(ebml::TIMECODE_SCALE)
+        .and_then(|x| x.uint())
+        .unwrap_or(1_000_000);
+    let field = info.child(ebml::DURATION).map(|x| {
+        (
+            data + info.offset + info.header_len + x.offset + x.header_len,
+            x.data.len(),
+        )
+    });
+
+    if let Some((offset, len)) = field {
+        // The `Duration` is a float in units of the `TimecodeScale`
+        let value = duration.as_nanos() as f64 / timecode_scale.max(1) as f64;
+        match len {
+            4 => init[offset..offset + 4].copy_from_slice(&(value as f32).to_be_bytes()),
+            8 => init[offset..offset + 8].copy_from_slice(&value.to_be_bytes()),
+            _ => return None,
+        }
+    }
+
+    Some(())
+}
+
+/// Prepare the init segment of a MP4 [`Stream`](super::Stream) for a clip of
+/// `duration`.
+///
+/// The durations of the `mvhd`, `mehd`, `tkhd` and `mdhd` boxes are
+/// overwritten in place, while the decode times of the fragments are left
+/// unchanged.
+fn clip_mp4_init(init: &mut [u8], duration: Duration) -> Option<()> {
+    /// The absolute offset of the payload of `child`, whose parent's payload
+    /// starts at `parent`
+    fn payload(parent: usize, child: &mp4::Mp4Box<'_>) -> usize {
+        parent + child.offset + child.header_len()
+    }
+
+    /// The absolute offset and length of a field of a full box, which is at
+    /// `v0` in a version 0 box and at `v1` in a version 1 box
+    fn field(
+        parent: usize,
+        full_box: &mp4::Mp4Box<'_>,
+        v0: usize,
+        v1: usize,
+    ) -> Option<(usize, usize)> {
+        let (position, len) = match full_box.data.first()? {
+            0 => (v0, 4),
+            1 => (v1, 8),
+            _ => return None,
+        };
+        full_box.data.get(position..position + len)?;
+        Some((payload(parent, full_box) + position, len))
+    }
+
+    /// The timescale of a `mvhd` or `mdhd` box
+    fn timescale(header: &mp4::Mp4Box<'_>) -> Option<u64> {
+        let mut reader = mp4::Reader::new(header.data);
+        let skip = if reader.u8()? == 0 { 11 } else { 19 };
+        reader.bytes(skip)?;
+        Some(u64::from(reader.u32()?))
+    }
+
+    /// `duration` in units of `1 / timescale` seconds
+    fn units(duration: Duration, timescale: u64) -> Option<u64> {
+        (duration.as_nanos() * u128::from(timescale) / 1_000_000_000)
+            .try_into()
+            .ok()
+    }
+
+    let moov = mp4::find(init, b"moov")?;
+    let moov_data = payload(0, &moov);
+    // The `(offset, length, value)` of every duration to overwrite
+    let mut fields = Vec::new();
+
+    let mvhd = moov.child(b"mvhd")?;
+    let movie_duration = units(duration, timescale(&mvhd)?)?;
+    fields.push((field(moov_data, &mvhd, 16, 24)?, movie_duration));
+
+    if let Some(mvex) = moov.child(b"mvex") {
+        if let Some(mehd) = mvex.child(b"mehd") {
+            fields.push((
+                field(payload(moov_data, &mvex), &mehd, 4, 4)?,
+                movie_duration,
+            ));
+        }
+    }
+
+    for trak in moov.children().filter(|x| &x.kind == b"trak") {
+        let trak_data = payload(moov_data, &trak);
+        if let Some(tkhd) = trak.child(b"tkhd") {
+            fields.push((field(trak_data, &tkhd, 20, 28)?, movie_duration));
+        }
+        if let Some(mdia) = trak.child(b"mdia") {
+            if let Some(mdhd) = mdia.child(b"mdhd") {
+                let media_duration = units(duration, timescale(&mdhd)?)?;
+                fields.push((
+                    field(payload(trak_data, &mdia), &mdhd, 16, 24)?,
+                    media_duration,
+                ));
+            }
+        }
+    }
+
+    for ((offset, len), value) in fields {
+        if len == 4 {
+            let value = u32::try_from(value).ok()?;
+            init[offset..offset + 4].copy_from_slice(&value.to_be_bytes());
+        } else {
+            init[offset..offset + 8].copy_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::container::{ebml, mp4};
+
+    /// The start of a WebM file of 60s, whose `Segment` and `SeekHead` have
+    /// 8 byte sizes
+    fn webm_init() -> Vec<u8> {
+        let mut init = Vec::new();
+        ebml::write_master(&mut init, ebml::EBML, |out| {
+            ebml::write_str(out, ebml::DOC_TYPE, "webm")
+        });
+
+        let mut segment = Vec::new();
+        let mut seek_head = Vec::new();
+        ebml::write_master(&mut seek_head, ebml::SEEK, |out| {
+            ebml::write(out, ebml::SEEK_ID, &ebml::INFO.to_be_bytes());
+            ebml::write_uint(out, ebml::SEEK_POSITION, 100);
+        });
+        ebml::write_id(&mut segment, ebml::SEEK_HEAD);
+        segment.extend(ebml::encode_size(Some(seek_head.len() as u64), 8).unwrap());
+        segment.extend(seek_head);
+        ebml::write_master(&mut segment, ebml::INFO, |out| {
+            ebml::write_uint(out, ebml::TIMECODE_SCALE, 1_000_000);
+            ebml::write_float(out, ebml::DURATION, 60_000.0);
+        });
+        ebml::write_master(&mut segment, ebml::TRACKS, |_| {});
+
+        ebml::write_id(&mut init, ebml::SEGMENT);
+        init.extend(ebml::encode_size(Some(1_000_000), 8).unwrap());
+        init.extend(segment);
+        init
+    }
+
+    #[test]
+    fn webm() {
+        let original = webm_init();
+        let mut init = original.clone();
+        super::clip_webm_init(&mut init, Duration::from_secs(30)).expect("Invalid init");
+
+        assert_eq!(init.len(), original.len());
+
+        let (segment, header_len) = ebml::segment(&init).expect("No segment");
+        assert_eq!(ebml::read_header(&init[segment..]).unwrap().1, None);
+
+        let children = ebml::elements(&init[segment + header_len..])
+            .map(|x| x.id)
+            .collect::<Vec<_>>();
+        assert_eq!(children, [ebml::VOID, ebml::INFO, ebml::TRACKS]);
+
+        let duration = ebml::find(&init[segment + header_len..], ebml::INFO)
+            .and_then(|x| x.child(ebml::DURATION))
+            .and_then(|x| x.float());
+        assert_eq!(duration, Some(30_000.0));
+    }
+
+    /// A `moov` box of 60s with a version 0 `mvhd`, `mehd` and `mdhd` and a
+    /// version 1 `tkhd`
+    fn mp4_init() -> Vec<u8> {
+        let mut init = Vec::new();
+        mp4::write_box(&mut init, b"ftyp", |out| out.extend_from_slice(b"dash"));
+        mp4::write_box(&mut init, b"moov", |out| {
+            mp4::write_full_box(out, b"mvhd", 0, 0, |out| {
+                out.extend_from_slice(&[0; 8]);
+                out.extend_from_slice(&1000u32.to_be_bytes());
+                out.extend_from_slice(&60_000u32.to_be_bytes());
+            });
+            mp4::write_box(out, b"mvex", |out| {
+                mp4::write_full_box(out, b"mehd", 0, 0, |out| {
+                    out.extend_from_slice(&60_000u32.to_be_bytes())
+                });
+            });
+            mp4::write_box(out, b"trak", |out| {
+                mp4::write_full_box(out, b"tkhd", 1, 3, |out| {
+                    out.extend_from_slice(&[0; 16]);
+                    out.extend_from_slice(&1u32.to_be_bytes());
+                    out.extend_from_slice(&[0; 4]);
+                    out.extend_from_slice(&60_000u64.to_be_bytes());
+                });
+                mp4::write_box(out, b"mdia", |out| {
+                    mp4::write_full_box(out, b"mdhd", 0, 0, |out| {
+                        out.extend_from_slice(&[0; 8]);
+                        out.extend_from_slice(&48_000u32.to_be_bytes());
+                        out.extend_from_slice(&(60 * 48_000u32).to_be_bytes());
+                    });
+                });
+            });
+        });
+        init
+    }
+
+    #[test]
+    fn mp4() {
+        let mut init = mp4_init();
+        super::clip_mp4_init(&mut init, Duration::from_secs(30)).expect("Invalid init");
+
+        let field = |data: &[u8], position: usize, len: usize| {
+            data[position..position + len]
+                .iter()
+                .fold(0, |acc, x| (acc << 8) | u64::from(*x))
+        };
+
+        let moov = mp4::find(&init, b"moov").expect("No moov");
+        let mvhd = moov.child(b"mvhd").unwrap();
+        assert_eq!(field(mvhd.data, 16, 4), 30_000);
+
+        let mehd = moov.child(b"mvex").and_then(|x| x.child(b"mehd")).unwrap();
+        assert_eq!(field(mehd.data, 4, 4), 30_000);
+
+        let trak = moov.child(b"trak").unwrap();
+        assert_eq!(field(trak.child(b"tkhd").unwrap().data, 28, 8), 30_000);
+
+        let mdhd = trak.child(b"mdia").and_then(|x| x.child(b"mdhd")).unwrap();
+        assert_eq!(field(mdhd.data, 16, 4), 30 * 48_000);
+    }
+
+    #[test]
+    fn mp4_overflow() {
+        // The duration does not fit into the version 0 boxes
+        let mut init = mp4_init();
+        assert_eq!(
+            super::clip_mp4_init(&mut init, Duration::from_secs(u64::from(u32::MAX))),
+            None
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Segment indices of [`Streams`](super::Stream) for seeking by time
+
+use std::{convert::TryInto, ops::Range, time::Duration};
+
+use crate::{
+    container::{ebml, mp4},
+    error,
+};
+
+use super::media_type::Container;
+
+/// The segment index of a [`Stream`](super::Stream), parsed from the MP4
+/// `sidx` box or the WebM `Cues`.
+///
+/// It maps the time of a [`Stream`](super::Stream) to byte ranges, which
+/// allows seeking and clipping without downloading the whole
+/// [`Stream`](super::Stream).
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+///
+/// let client = ytextract::Client::new();
+///
+/// let stream = client.streams("nI2e-J6fsuk".parse()?).await?.next().unwrap();
+/// let index = stream.index().await?;
+///
+/// let segment = index.segment_at(Duration::from_secs(10)).unwrap();
+/// println!("10s are in bytes {:?}", segment.range());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    init: Range<u64>,
+    segments: Vec<Segment>,
+}
+
+impl Index {
+    /// The byte range of the initialization segment, which has to precede
+    /// any [`Segment`] for it to be decodable.
+    pub fn init_range(&self) -> Range<u64> {
+        self.init.clone()
+    }
+
+    /// All [`Segments`](Segment) of a [`Index`] in order
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// The [`Segment`] containing `time`
+    pub fn segment_at(&self, time: Duration) -> Option<&Segment> {
+        let i = self.segments.partition_point(|x| x.end() <= time);
+        self.segments.get(i).filter(|x| x.start <= time)
+    }
+
+    /// The [`Segments`](Segment) overlapping the time `range`
+    pub fn segments_between(&self, range: Range<Duration>) -> &[Segment] {
+        let start = self.segments.partition_point(|x| x.end() <= range.start);
+        let end = self.segments.partition_point(|x| x.start < range.end);
+        &self.segments[start..end.max(start)]
+    }
+
+    /// Parse the `sidx` box of a MP4 [`Stream`](super::Stream). `index` is
+    /// the content of the `index` byte range, which starts at
+    /// `index_offset`.
+    ///
+    /// Returns [`None`] if the `sidx` box is missing or malformed.
+    fn from_sidx(init: Range<u64>, index: &[u8], index_offset: u64) -> Option<Self> {
+        let sidx = mp4::find(index, b"sidx")?;
+        let mut reader = mp4::Reader::new(sidx.data);
+
+        let version = reader.u8()?;
+        let _flags = reader.u24()?;
+        let _reference_id = reader.u32()?;
+        let timescale = u64::from(reader.u32()?);
+
+        let (mut time, first_offset) = if version == 0 {
+            (u64::from(reader.u32()?), u64::from(reader.u32()?))
+        } else {
+            (reader.u64()?, reader.u64()?)
+        };
+
+        let _reserved = reader.u16()?;
+        let reference_count = reader.u16()?;
+
+        // Offsets are relative to the first byte after the `sidx` box
+        let mut offset = index_offset
+            .checked_add((sidx.offset + sidx.size) as u64)?
+            .checked_add(first_offset)?;
+
+        let mut segments = Vec::with_capacity(reference_count.into());
+        for _ in 0..reference_count {
+            let size = u64::from(reader.u32()? & 0x7FFF_FFFF);
+            let duration = u64::from(reader.u32()?);
+            let _sap = reader.u32()?;
+
+            segments.push(Segment {
+                start: scale(time, timescale)?,
+                duration: scale(duration, timescale)?,
+                range: offset..offset.checked_add(size)?,
+            });
+
+            time = time.checked_add(duration)?;
+            offset += size;
+        }
+
+        Some(Self { init, segments })
+    }
+
+    /// Parse the `Cues` of a WebM [`Stream`](super::Stream). The `init`
+    /// data is needed for the position of the `Segment` and the
+    /// `TimecodeScale`, and the `duration` if the `Info` does not contain
+    /// it.
+    ///
+    /// Returns [`None`] if the `Cues` are missing or malformed.
+    fn from_cues(
+        init: Range<u64>,
+        init_data: &[u8],
+        index: &[u8],
+        content_length: u64,
+        duration: Option<Duration>,
+    ) -> Option<Self> {
+        let (segment_offset, header_len) = ebml::segment(init_data)?;
+        let segment_data = segment_offset + header_len;
+
+        let info = ebml::find(&init_data[segment_data..], ebml::INFO)?;
+        let timecode_scale = info
+            .child(ebml::TIMECODE_SCALE)
+            .and_then(|x| x.uint())
+            .unwrap_or(1_000_000);
+        // The `Duration` is a float in units of the `TimecodeScale`
+        let duration = info
+            .child(ebml::DURATION)
+            .and_then(|x| x.float())
+            .map(|x| Duration::from_nanos((x * timecode_scale as f64) as u64))
+            .or(duration);
+
+        let cues = ebml::find(index, ebml::CUES)?;
+        let points = cues
+            .children()
+            .filter(|x| x.id == ebml::CUE_POINT)
+            .map(|point| {
+                let time = point.child(ebml::CUE_TIME)?.uint()?;
+                let position = point
+                    .child(ebml::CUE_TRACK_POSITIONS)?
+                    .child(ebml::CUE_CLUSTER_POSITION)?
+                    .uint()?;
+
+                Some((
+                    Duration::from_nanos(time.checked_mul(timecode_scale)?),
+                    (segment_data as u64).checked_add(position)?,
+                ))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        let ends = points
+            .iter()
+            .skip(1)
+            .map(|x| Some(*x))
+            .chain(std::iter::once(
+                duration.map(|duration| (duration, content_length)),
+            ));
+
+        let segments = points
+            .iter()
+            .zip(ends)
+            .map(|(&(start, offset), end)| {
+                let (end, end_offset) = end?;
+                if end_offset < offset {
+                    return None;
+                }
+                Some(Segment {
+                    start,
+                    duration: end.checked_sub(start)?,
+                    range: offset..end_offset,
+                })
+            })
+            .collect::<Option<_>>()?;
+
+        Some(Self { init, segments })
+    }
+}
+
+/// A independently decodable segment of a [`Stream`](super::Stream)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    start: Duration,
+    duration: Duration,
+    range: Range<u64>,
+}
+
+impl Segment {
+    /// The time at which a [`Segment`] starts
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// The time at which a [`Segment`] ends
+    pub fn end(&self) -> Duration {
+        self.start + self.duration
+    }
+
+    /// The [`Duration`] of a [`Segment`]
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The byte range of a [`Segment`]
+    pub fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    /// The size of a [`Segment`] in bytes
+    pub fn size(&self) -> u64 {
+        self.range.end - self.range.start
+    }
+}
+
+/// Convert `value` in units of `1 / timescale` seconds into a [`Duration`]
+fn scale(value: u64, timescale: u64) -> Option<Duration> {
+    let nanos = u128::from(value) * 1_000_000_000 / u128::from(timescale).max(1);
+    Some(Duration::from_nanos(nanos.try_into().ok()?))
+}
+
+impl super::Common {
+    /// Fetch and parse the segment [`Index`] of a [`Stream`](super::Stream).
+    ///
+    /// This requires a [`init_range`](Self::init_range) and
+    /// [`index_range`](Self::index_range), which all adaptive streams have.
+    pub async fn index(&self) -> crate::Result<Index> {
+        let init = self.init_range().ok_or(error::Stream::MissingIndex)?;
+        let index = self.index_range().ok_or(error::Stream::MissingIndex)?;
+
+        // The index usually directly follows the init segment
+        let (init_data, index_data) = if init.end == index.start {
+            let data = self.request_range(init.start..index.end).await?;
+            let split = (init.end - init.start) as usize;
+            if data.len() < split {
+                return Err(error::Stream::InvalidIndex.into());
+            }
+            (data.slice(..split), data.slice(split..))
+        } else {
+            (
+                self.request_range(init.clone()).await?,
+                self.request_range(index.clone()).await?,
+            )
+        };
+
+        let parsed = match self.media_type().container() {
+            Container::Mp4 => Index::from_sidx(init, &index_data, index.start),
+            Container::WebM => Index::from_cues(
+                init,
+                &init_data,
+                &index_data,
+                self.content_length().await?,
+                self.duration(),
+            ),
+            other => return Err(error::Stream::UnsupportedContainer(other.clone()).into()),
+        };
+
+        Ok(parsed.ok_or(error::Stream::InvalidIndex)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Index;
+
+    /// A `sidx` box with version 1 and the `(size, duration)` of its
+    /// references
+    fn sidx(timescale: u32, time: u64, first_offset: u64, references: &[(u32, u32)]) -> Vec<u8> {
+        let mut payload = vec![1, 0, 0, 0, 0, 0, 0, 1];
+        payload.extend_from_slice(&timescale.to_be_bytes());
+        payload.extend_from_slice(&time.to_be_bytes());
+        payload.extend_from_slice(&first_offset.to_be_bytes());
+        payload.extend_from_slice(&[0, 0]);
+        payload.extend_from_slice(&(references.len() as u16).to_be_bytes());
+        for (size, duration) in references {
+            payload.extend_from_slice(&size.to_be_bytes());
+            payload.extend_from_slice(&duration.to_be_bytes());
+            payload.extend_from_slice(&0x9000_0000u32.to_be_bytes());
+        }
+
+        let mut sidx = ((payload.len() + 8) as u32).to_be_bytes().to_vec();
+        sidx.extend_from_slice(b"sidx");
+        sidx.extend_from_slice(&payload);
+        sidx
+    }
+
+    /// A EBML element with a payload shorter than 127 bytes
+    fn element(id: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut element = id.to_vec();
+        element.push(0x80 | payload.len() as u8);
+        element.extend_from_slice(payload);
+        element
+    }
+
+    /// The start of a WebM file with a duration of 5s
+    fn webm_init() -> Vec<u8> {
+        let mut init = element(&[0x1A, 0x45, 0xDF, 0xA3], &[]);
+        // A `Segment` of unknown size
+        init.extend_from_slice(&[0x18, 0x53, 0x80, 0x67, 0xFF]);
+
+        let mut info = element(&[0x2A, 0xD7, 0xB1], &1_000_000u64.to_be_bytes());
+        info.extend(element(&[0x44, 0x89], &5000f64.to_be_bytes()));
+        init.extend(element(&[0x15, 0x49, 0xA9, 0x66], &info));
+        init
+    }
+
+    /// `Cues` with the `(time, cluster position)` of their points
+    fn cues(points: &[(u64, u64)]) -> Vec<u8> {
+        let mut cues = Vec::new();
+        for (time, position) in points {
+            let mut positions = element(&[0xF7], &[1]);
+            positions.extend(element(&[0xF1], &position.to_be_bytes()));
+
+            let mut point = element(&[0xB3], &time.to_be_bytes());
+            point.extend(element(&[0xB7], &positions));
+            cues.extend(element(&[0xBB], &point));
+        }
+        element(&[0x1C, 0x53, 0xBB, 0x6B], &cues)
+    }
+
+    #[test]
+    fn mp4() {
+        let data = sidx(1000, 0, 10, &[(100, 2000), (200, 3000)]);
+        let index = Index::from_sidx(0..500, &data, 500).expect("Invalid index");
+
+        // Offsets start after the `sidx` box and its first offset
+        let start = 500 + data.len() as u64 + 10;
+        let segments = index.segments();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].range(), start..start + 100);
+        assert_eq!(segments[1].range(), start + 100..start + 300);
+        assert_eq!(segments[1].start(), Duration::from_secs(2));
+        assert_eq!(segments[1].duration(), Duration::from_secs(3));
+        assert_eq!(
+            index.segment_at(Duration::from_millis(2500)),
+            Some(&segments[1])
+        );
+        assert_eq!(index.segment_at(Duration::from_secs(5)), None);
+    }
+
+    #[test]
+    fn mp4_overflow() {
+        let data = sidx(1000, 0, u64::MAX, &[(100, 2000)]);
+        assert_eq!(Index::from_sidx(0..500, &data, 500), None);
+
+        let data = sidx(u32::MAX, u64::MAX - 1, 0, &[(100, 10), (100, 10)]);
+        assert_eq!(Index::from_sidx(0..500, &data, 500), None);
+
+        assert_eq!(Index::from_sidx(0..500, &data[..20], 500), None);
+    }
+
+    #[test]
+    fn webm() {
+        let init = webm_init();
+        let index = Index::from_cues(
+            0..init.len() as u64,
+            &init,
+            &cues(&[(0, 100), (2000, 400)]),
+            10_000,
+            None,
+        )
+        .expect("Invalid index");
+
+        // Positions are relative to the payload of the `Segment`
+        let segments = index.segments();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].range(), 110..410);
+        assert_eq!(segments[0].duration(), Duration::from_secs(2));
+        assert_eq!(segments[1].range(), 410..10_000);
+        assert_eq!(segments[1].duration(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn webm_overflow() {
+        let init = webm_init();
+        let parse = |points: &[(u64, u64)]| {
+            Index::from_cues(0..init.len() as u64, &init, &cues(points), 10_000, None)
+        };
+
+        assert_eq!(parse(&[(0, 100), (2000, u64::MAX)]), None);
+        assert_eq!(parse(&[(u64::MAX, 100)]), None);
+        // A cluster after the end of the stream
+        assert_eq!(parse(&[(0, 20_000)]), None);
+    }
+}
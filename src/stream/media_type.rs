@@ -0,0 +1,335 @@
+//! Parsed [mime types](https://en.wikipedia.org/wiki/Media_type) of streams
+
+use std::{convert::Infallible, fmt, str::FromStr};
+
+/// A parsed [mime type](https://en.wikipedia.org/wiki/Media_type) of a
+/// [`Stream`](super::Stream), e.g. `video/webm; codecs="vp9"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaType {
+    ty: String,
+    container: Container,
+    codecs: Vec<Codec>,
+}
+
+impl MediaType {
+    /// Returns `true` if this is a `audio/*` media type.
+    pub fn is_audio(&self) -> bool {
+        self.ty == "audio"
+    }
+
+    /// Returns `true` if this is a `video/*` media type.
+    pub fn is_video(&self) -> bool {
+        self.ty == "video"
+    }
+
+    /// The [`Container`] of a [`MediaType`]
+    pub fn container(&self) -> &Container {
+        &self.container
+    }
+
+    /// The [`Codecs`](Codec) of a [`MediaType`]
+    pub fn codecs(&self) -> &[Codec] {
+        &self.codecs
+    }
+
+    /// The file extension commonly used for this [`MediaType`], e.g. `mp4`,
+    /// `m4a` or `webm`.
+    pub fn extension(&self) -> &str {
+        match (&self.container, self.is_audio()) {
+            (Container::Mp4, true) => "m4a",
+            (Container::Mp4, false) => "mp4",
+            (Container::WebM, _) => "webm",
+            (Container::ThreeGp, _) => "3gp",
+            (Container::Unknown(other), _) => other,
+        }
+    }
+}
+
+impl FromStr for MediaType {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl From<&str> for MediaType {
+    fn from(s: &str) -> Self {
+        let (essence, parameters) = s.split_once(';').unwrap_or((s, ""));
+        let (ty, subtype) = essence.trim().split_once('/').unwrap_or((essence, ""));
+
+        let codecs = parameters
+            .split_once("codecs=")
+            .map(|(_, codecs)| codecs.trim().trim_matches('"'))
+            .into_iter()
+            .flat_map(|codecs| codecs.split(','))
+            .map(|codec| Codec::from(codec.trim()))
+            .collect();
+
+        Self {
+            ty: ty.to_string(),
+            container: Container::from(subtype),
+            codecs,
+        }
+    }
+}
+
+impl fmt::Display for MediaType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.ty, self.container)?;
+
+        if !self.codecs.is_empty() {
+            let codecs: Vec<_> = self.codecs.iter().map(Codec::to_string).collect();
+            write!(f, "; codecs=\"{}\"", codecs.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The container format of a [`MediaType`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Container {
+    /// [MPEG-4 Part 14](https://en.wikipedia.org/wiki/MPEG-4_Part_14)
+    Mp4,
+    /// [WebM](https://en.wikipedia.org/wiki/WebM)
+    WebM,
+    /// [3GP](https://en.wikipedia.org/wiki/3GP_and_3G2)
+    ThreeGp,
+    /// A container unknown to this library
+    Unknown(String),
+}
+
+impl FromStr for Container {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl From<&str> for Container {
+    fn from(s: &str) -> Self {
+        match s {
+            "mp4" => Self::Mp4,
+            "webm" => Self::WebM,
+            "3gpp" => Self::ThreeGp,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Container {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mp4 => f.write_str("mp4"),
+            Self::WebM => f.write_str("webm"),
+            Self::ThreeGp => f.write_str("3gpp"),
+            Self::Unknown(other) => f.write_str(other),
+        }
+    }
+}
+
+/// A codec of a [`MediaType`] together with its profile and level
+/// information, as described in [RFC 6381](https://datatracker.ietf.org/doc/html/rfc6381).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Codec {
+    /// H.264/AVC, e.g. `avc1.64001F`
+    Avc {
+        /// `true` for `avc3`, which carries its parameter sets in the
+        /// stream, `false` for `avc1`
+        avc3: bool,
+        /// The `profile_idc`, e.g. `0x64` for High
+        profile: u8,
+        /// The constraint set flags
+        constraints: u8,
+        /// The `level_idc`, e.g. `0x1F` for 3.1
+        level: u8,
+    },
+    /// AV1, e.g. `av01.0.08M.08`
+    Av1 {
+        /// The seq_profile, e.g. `0` for Main
+        profile: u8,
+        /// The seq_level_idx
+        level: u8,
+        /// `true` for the High tier, `false` for the Main tier
+        high_tier: bool,
+        /// The bit depth of a sample
+        bit_depth: u8,
+    },
+    /// VP9, e.g. `vp9` or `vp09.02.51.10`
+    Vp9 {
+        /// The profile
+        profile: Option<u8>,
+        /// The level multiplied by 10
+        level: Option<u8>,
+        /// The bit depth of a sample
+        bit_depth: Option<u8>,
+    },
+    /// VP8
+    Vp8,
+    /// MPEG-4 audio, e.g. `mp4a.40.2` (AAC-LC)
+    Mp4a {
+        /// The object type indication, `0x40` for MPEG-4 audio
+        object_type: u8,
+        /// The audio object type, e.g. `2` for AAC-LC or `5` for HE-AAC
+        audio_object_type: Option<u8>,
+    },
+    /// Opus
+    Opus,
+    /// Vorbis
+    Vorbis,
+    /// A codec unknown to this library
+    Unknown(String),
+}
+
+impl Codec {
+    /// The short name of a [`Codec`], e.g. `avc1`, `avc3`, `av01`, `vp9` or
+    /// `opus`.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Avc { avc3: false, .. } => "avc1",
+            Self::Avc { avc3: true, .. } => "avc3",
+            Self::Av1 { .. } => "av01",
+            Self::Vp9 { .. } => "vp9",
+            Self::Vp8 => "vp8",
+            Self::Mp4a { .. } => "mp4a",
+            Self::Opus => "opus",
+            Self::Vorbis => "vorbis",
+            Self::Unknown(other) => other.split('.').next().unwrap_or(other),
+        }
+    }
+
+    /// Returns `true` if this is a audio codec.
+    pub fn is_audio(&self) -> bool {
+        matches!(self, Self::Mp4a { .. } | Self::Opus | Self::Vorbis)
+    }
+
+    /// Returns `true` if this is a video codec.
+    pub fn is_video(&self) -> bool {
+        matches!(
+            self,
+            Self::Avc { .. } | Self::Av1 { .. } | Self::Vp9 { .. } | Self::Vp8
+        )
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split('.');
+        let name = parts.next()?;
+
+        let codec = match name {
+            "avc1" | "avc3" => {
+                let info = parts.next()?;
+                let byte = |i: usize| u8::from_str_radix(info.get(i..i + 2)?, 16).ok();
+                Self::Avc {
+                    avc3: name == "avc3",
+                    profile: byte(0)?,
+                    constraints: byte(2)?,
+                    level: byte(4)?,
+                }
+            }
+            "av01" => {
+                let profile = parts.next()?.parse().ok()?;
+                let level_tier = parts.next()?;
+                let (level, tier) = level_tier.split_at(level_tier.len().checked_sub(1)?);
+                Self::Av1 {
+                    profile,
+                    level: level.parse().ok()?,
+                    high_tier: match tier {
+                        "M" => false,
+                        "H" => true,
+                        _ => return None,
+                    },
+                    bit_depth: parts.next()?.parse().ok()?,
+                }
+            }
+            "vp9" => Self::Vp9 {
+                profile: None,
+                level: None,
+                bit_depth: None,
+            },
+            "vp09" => Self::Vp9 {
+                profile: Some(parts.next()?.parse().ok()?),
+                level: Some(parts.next()?.parse().ok()?),
+                bit_depth: Some(parts.next()?.parse().ok()?),
+            },
+            "vp8" | "vp08" => Self::Vp8,
+            "mp4a" => Self::Mp4a {
+                object_type: u8::from_str_radix(parts.next()?, 16).ok()?,
+                audio_object_type: match parts.next() {
+                    Some(aot) => Some(aot.parse().ok()?),
+                    None => None,
+                },
+            },
+            "opus" => Self::Opus,
+            "vorbis" => Self::Vorbis,
+            _ => return None,
+        };
+
+        Some(codec)
+    }
+}
+
+impl FromStr for Codec {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl From<&str> for Codec {
+    fn from(s: &str) -> Self {
+        Self::parse(s).unwrap_or_else(|| Self::Unknown(s.to_string()))
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Avc {
+                profile,
+                constraints,
+                level,
+                ..
+            } => write!(
+                f,
+                "{}.{:02X}{:02X}{:02X}",
+                self.name(),
+                profile,
+                constraints,
+                level
+            ),
+            Self::Av1 {
+                profile,
+                level,
+                high_tier,
+                bit_depth,
+            } => write!(
+                f,
+                "av01.{}.{:02}{}.{:02}",
+                profile,
+                level,
+                if *high_tier { 'H' } else { 'M' },
+                bit_depth
+            ),
+            Self::Vp9 {
+                profile: Some(profile),
+                level: Some(level),
+                bit_depth: Some(bit_depth),
+            } => write!(f, "vp09.{:02}.{:02}.{:02}", profile, level, bit_depth),
+            Self::Vp9 { .. } => f.write_str("vp9"),
+            Self::Vp8 => f.write_str("vp8"),
+            Self::Mp4a {
+                object_type,
+                audio_object_type: Some(aot),
+            } => write!(f, "mp4a.{:x}.{}", object_type, aot),
+            Self::Mp4a { object_type, .. } => write!(f, "mp4a.{:x}", object_type),
+            Self::Opus => f.write_str("opus"),
+            Self::Vorbis => f.write_str("vorbis"),
+            Self::Unknown(other) => f.write_str(other),
+        }
+    }
+}
@@ -0,0 +1,685 @@
+//! Selection and ranking of [`Streams`](super::Stream)
+
+use std::{cmp::Ordering, str::FromStr};
+
+use super::{media_type::Codec, Audio, MediaType, Stream, Video};
+use crate::error;
+
+/// Selects the best matching [`Video`] and/or [`Audio`] stream out of the
+/// [`Streams`](super::Stream) of a [`Video`](crate::Video).
+///
+/// A [`Selector`] consists of one or more alternatives, which are tried in
+/// order until one of them matches. Each alternative is made up of a
+/// [`Query`] for the video and/or a [`Query`] for the audio stream.
+///
+/// A [`Selector`] can also be parsed from a subset of
+/// [yt-dlp's format selection syntax](https://github.com/yt-dlp/yt-dlp#format-selection),
+/// e.g. `bestvideo[height<=1080][ext=mp4]+bestaudio/best`.
+///
+/// As YouTube only serves adaptive streams, `best` and `worst` select a
+/// video and a audio stream. Their filters apply to the stream they
+/// describe, while `ext` only applies to the video stream, because the
+/// audio of a `mp4` video is a `m4a` file. The `*` suffix (e.g. `bv*`) for
+/// streams that may also contain audio is not supported.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use ytextract::stream::{Query, Selector};
+///
+/// let client = ytextract::Client::new();
+///
+/// let streams = client.streams("nI2e-J6fsuk".parse()?).await?;
+///
+/// let selector = Selector::video_audio(
+///     Query::best().max_height(1080).codec("vp9"),
+///     Query::best().codec("opus"),
+/// );
+/// let selection = selector.select(streams).expect("No matching streams");
+///
+/// println!("{:#?}", selection);
+///
+/// let selector: Selector = "bestvideo[height<=720]+bestaudio/best".parse()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Selector {
+    alternatives: Vec<Choice>,
+}
+
+#[derive(Debug, Clone)]
+struct Choice {
+    video: Option<Query>,
+    audio: Option<Query>,
+}
+
+impl Default for Selector {
+    fn default() -> Self {
+        Self::video_audio(Query::best(), Query::best())
+    }
+}
+
+impl Selector {
+    /// Create a [`Selector`] that selects the best video and the best audio
+    /// stream.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a [`Selector`] that selects a video and a audio stream.
+    pub fn video_audio(video: Query, audio: Query) -> Self {
+        Self::from_choice(Some(video), Some(audio))
+    }
+
+    /// Create a [`Selector`] that only selects a video stream.
+    pub fn video_only(video: Query) -> Self {
+        Self::from_choice(Some(video), None)
+    }
+
+    /// Create a [`Selector`] that only selects a audio stream.
+    pub fn audio_only(audio: Query) -> Self {
+        Self::from_choice(None, Some(audio))
+    }
+
+    fn from_choice(video: Option<Query>, audio: Option<Query>) -> Self {
+        Self {
+            alternatives: vec![Choice { video, audio }],
+        }
+    }
+
+    /// Use `other` as a fallback if this [`Selector`] does not match.
+    pub fn or(mut self, other: Selector) -> Self {
+        self.alternatives.extend(other.alternatives);
+        self
+    }
+
+    /// Select the matching [`Streams`](Stream) out of `streams`.
+    ///
+    /// Returns [`None`] if no alternative matched.
+    pub fn select(&self, streams: impl IntoIterator<Item = Stream>) -> Option<Selection> {
+        let (videos, audios): (Vec<_>, Vec<_>) = streams.into_iter().partition(Stream::is_video);
+
+        let videos: Vec<_> = videos
+            .into_iter()
+            .filter_map(|stream| match stream {
+                Stream::Video(video) => Some(video),
+                Stream::Audio(_) => None,
+            })
+            .collect();
+        let audios: Vec<_> = audios
+            .into_iter()
+            .filter_map(|stream| match stream {
+                Stream::Audio(audio) => Some(audio),
+                Stream::Video(_) => None,
+            })
+            .collect();
+
+        self.alternatives.iter().find_map(|choice| {
+            let video = match &choice.video {
+                Some(query) => Some(query.pick(&videos, Properties::video)?.clone()),
+                None => None,
+            };
+            let audio = match &choice.audio {
+                Some(query) => Some(query.pick(&audios, Properties::audio)?.clone()),
+                None => None,
+            };
+
+            Some(Selection { video, audio })
+        })
+    }
+}
+
+impl FromStr for Selector {
+    type Err = error::Selector;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut alternatives = Vec::new();
+
+        for alternative in split_outside_brackets(s, '/') {
+            let mut video = None;
+            let mut audio = None;
+
+            for part in split_outside_brackets(alternative, '+') {
+                let part = part.trim();
+                let (name, filters) = part.split_at(part.find('[').unwrap_or(part.len()));
+
+                let (order, kind) = match name {
+                    "best" | "b" => (Order::Best, None),
+                    "worst" | "w" => (Order::Worst, None),
+                    "bestvideo" | "bv" => (Order::Best, Some(Kind::Video)),
+                    "worstvideo" | "wv" => (Order::Worst, Some(Kind::Video)),
+                    "bestaudio" | "ba" => (Order::Best, Some(Kind::Audio)),
+                    "worstaudio" | "wa" => (Order::Worst, Some(Kind::Audio)),
+                    "" => return Err(error::Selector::Empty),
+                    unknown => return Err(error::Selector::UnknownStream(unknown.to_string())),
+                };
+
+                let filters = parse_filters(filters)?;
+                let query = Query {
+                    order,
+                    ..Query::best()
+                };
+
+                match kind {
+                    Some(Kind::Video) => video = Some(query.with_filters(filters)),
+                    Some(Kind::Audio) => audio = Some(query.with_filters(filters)),
+                    // Only adaptive streams are available, so `best` is the
+                    // combination of the best video and audio stream. `ext`
+                    // stays with the video, as the audio has its own extension.
+                    None => {
+                        let (audio_filters, video_filters) =
+                            filters.into_iter().partition(|x| x.field.is_audio());
+                        video = Some(query.clone().with_filters(video_filters));
+                        audio = Some(query.with_filters(audio_filters));
+                    }
+                }
+            }
+
+            alternatives.push(Choice { video, audio });
+        }
+
+        Ok(Self { alternatives })
+    }
+}
+
+/// The [`Streams`](Stream) chosen by a [`Selector`].
+#[derive(Debug, Clone)]
+pub struct Selection {
+    video: Option<Video>,
+    audio: Option<Audio>,
+}
+
+impl Selection {
+    /// The selected [`Video`] stream.
+    pub fn video(&self) -> Option<&Video> {
+        self.video.as_ref()
+    }
+
+    /// The selected [`Audio`] stream.
+    pub fn audio(&self) -> Option<&Audio> {
+        self.audio.as_ref()
+    }
+
+    /// Split a [`Selection`] into its [`Video`] and [`Audio`] stream.
+    pub fn into_parts(self) -> (Option<Video>, Option<Audio>) {
+        (self.video, self.audio)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Order {
+    Best,
+    Worst,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Video,
+    Audio,
+}
+
+/// A query for a single [`Video`] or [`Audio`] stream used by a
+/// [`Selector`].
+///
+/// Constraints (e.g. [`max_height`](Self::max_height)) remove streams from
+/// the candidates, while preferences (e.g. [`codec`](Self::codec)) only
+/// influence the ranking of the remaining candidates.
+///
+/// Candidates are ranked by:
+///
+/// 1. The default [`AudioTrack`](super::AudioTrack) over other tracks
+/// 2. The preferred codecs, in the order they were added
+/// 3. The preferred containers, in the order they were added
+/// 4. The distance to the [`target_bitrate`](Self::target_bitrate)
+/// 5. The height, fps and bitrate for video and the bitrate and sample rate
+///    for audio. The highest wins for [`Query::best`] and the lowest for
+///    [`Query::worst`]
+#[derive(Debug, Clone)]
+pub struct Query {
+    order: Order,
+    filters: Vec<Filter>,
+    codecs: Vec<String>,
+    containers: Vec<String>,
+    target_bitrate: Option<u64>,
+}
+
+impl Query {
+    /// Query for the best stream.
+    pub fn best() -> Self {
+        Self {
+            order: Order::Best,
+            filters: Vec::new(),
+            codecs: Vec::new(),
+            containers: Vec::new(),
+            target_bitrate: None,
+        }
+    }
+
+    /// Query for the worst stream.
+    pub fn worst() -> Self {
+        Self {
+            order: Order::Worst,
+            ..Self::best()
+        }
+    }
+
+    fn with_filters(mut self, filters: Vec<Filter>) -> Self {
+        self.filters.extend(filters);
+        self
+    }
+
+    fn numeric(self, field: Field, op: Op, value: u64) -> Self {
+        self.with_filters(vec![Filter {
+            field,
+            op,
+            value: Value::Number(value as f64),
+        }])
+    }
+
+    /// Only match streams with a height of at most `height`.
+    pub fn max_height(self, height: u64) -> Self {
+        self.numeric(Field::Height, Op::LessEqual, height)
+    }
+
+    /// Only match streams with a height of at least `height`.
+    pub fn min_height(self, height: u64) -> Self {
+        self.numeric(Field::Height, Op::GreaterEqual, height)
+    }
+
+    /// Only match streams with a width of at most `width`.
+    pub fn max_width(self, width: u64) -> Self {
+        self.numeric(Field::Width, Op::LessEqual, width)
+    }
+
+    /// Only match streams with at most `fps` frames per second.
+    pub fn max_fps(self, fps: u64) -> Self {
+        self.numeric(Field::Fps, Op::LessEqual, fps)
+    }
+
+    /// Only match streams with at least `fps` frames per second.
+    pub fn min_fps(self, fps: u64) -> Self {
+        self.numeric(Field::Fps, Op::GreaterEqual, fps)
+    }
+
+    /// Only match streams with a bitrate of at most `bitrate`.
+    pub fn max_bitrate(self, bitrate: u64) -> Self {
+        self.numeric(Field::Bitrate, Op::LessEqual, bitrate)
+    }
+
+    /// Only match streams with a bitrate of at least `bitrate`.
+    pub fn min_bitrate(self, bitrate: u64) -> Self {
+        self.numeric(Field::Bitrate, Op::GreaterEqual, bitrate)
+    }
+
+    /// Only match streams that are (or are not) HDR.
+    pub fn hdr(self, hdr: bool) -> Self {
+        self.with_filters(vec![Filter {
+            field: Field::Hdr,
+            op: Op::Equal,
+            value: Value::Number(if hdr { 1.0 } else { 0.0 }),
+        }])
+    }
+
+    /// Prefer streams with the `codec`, e.g. `vp9`, `avc1` or `opus`.
+    ///
+    /// Codecs are matched case-insensitively by prefix, so `avc1` matches
+    /// `avc1.64001F`.
+    pub fn codec(mut self, codec: impl Into<String>) -> Self {
+        self.codecs.push(codec.into());
+        self
+    }
+
+    /// Prefer streams with the `container`, e.g. `mp4`, `m4a` or `webm`.
+    pub fn container(mut self, container: impl Into<String>) -> Self {
+        self.containers.push(container.into());
+        self
+    }
+
+    /// Only match audio streams with a [`AudioTrack`](super::AudioTrack) in
+    /// the `language`, e.g. `en` or `de-DE`.
+    ///
+    /// Streams of videos with only a single audio track always match. If
+    /// no language is requested, the default audio track is preferred.
+    pub fn language(self, language: impl Into<String>) -> Self {
+        self.with_filters(vec![Filter {
+            field: Field::Language,
+            op: Op::Equal,
+            value: Value::Text(language.into()),
+        }])
+    }
+
+    /// Prefer streams with a bitrate closest to `bitrate`.
+    pub fn target_bitrate(mut self, bitrate: u64) -> Self {
+        self.target_bitrate = Some(bitrate);
+        self
+    }
+
+    fn pick<'a, T>(&self, candidates: &'a [T], props: fn(&T) -> Properties) -> Option<&'a T> {
+        candidates
+            .iter()
+            .map(|x| (props(x), x))
+            .filter(|(props, _)| self.filters.iter().all(|filter| filter.matches(props)))
+            // `max_by` returns the last maximum, so iterate in reverse to
+            // prefer the first stream YouTube returned.
+            .rev()
+            .max_by(|(a, _), (b, _)| self.compare(a, b))
+            .map(|(_, x)| x)
+    }
+
+    /// Compare two candidates. The greater one is the better one.
+    fn compare(&self, a: &Properties, b: &Properties) -> Ordering {
+        fn rank(preferences: &[String], matches: impl Fn(&str) -> bool) -> usize {
+            preferences
+                .iter()
+                .position(|x| matches(x))
+                .unwrap_or(preferences.len())
+        }
+
+        let codec = |x: &Properties| {
+            rank(&self.codecs, |preference| {
+                // The profile of e.g. `avc1.64001F` is hex in either case
+                x.codec().is_some_and(|codec| {
+                    let preference = preference.to_ascii_lowercase();
+                    codec.name().eq_ignore_ascii_case(&preference)
+                        || codec
+                            .to_string()
+                            .to_ascii_lowercase()
+                            .starts_with(&preference)
+                })
+            })
+        };
+        let container = |x: &Properties| rank(&self.containers, |preference| x.ext() == preference);
+
+        let preference = a
+            .default_track
+            .cmp(&b.default_track)
+            .then_with(|| codec(b).cmp(&codec(a)))
+            .then_with(|| container(b).cmp(&container(a)))
+            .then_with(|| match self.target_bitrate {
+                Some(target) => {
+                    let distance = |x: &Properties| (x.bitrate as i64 - target as i64).abs();
+                    distance(b).cmp(&distance(a))
+                }
+                None => Ordering::Equal,
+            });
+
+        let quality = a
+            .height
+            .cmp(&b.height)
+            .then_with(|| a.fps.cmp(&b.fps))
+            .then_with(|| a.bitrate.cmp(&b.bitrate))
+            .then_with(|| a.sample_rate.cmp(&b.sample_rate));
+
+        preference.then(match self.order {
+            Order::Best => quality,
+            Order::Worst => quality.reverse(),
+        })
+    }
+}
+
+/// The properties of a stream relevant for selecting it.
+struct Properties {
+    media_type: MediaType,
+    height: Option<u64>,
+    width: Option<u64>,
+    fps: Option<u64>,
+    hdr: bool,
+    bitrate: u64,
+    sample_rate: Option<u64>,
+    channels: Option<u64>,
+    language: Option<String>,
+    default_track: bool,
+}
+
+impl Properties {
+    fn video(video: &Video) -> Self {
+        Self {
+            media_type: video.media_type(),
+            height: Some(video.height()),
+            width: Some(video.width()),
+            fps: Some(video.fps()),
+            hdr: video.is_hdr(),
+            bitrate: video.bitrate(),
+            sample_rate: None,
+            channels: None,
+            language: None,
+            default_track: true,
+        }
+    }
+
+    fn audio(audio: &Audio) -> Self {
+        Self {
+            media_type: audio.media_type(),
+            height: None,
+            width: None,
+            fps: None,
+            hdr: false,
+            bitrate: audio.bitrate(),
+            sample_rate: Some(audio.sample_rate()),
+            channels: Some(audio.channels()),
+            language: audio.audio_track().map(|x| x.language().to_string()),
+            default_track: audio.audio_track().is_none_or(|x| x.is_default()),
+        }
+    }
+
+    fn ext(&self) -> &str {
+        self.media_type.extension()
+    }
+
+    fn codec(&self) -> Option<&Codec> {
+        self.media_type.codecs().first()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Height,
+    Width,
+    Fps,
+    Hdr,
+    Bitrate,
+    AudioBitrate,
+    SampleRate,
+    Channels,
+    Ext,
+    VideoCodec,
+    AudioCodec,
+    Language,
+}
+
+impl Field {
+    fn is_audio(&self) -> bool {
+        matches!(
+            self,
+            Self::AudioBitrate
+                | Self::SampleRate
+                | Self::Channels
+                | Self::AudioCodec
+                | Self::Language
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    Number(f64),
+    Text(String),
+}
+
+#[derive(Debug, Clone)]
+struct Filter {
+    field: Field,
+    op: Op,
+    value: Value,
+}
+
+impl Filter {
+    fn matches(&self, props: &Properties) -> bool {
+        let number = match self.field {
+            Field::Height => props.height,
+            Field::Width => props.width,
+            Field::Fps => props.fps,
+            Field::Hdr => Some(props.hdr as u64),
+            Field::Bitrate => Some(props.bitrate),
+            Field::AudioBitrate => props.media_type.is_audio().then_some(props.bitrate),
+            Field::SampleRate => props.sample_rate,
+            Field::Channels => props.channels,
+            Field::Ext => return self.matches_text(props.ext(), false),
+            Field::VideoCodec if props.media_type.is_video() => {
+                return self.matches_codec(props.codec())
+            }
+            Field::AudioCodec if props.media_type.is_audio() => {
+                return self.matches_codec(props.codec())
+            }
+            Field::Language => match &props.language {
+                Some(language) => return self.matches_text(language, false),
+                None => None,
+            },
+            Field::VideoCodec | Field::AudioCodec => None,
+        };
+
+        match (number, &self.value) {
+            (Some(number), Value::Number(value)) => {
+                let number = number as f64;
+                match self.op {
+                    Op::Less => number < *value,
+                    Op::LessEqual => number <= *value,
+                    Op::Greater => number > *value,
+                    Op::GreaterEqual => number >= *value,
+                    Op::Equal => (number - value).abs() < f64::EPSILON,
+                    Op::NotEqual => (number - value).abs() >= f64::EPSILON,
+                    Op::StartsWith | Op::EndsWith | Op::Contains => false,
+                }
+            }
+            // The field does not apply to this kind of stream
+            (None, _) => true,
+            (Some(_), Value::Text(_)) => false,
+        }
+    }
+
+    /// Codecs are compared case-insensitively, as the hex digits of e.g.
+    /// `avc1.4D401F` can be in either case
+    fn matches_codec(&self, codec: Option<&Codec>) -> bool {
+        self.matches_text(&codec.map(Codec::to_string).unwrap_or_default(), true)
+    }
+
+    fn matches_text(&self, text: &str, ignore_case: bool) -> bool {
+        let value = match &self.value {
+            Value::Text(value) => value.as_str(),
+            Value::Number(_) => return false,
+        };
+
+        let (text, value) = if ignore_case {
+            (text.to_ascii_lowercase(), value.to_ascii_lowercase())
+        } else {
+            (text.to_string(), value.to_string())
+        };
+
+        match self.op {
+            Op::Equal => text == value,
+            Op::NotEqual => text != value,
+            Op::StartsWith => text.starts_with(&value),
+            Op::EndsWith => text.ends_with(&value),
+            Op::Contains => text.contains(&value),
+            Op::Less | Op::LessEqual | Op::Greater | Op::GreaterEqual => false,
+        }
+    }
+}
+
+/// Parse filters in the form of `[height<=1080][ext=mp4]`
+fn parse_filters(mut filters: &str) -> Result<Vec<Filter>, error::Selector> {
+    const OPS: &[(&str, Op)] = &[
+        ("<=", Op::LessEqual),
+        (">=", Op::GreaterEqual),
+        ("!=", Op::NotEqual),
+        ("^=", Op::StartsWith),
+        ("$=", Op::EndsWith),
+        ("*=", Op::Contains),
+        ("<", Op::Less),
+        (">", Op::Greater),
+        ("=", Op::Equal),
+    ];
+
+    let mut ret = Vec::new();
+
+    while !filters.is_empty() {
+        let invalid = || error::Selector::InvalidFilter(filters.to_string());
+
+        let end = filters.find(']').ok_or_else(invalid)?;
+        let filter = filters[..end].strip_prefix('[').ok_or_else(invalid)?;
+
+        let (position, (op_str, op)) = OPS
+            .iter()
+            .filter_map(|op| filter.find(op.0).map(|pos| (pos, op)))
+            .min_by_key(|(pos, op)| (*pos, std::cmp::Reverse(op.0.len())))
+            .ok_or_else(invalid)?;
+
+        let key = filter[..position].trim();
+        let value = filter[position + op_str.len()..].trim();
+
+        let (field, scale) = match key {
+            "height" => (Field::Height, 1.0),
+            "width" => (Field::Width, 1.0),
+            "fps" => (Field::Fps, 1.0),
+            // yt-dlp uses kbit/s
+            "tbr" | "vbr" => (Field::Bitrate, 1000.0),
+            "abr" => (Field::AudioBitrate, 1000.0),
+            "asr" => (Field::SampleRate, 1.0),
+            "audio_channels" => (Field::Channels, 1.0),
+            "ext" => (Field::Ext, 1.0),
+            "vcodec" => (Field::VideoCodec, 1.0),
+            "acodec" => (Field::AudioCodec, 1.0),
+            "language" => (Field::Language, 1.0),
+            "dynamic_range" => (Field::Hdr, 1.0),
+            _ => return Err(invalid()),
+        };
+
+        let value = match field {
+            Field::Ext | Field::VideoCodec | Field::AudioCodec | Field::Language => {
+                Value::Text(value.to_string())
+            }
+            Field::Hdr => Value::Number(match value {
+                "SDR" => 0.0,
+                _ if value.starts_with("HDR") => 1.0,
+                _ => return Err(invalid()),
+            }),
+            _ => Value::Number(value.parse::<f64>().map_err(|_| invalid())? * scale),
+        };
+
+        ret.push(Filter {
+            field,
+            op: *op,
+            value,
+        });
+        filters = &filters[end + 1..];
+    }
+
+    Ok(ret)
+}
+
+fn split_outside_brackets(s: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut depth = 0;
+    s.split(move |c| {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+        c == separator && depth == 0
+    })
+}
@@ -31,11 +31,35 @@ impl Stream {
         self.video.fps
     }
 
+    /// The quality label of a [`Stream`], e.g. `1080p60` or `2160p HDR`
+    pub fn quality_label(&self) -> &str {
+        &self.video.quality_label
+    }
+
+    /// The [`Projection`] of a [`Stream`]
+    pub fn projection(&self) -> Projection {
+        self.video.projection_type
+    }
+
+    /// The [`ColorInfo`] of a [`Stream`]
+    pub fn color_info(&self) -> Option<&ColorInfo> {
+        self.video.color_info.as_ref()
+    }
+
+    /// If a [`Stream`] has a high dynamic range
+    pub fn is_hdr(&self) -> bool {
+        self.color_info()
+            .map_or_else(|| self.quality_label().contains("HDR"), ColorInfo::is_hdr)
+    }
+
     pub(super) fn debug(&self, debug: &mut std::fmt::DebugStruct<'_, '_>) {
         debug
             .field("width", &self.width())
             .field("height", &self.height())
-            .field("fps", &self.fps());
+            .field("fps", &self.fps())
+            .field("quality_label", &self.quality_label())
+            .field("projection", &self.projection())
+            .field("color_info", &self.color_info());
     }
 }
 
@@ -47,3 +71,105 @@ impl std::fmt::Debug for Stream {
         debug.finish()
     }
 }
+
+/// The projection of a video [`Stream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[non_exhaustive]
+pub enum Projection {
+    /// A normal, flat video
+    #[default]
+    Rectangular,
+    /// A 360° video
+    Equirectangular,
+    /// A 360° stereoscopic video with the views on top of each other
+    #[serde(rename = "EQUIRECTANGULAR_THREED_TOP_BOTTOM")]
+    EquirectangularStereo,
+    /// A video with a custom projection mesh (e.g. VR180)
+    Mesh,
+    /// A projection unknown to this library
+    #[serde(other)]
+    Unknown,
+}
+
+/// The color information of a video [`Stream`]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorInfo {
+    primaries: Option<ColorPrimaries>,
+    transfer_characteristics: Option<TransferCharacteristics>,
+    matrix_coefficients: Option<MatrixCoefficients>,
+}
+
+impl ColorInfo {
+    /// The [`ColorPrimaries`] of a [`Stream`]
+    pub fn primaries(&self) -> Option<ColorPrimaries> {
+        self.primaries
+    }
+
+    /// The [`TransferCharacteristics`] of a [`Stream`]
+    pub fn transfer_characteristics(&self) -> Option<TransferCharacteristics> {
+        self.transfer_characteristics
+    }
+
+    /// The [`MatrixCoefficients`] of a [`Stream`]
+    pub fn matrix_coefficients(&self) -> Option<MatrixCoefficients> {
+        self.matrix_coefficients
+    }
+
+    /// If the transfer characteristics describe a high dynamic range
+    pub fn is_hdr(&self) -> bool {
+        matches!(
+            self.transfer_characteristics,
+            Some(TransferCharacteristics::Smpte2084 | TransferCharacteristics::AribStdB67)
+        )
+    }
+}
+
+/// The color primaries of a video [`Stream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub enum ColorPrimaries {
+    /// ITU-R BT.709
+    #[serde(rename = "COLOR_PRIMARIES_BT709")]
+    Bt709,
+    /// ITU-R BT.2020
+    #[serde(rename = "COLOR_PRIMARIES_BT2020")]
+    Bt2020,
+    /// Primaries unknown to this library
+    #[serde(other)]
+    Unknown,
+}
+
+/// The transfer characteristics of a video [`Stream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub enum TransferCharacteristics {
+    /// ITU-R BT.709
+    #[serde(rename = "COLOR_TRANSFER_CHARACTERISTICS_BT709")]
+    Bt709,
+    /// SMPTE ST 2084, also known as PQ (HDR10)
+    #[serde(rename = "COLOR_TRANSFER_CHARACTERISTICS_SMPTEST2084")]
+    Smpte2084,
+    /// ARIB STD-B67, also known as HLG
+    #[serde(rename = "COLOR_TRANSFER_CHARACTERISTICS_ARIB_STD_B67")]
+    AribStdB67,
+    /// Transfer characteristics unknown to this library
+    #[serde(other)]
+    Unknown,
+}
+
+/// The matrix coefficients of a video [`Stream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[non_exhaustive]
+pub enum MatrixCoefficients {
+    /// ITU-R BT.709
+    #[serde(rename = "COLOR_MATRIX_COEFFICIENTS_BT709")]
+    Bt709,
+    /// ITU-R BT.2020 non-constant luminance
+    #[serde(rename = "COLOR_MATRIX_COEFFICIENTS_BT2020_NCL")]
+    Bt2020Ncl,
+    /// Matrix coefficients unknown to this library
+    #[serde(other)]
+    Unknown,
+}
@@ -20,13 +20,28 @@
 //! # }
 //! ```
 
+pub mod captions;
+pub mod chapters;
+pub mod comment;
+pub mod description;
+pub mod live;
+pub mod live_chat;
+pub mod microformat;
 pub mod related;
+pub mod short;
+pub mod storyboard;
+pub mod transcript;
 
 use crate::{
-    youtube::{innertube::Next, next, parse_date, player_response::PlayerResponse},
+    youtube::{
+        innertube::Next,
+        next, parse_date,
+        player_response::{PlayerMicroformatRenderer, PlayerResponse},
+    },
     Client, Stream, Thumbnail,
 };
 
+use chrono::{DateTime, Utc};
 use std::time::Duration;
 
 /// A Video.
@@ -48,6 +63,12 @@ impl Video {
         })
     }
 
+    /// The [`Client`] a [`Video`] was requested with.
+    #[cfg(feature = "mux")]
+    pub(crate) fn client(&self) -> &Client {
+        &self.client
+    }
+
     /// The title of a [`Video`].
     pub fn title(&self) -> &str {
         &self.player_response.video_details.title
@@ -93,6 +114,22 @@ impl Video {
         &self.player_response.video_details.short_description
     }
 
+    /// The description of a [`Video`], split into text and
+    /// [`Segments`](description::Segment) for links, timestamps, hashtags
+    /// and mentions.
+    pub fn description_segments(&self) -> Vec<description::Segment> {
+        description::get(
+            self.initial_data
+                .contents
+                .two_column_watch_next_results
+                .results
+                .results
+                .secondary(),
+            self.id(),
+            self.description(),
+        )
+    }
+
     /// The amount of views a [`Video`] received.
     pub fn views(&self) -> u64 {
         self.player_response.video_details.view_count
@@ -121,18 +158,77 @@ impl Video {
     }
 
     /// If a [`Video`] is live (e.g. a Livestream) or if it was live in the
-    /// past.
+    /// past. See [`Video::live_status`] for details.
     pub fn live(&self) -> bool {
         self.player_response.video_details.is_live_content
     }
 
+    /// Whether a [`Video`] is a [Short](https://www.youtube.com/shorts).
+    ///
+    /// This is a heuristic, as YouTube only serves Shorts on the `shorts/`
+    /// page: a Short is at most 3 minutes long and its video is not wider
+    /// than it is tall. Use [`Video::short`] to request the metadata of the
+    /// `shorts/` page.
+    pub fn is_short(&self) -> bool {
+        let largest = self
+            .player_response
+            .streaming_data
+            .iter()
+            .flat_map(|x| &x.adaptive_formats)
+            .filter_map(|x| Some((x.width?, x.height?)))
+            .max_by_key(|(width, height)| width * height);
+
+        self.duration() <= Duration::from_secs(3 * 60)
+            && largest.is_some_and(|(width, height)| width <= height)
+    }
+
+    /// The [`Short`](short::Short) metadata of a [`Video`], like the sound
+    /// it uses. [`None`] if it is not a Short.
+    pub async fn short(&self) -> crate::Result<Option<short::Short>> {
+        short::Short::get(&self.client, self.id()).await
+    }
+
+    /// The [`LiveStatus`] of a [`Video`], e.g. whether it is an upcoming
+    /// livestream or premiere.
+    ///
+    /// The start and end of a livestream are part of the
+    /// [`Microformat`](microformat::Microformat), which is requested if the
+    /// [`Video`] is, was or will be live.
+    pub async fn live_status(&self) -> crate::Result<LiveStatus> {
+        let details = &self.player_response.video_details;
+        let microformat = if details.is_live_content || details.is_upcoming {
+            self.client.api.microformat(self.id()).await?
+        } else {
+            None
+        };
+
+        let primary = self
+            .initial_data
+            .contents
+            .two_column_watch_next_results
+            .results
+            .results
+            .primary();
+
+        Ok(LiveStatus::new(
+            &self.player_response,
+            microformat.as_ref(),
+            Some(primary),
+        ))
+    }
+
     /// The [`Thumbnails`](Thumbnail) of a [`Video`]
     pub fn thumbnails(&self) -> &Vec<Thumbnail> {
         &self.player_response.video_details.thumbnail.thumbnails
     }
 
-    /// The date a [`Video`] was published.
-    pub fn date(&self) -> chrono::NaiveDate {
+    /// The date a [`Video`] was published. [`None`] if it could not be
+    /// determined, e.g. for a relative date like `Started streaming 3 hours
+    /// ago` of a ongoing livestream.
+    ///
+    /// The exact time is available from
+    /// [`Microformat::publish_date`](microformat::Microformat::publish_date).
+    pub fn date(&self) -> Option<chrono::NaiveDate> {
         parse_date(
             &self
                 .initial_data
@@ -143,7 +239,87 @@ impl Video {
                 .primary()
                 .date_text,
         )
-        .expect("Unable to parse date")
+    }
+
+    /// The [`Microformat`](microformat::Microformat) metadata of a
+    /// [`Video`], like its category and exact publish date. It is requested
+    /// separately, as only the web player includes it.
+    pub async fn microformat(&self) -> crate::Result<Option<microformat::Microformat>> {
+        Ok(self
+            .client
+            .api
+            .microformat(self.id())
+            .await?
+            .map(microformat::Microformat))
+    }
+
+    /// The [`Storyboard`](storyboard::Storyboard) of a [`Video`], if it has
+    /// one.
+    pub fn storyboard(&self) -> Option<storyboard::Storyboard> {
+        let spec = &self
+            .player_response
+            .storyboards
+            .as_ref()?
+            .player_storyboard_spec_renderer
+            .as_ref()?
+            .spec;
+
+        storyboard::Storyboard::parse(spec, self.duration())
+    }
+
+    /// The [`CaptionTracks`](captions::CaptionTrack) of a [`Video`].
+    pub fn captions(&self) -> Vec<captions::CaptionTrack> {
+        self.player_response
+            .captions
+            .iter()
+            .flat_map(|x| x.player_captions_tracklist_renderer.caption_tracks.iter())
+            .map(|x| captions::CaptionTrack::new(x.clone(), self.client.clone()))
+            .collect()
+    }
+
+    /// The [`TranslationLanguages`](captions::TranslationLanguage) the
+    /// [`CaptionTracks`](captions::CaptionTrack) of a [`Video`] can be
+    /// [translated](captions::CaptionTrack::translate) into.
+    pub fn translation_languages(&self) -> Vec<captions::TranslationLanguage> {
+        self.player_response
+            .captions
+            .iter()
+            .flat_map(|x| {
+                x.player_captions_tracklist_renderer
+                    .translation_languages
+                    .iter()
+            })
+            .map(|x| captions::TranslationLanguage::new(x.clone()))
+            .collect()
+    }
+
+    /// The [`Chapters`](chapters::Chapter) of a [`Video`].
+    ///
+    /// If YouTube provides no chapters, they are parsed from the timestamps
+    /// in the [description](Self::description).
+    pub fn chapters(&self) -> Vec<chapters::Chapter> {
+        chapters::get(
+            self.initial_data.chapters(),
+            self.description(),
+            self.duration(),
+        )
+    }
+
+    /// The [`Transcript`](transcript::Transcript) of a [`Video`], if it has
+    /// one.
+    pub async fn transcript(&self) -> crate::Result<Option<transcript::Transcript>> {
+        let params = self
+            .initial_data
+            .engagement_panels
+            .iter()
+            .find_map(|x| x.transcript_params());
+
+        match params {
+            Some(params) => {
+                transcript::Transcript::get(self.client.clone(), params.to_string()).await
+            }
+            None => Ok(None),
+        }
     }
 
     /// The [`Items`](Related) related to a [`Video`].
@@ -197,10 +373,59 @@ impl Video {
         })
     }
 
+    /// The [`Comments`](comment::Comment) of a [`Video`] in the given
+    /// [`Sort`](comment::Sort) order. [`None`] if the comments are disabled.
+    ///
+    /// A failed request is yielded as an error and ends the stream, as is a
+    /// [`Sort`](comment::Sort) order that YouTube does not offer.
+    pub fn comments(
+        &self,
+        sort: comment::Sort,
+    ) -> Option<impl futures_core::Stream<Item = crate::Result<comment::Comment>>> {
+        let token = self
+            .initial_data
+            .contents
+            .two_column_watch_next_results
+            .results
+            .results
+            .comments()?
+            .get();
+
+        Some(comment::stream(self.client.clone(), token, Some(sort)))
+    }
+
+    /// The [`LiveChat`](live_chat::LiveChat) of a livestream, or its replay
+    /// once it has ended. [`None`] if a [`Video`] has no chat.
+    pub fn live_chat(&self) -> Option<live_chat::LiveChat> {
+        let renderer = self
+            .initial_data
+            .contents
+            .two_column_watch_next_results
+            .conversation_bar
+            .as_ref()?
+            .live_chat_renderer
+            .as_ref()?;
+        let continuation = renderer.continuations.iter().find_map(|x| x.data())?;
+
+        Some(live_chat::LiveChat::new(
+            self.client.clone(),
+            continuation.continuation.clone(),
+            renderer.is_replay,
+        ))
+    }
+
     /// The [`Streams`](Stream) of a [`Video`]
     pub async fn streams(&self) -> crate::Result<impl Iterator<Item = Stream>> {
         crate::stream::get(self.client.clone(), self.id()).await
     }
+
+    /// The [`AudioTracks`](crate::stream::AudioTrack) (e.g. dubbed
+    /// languages) available for a [`Video`].
+    ///
+    /// This is empty if a [`Video`] only has a single audio track.
+    pub async fn audio_tracks(&self) -> crate::Result<Vec<crate::stream::AudioTrack>> {
+        crate::stream::audio_tracks(self.client.clone(), self.id()).await
+    }
 }
 
 impl std::fmt::Debug for Video {
@@ -287,10 +512,7 @@ impl<'a> Eq for Channel<'a> {}
 define_id! {
     11,
     "An Id describing a [`Video`]",
-    [
-        "watch?v=",
-        "embed/",
-    ]
+    crate::link::YoutubeUrl::Video { id, .. } => id
 }
 
 /// A Item that is related to a [`Video`].
@@ -308,3 +530,82 @@ pub enum Related {
     /// A Radio
     Radio(related::Radio),
 }
+
+/// The livestream or premiere state of a [`Video`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiveStatus {
+    /// A regular video that never was live
+    NotLive,
+
+    /// An upcoming livestream or premiere
+    Upcoming {
+        /// The time it is scheduled to start, if it is known
+        scheduled_start: Option<DateTime<Utc>>,
+    },
+
+    /// A livestream or premiere that is currently live
+    Live {
+        /// The amount of concurrent viewers, if it is known
+        viewers: Option<u64>,
+        /// The time it started, if it is known
+        start: Option<DateTime<Utc>>,
+    },
+
+    /// A livestream or premiere that ended and was processed
+    WasLive {
+        /// The time it started, if it is known
+        start: Option<DateTime<Utc>>,
+        /// The time it ended, if it is known
+        end: Option<DateTime<Utc>>,
+    },
+
+    /// A livestream that ended recently and is still being processed
+    PostLive,
+}
+
+impl LiveStatus {
+    pub(crate) fn new(
+        player_response: &PlayerResponse,
+        microformat: Option<&PlayerMicroformatRenderer>,
+        primary: Option<&next::VideoPrimaryInfoRenderer>,
+    ) -> Self {
+        let details = &player_response.video_details;
+        let broadcast = microformat.and_then(|x| x.live_broadcast_details.as_ref());
+        let timestamp = |value: Option<&String>| {
+            value
+                .and_then(|x| microformat::parse_timestamp(x))
+                .map(|x| x.with_timezone(&Utc))
+        };
+        let start = timestamp(broadcast.and_then(|x| x.start_timestamp.as_ref()));
+
+        if details.is_upcoming {
+            // The offline slate of the player is only a fallback for the
+            // `upcomingEventData` of the watch page
+            let scheduled_start = primary
+                .and_then(|x| x.scheduled_start_time())
+                .or_else(|| {
+                    player_response
+                        .playability_status
+                        .as_ref()
+                        .and_then(|x| x.scheduled_start_time())
+                })
+                .and_then(|x| DateTime::from_timestamp(x, 0))
+                .or(start);
+            Self::Upcoming { scheduled_start }
+        } else if details.is_live || broadcast.is_some_and(|x| x.is_live_now) {
+            Self::Live {
+                viewers: primary.and_then(|x| x.viewers()),
+                start,
+            }
+        } else if details.is_post_live_dvr {
+            Self::PostLive
+        } else if details.is_live_content {
+            Self::WasLive {
+                start,
+                end: timestamp(broadcast.and_then(|x| x.end_timestamp.as_ref())),
+            }
+        } else {
+            Self::NotLive
+        }
+    }
+}
@@ -0,0 +1,643 @@
+//! Captions (subtitles) of a video.
+
+use quick_xml::events::{BytesStart, Event};
+use reqwest::Url;
+
+use std::time::Duration;
+
+use crate::{error, youtube::player_response, Client};
+
+/// A caption track of a [`Video`](super::Video).
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("nI2e-J6fsuk".parse()?).await?;
+/// let track = video.captions().into_iter().next().unwrap();
+///
+/// let captions = track.fetch().await?;
+/// println!("{}", captions.to_srt());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CaptionTrack {
+    url: Url,
+    name: String,
+    language: String,
+    kind: Kind,
+    translatable: bool,
+    client: Client,
+}
+
+impl CaptionTrack {
+    pub(crate) fn new(track: player_response::CaptionTrack, client: Client) -> Self {
+        Self {
+            url: track.base_url,
+            name: track.name.text(),
+            language: track.language_code,
+            kind: match track.kind.as_deref() {
+                Some("asr") => Kind::Asr,
+                _ => Kind::Manual,
+            },
+            translatable: track.is_translatable,
+            client,
+        }
+    }
+
+    /// The human readable name of a [`CaptionTrack`], e.g. `English` or
+    /// `English (auto-generated)`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The language code of a [`CaptionTrack`], e.g. `en` or `de-DE`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The [`Kind`] of a [`CaptionTrack`]
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Whether a [`CaptionTrack`] can be [translated](Self::translate)
+    pub fn is_translatable(&self) -> bool {
+        self.translatable
+    }
+
+    /// A [`CaptionTrack`] automatically translated into `language` by
+    /// YouTube. Returns [`None`] if the track is not translatable.
+    ///
+    /// The available languages can be found with
+    /// [`Video::translation_languages`](super::Video::translation_languages).
+    pub fn translate(&self, language: &str) -> Option<Self> {
+        if !self.translatable {
+            return None;
+        }
+
+        let mut track = self.clone();
+        track.url = with_query(&self.url, "tlang", Some(language));
+        track.language = language.to_string();
+        Some(track)
+    }
+
+    /// Fetch the [`Captions`] of a [`CaptionTrack`] in the
+    /// [`Json3`](Format::Json3) format.
+    pub async fn fetch(&self) -> crate::Result<Captions> {
+        self.fetch_format(Format::Json3).await
+    }
+
+    /// Fetch the [`Captions`] of a [`CaptionTrack`] in the given
+    /// [`Format`].
+    pub async fn fetch_format(&self, format: Format) -> crate::Result<Captions> {
+        let data = self
+            .client
+            .api
+            .http
+            .get(with_query(&self.url, "fmt", format.query()))
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Captions::parse(format, &data)
+    }
+}
+
+impl std::fmt::Debug for CaptionTrack {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CaptionTrack")
+            .field("name", &self.name)
+            .field("language", &self.language)
+            .field("kind", &self.kind)
+            .field("translatable", &self.translatable)
+            .finish()
+    }
+}
+
+impl PartialEq for CaptionTrack {
+    fn eq(&self, other: &Self) -> bool {
+        self.url == other.url
+    }
+}
+
+impl Eq for CaptionTrack {}
+
+/// Replace the `key` in the query of a `url` with `value`, or remove it
+fn with_query(url: &Url, key: &str, value: Option<&str>) -> Url {
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| k != key)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+
+    let mut url = url.clone();
+    {
+        let mut query = url.query_pairs_mut();
+        query.clear().extend_pairs(pairs);
+        if let Some(value) = value {
+            query.append_pair(key, value);
+        }
+    }
+    url
+}
+
+/// The kind of a [`CaptionTrack`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// Captions uploaded by the creator of a video
+    Manual,
+    /// Captions generated by automatic speech recognition
+    Asr,
+}
+
+/// A language a [`CaptionTrack`] can be translated into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranslationLanguage {
+    language: String,
+    name: String,
+}
+
+impl TranslationLanguage {
+    pub(crate) fn new(language: player_response::TranslationLanguage) -> Self {
+        Self {
+            language: language.language_code,
+            name: language.language_name.text(),
+        }
+    }
+
+    /// The language code of a [`TranslationLanguage`], e.g. `de`
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// The human readable name of a [`TranslationLanguage`], e.g. `German`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The formats of the timedtext API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Format {
+    /// JSON with word-level timing for automatically generated captions
+    #[default]
+    Json3,
+    /// XML with word-level timing for automatically generated captions
+    Srv3,
+    /// The legacy XML format without word-level timing
+    Xml,
+}
+
+impl Format {
+    fn query(&self) -> Option<&'static str> {
+        match self {
+            Self::Json3 => Some("json3"),
+            Self::Srv3 => Some("srv3"),
+            Self::Xml => None,
+        }
+    }
+}
+
+/// The parsed captions of a [`CaptionTrack`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Captions {
+    cues: Vec<Cue>,
+}
+
+impl Captions {
+    /// Parse captions in the given [`Format`]
+    pub fn parse(format: Format, data: &str) -> crate::Result<Self> {
+        let cues = match format {
+            Format::Json3 => json3(data),
+            Format::Srv3 | Format::Xml => xml(data),
+        }
+        .ok_or(error::Captions::InvalidFormat)?;
+
+        let mut cues: Vec<Cue> = cues
+            .into_iter()
+            .filter(|x| !x.text.trim().is_empty())
+            .collect();
+        cues.sort_by_key(|x| x.start);
+
+        // Cues without a duration last until the next one starts
+        for i in 0..cues.len() {
+            if cues[i].duration.is_zero() {
+                if let Some(next) = cues.get(i + 1).map(|x| x.start) {
+                    cues[i].duration = next.saturating_sub(cues[i].start);
+                }
+            }
+        }
+
+        Ok(Self { cues })
+    }
+
+    /// The [`Cues`](Cue) of [`Captions`], ordered by their start
+    pub fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    /// Convert [`Captions`] into the [SubRip](https://en.wikipedia.org/wiki/SubRip)
+    /// (`.srt`) format
+    pub fn to_srt(&self) -> String {
+        let mut out = String::new();
+        for (i, cue) in (1..).zip(&self.cues) {
+            out.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                i,
+                timestamp(cue.start(), ','),
+                timestamp(cue.end(), ','),
+                cue.text.trim()
+            ));
+        }
+        out
+    }
+
+    /// Convert [`Captions`] into the [WebVTT](https://www.w3.org/TR/webvtt1/)
+    /// (`.vtt`) format, including the timing of [`Words`](Word)
+    pub fn to_webvtt(&self) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        for cue in &self.cues {
+            out.push_str(&format!(
+                "{} --> {}\n",
+                timestamp(cue.start(), '.'),
+                timestamp(cue.end(), '.')
+            ));
+
+            if cue.words.len() > 1 {
+                for (i, word) in cue.words.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(&format!("<{}>", timestamp(word.start, '.')));
+                    }
+                    out.push_str(&format!("<c>{}</c>", escape(&word.text)));
+                }
+            } else {
+                out.push_str(&escape(cue.text.trim()));
+            }
+            out.push_str("\n\n");
+        }
+        out
+    }
+
+    /// Convert [`Captions`] into plain text, with one line per [`Cue`]
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for cue in &self.cues {
+            out.push_str(cue.text.trim());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// A single cue of [`Captions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    start: Duration,
+    duration: Duration,
+    text: String,
+    words: Vec<Word>,
+}
+
+impl Cue {
+    /// The time a [`Cue`] is shown
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// The time a [`Cue`] is hidden again
+    pub fn end(&self) -> Duration {
+        self.start.saturating_add(self.duration)
+    }
+
+    /// The duration a [`Cue`] is shown for
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The text of a [`Cue`], which can contain multiple lines
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The [`Words`](Word) of a [`Cue`] with their timing.
+    ///
+    /// This is empty if the captions have no word-level timing, which is
+    /// usually the case for [`Manual`](Kind::Manual) captions.
+    pub fn words(&self) -> &[Word] {
+        &self.words
+    }
+}
+
+/// A word of a [`Cue`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    start: Duration,
+    text: String,
+}
+
+impl Word {
+    /// The time a [`Word`] is spoken, relative to the start of the video
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// The text of a [`Word`], including its leading whitespace
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Build a [`Cue`] from its segments. `timed` is `true` if any segment
+/// had its own timing.
+fn cue(start: Duration, duration: Duration, segments: Vec<Word>, timed: bool) -> Cue {
+    let text = segments.iter().map(|x| x.text.as_str()).collect();
+    Cue {
+        start,
+        duration,
+        text,
+        words: if timed { segments } else { Vec::new() },
+    }
+}
+
+/// Parse the `json3` format
+fn json3(data: &str) -> Option<Vec<Cue>> {
+    #[derive(serde::Deserialize)]
+    struct Root {
+        #[serde(default)]
+        events: Vec<Event>,
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Event {
+        t_start_ms: u64,
+        #[serde(default)]
+        d_duration_ms: u64,
+        #[serde(default)]
+        segs: Vec<Segment>,
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Segment {
+        #[serde(default)]
+        utf8: String,
+        t_offset_ms: Option<u64>,
+    }
+
+    let root: Root = serde_json::from_str(data).ok()?;
+
+    Some(
+        root.events
+            .into_iter()
+            .map(|event| {
+                let start = Duration::from_millis(event.t_start_ms);
+                let timed = event.segs.iter().any(|x| x.t_offset_ms.is_some());
+                let words = event
+                    .segs
+                    .into_iter()
+                    .map(|segment| Word {
+                        start: start.saturating_add(Duration::from_millis(
+                            segment.t_offset_ms.unwrap_or(0),
+                        )),
+                        text: segment.utf8,
+                    })
+                    .collect();
+
+                cue(
+                    start,
+                    Duration::from_millis(event.d_duration_ms),
+                    words,
+                    timed,
+                )
+            })
+            .collect(),
+    )
+}
+
+/// Parse the `srv3` (`<p t="ms" d="ms"><s t="ms">`) and the legacy
+/// (`<text start="s" dur="s">`) XML formats
+fn xml(data: &str) -> Option<Vec<Cue>> {
+    /// The value of the attribute `name` as a [`Duration`]
+    fn time(element: &BytesStart<'_>, name: &str, seconds: bool) -> Option<Duration> {
+        let value = element.try_get_attribute(name).ok()??;
+        let value = value.unescape_value().ok()?;
+        if seconds {
+            Duration::try_from_secs_f64(value.parse().ok()?).ok()
+        } else {
+            Some(Duration::from_millis(value.parse().ok()?))
+        }
+    }
+
+    let mut reader = quick_xml::Reader::from_str(data);
+    let mut cues = Vec::new();
+
+    // The start, duration, segments and if it is the legacy format
+    let mut current: Option<(Duration, Duration, Vec<Word>, bool)> = None;
+    let mut timed = false;
+
+    loop {
+        match reader.read_event().ok()? {
+            Event::Start(element) => match element.name().as_ref() {
+                b"p" => {
+                    let start = time(&element, "t", false).unwrap_or_default();
+                    let duration = time(&element, "d", false).unwrap_or_default();
+                    current = Some((start, duration, Vec::new(), false));
+                    timed = false;
+                }
+                b"text" => {
+                    let start = time(&element, "start", true).unwrap_or_default();
+                    let duration = time(&element, "dur", true).unwrap_or_default();
+                    current = Some((start, duration, Vec::new(), true));
+                    timed = false;
+                }
+                b"s" => {
+                    if let Some((start, _, segments, _)) = &mut current {
+                        let offset = time(&element, "t", false);
+                        timed |= offset.is_some();
+                        segments.push(Word {
+                            start: start.saturating_add(offset.unwrap_or_default()),
+                            text: String::new(),
+                        });
+                    }
+                }
+                _ => {}
+            },
+            Event::Empty(element) if element.name().as_ref() == b"br" => {
+                if let Some((start, _, segments, _)) = &mut current {
+                    push(segments, *start, "\n");
+                }
+            }
+            Event::Text(text) => {
+                if let Some((start, _, segments, legacy)) = &mut current {
+                    let text = text.unescape().ok()?;
+                    // The legacy format escapes its content twice
+                    let text = if *legacy {
+                        quick_xml::escape::unescape(&text)
+                            .map(|x| x.into_owned())
+                            .unwrap_or_else(|_| text.into_owned())
+                    } else {
+                        text.into_owned()
+                    };
+                    push(segments, *start, &text);
+                }
+            }
+            Event::End(element) if matches!(element.name().as_ref(), b"p" | b"text") => {
+                if let Some((start, duration, segments, _)) = current.take() {
+                    cues.push(cue(start, duration, segments, timed));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Some(cues)
+}
+
+/// Append `text` to the last segment, or start the first one
+fn push(segments: &mut Vec<Word>, start: Duration, text: &str) {
+    match segments.last_mut() {
+        Some(segment) => segment.text.push_str(text),
+        None => segments.push(Word {
+            start,
+            text: text.to_string(),
+        }),
+    }
+}
+
+/// Format a timestamp as `HH:MM:SS<separator>mmm`
+fn timestamp(time: Duration, separator: char) -> String {
+    let millis = time.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+/// Escape the text of a WebVTT cue
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Captions, Cue, Format, Word};
+
+    fn word(millis: u64, text: &str) -> Word {
+        Word {
+            start: Duration::from_millis(millis),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn json3() {
+        // Out of order, without a duration and with an empty event
+        let data = r#"{"events": [
+            {"tStartMs": 3500, "dDurationMs": 1000, "segs": [{"utf8": "a < b & c"}]},
+            {"tStartMs": 1000, "segs": [{"utf8": "Hello"}, {"utf8": " world", "tOffsetMs": 500}]},
+            {"tStartMs": 2000, "dDurationMs": 100, "segs": [{"utf8": "\n"}]}
+        ]}"#;
+        let captions = Captions::parse(Format::Json3, data).unwrap();
+
+        let cues = captions.cues();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start(), Duration::from_millis(1000));
+        // The duration is filled until the next cue
+        assert_eq!(cues[0].end(), Duration::from_millis(3500));
+        assert_eq!(cues[0].text(), "Hello world");
+        assert_eq!(cues[0].words(), [word(1000, "Hello"), word(1500, " world")]);
+        assert_eq!(cues[1].duration(), Duration::from_millis(1000));
+        assert!(cues[1].words().is_empty());
+
+        assert_eq!(
+            captions.to_srt(),
+            "1\n00:00:01,000 --> 00:00:03,500\nHello world\n\n\
+             2\n00:00:03,500 --> 00:00:04,500\na < b & c\n\n"
+        );
+        assert_eq!(
+            captions.to_webvtt(),
+            "WEBVTT\n\n\
+             00:00:01.000 --> 00:00:03.500\n<c>Hello</c><00:00:01.500><c> world</c>\n\n\
+             00:00:03.500 --> 00:00:04.500\na &lt; b &amp; c\n\n"
+        );
+
+        assert!(Captions::parse(Format::Json3, "<timedtext/>").is_err());
+    }
+
+    #[test]
+    fn srv3() {
+        let data = r#"<?xml version="1.0" encoding="utf-8" ?><timedtext format="3"><body>
+            <p t="0" d="1500"><s>One</s><s t="700"> two</s></p>
+            <p t="2000" d="500">Line<br/>break &amp; more</p>
+        </body></timedtext>"#;
+        let captions = Captions::parse(Format::Srv3, data).unwrap();
+
+        let cues = captions.cues();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text(), "One two");
+        assert_eq!(cues[0].words(), [word(0, "One"), word(700, " two")]);
+        assert_eq!(cues[0].end(), Duration::from_millis(1500));
+        assert_eq!(cues[1].start(), Duration::from_millis(2000));
+        assert_eq!(cues[1].text(), "Line\nbreak & more");
+        assert!(cues[1].words().is_empty());
+    }
+
+    #[test]
+    fn legacy() {
+        let data = r#"<?xml version="1.0" encoding="utf-8" ?><transcript>
+            <text start="1.5" dur="2.25">It&amp;#39;s &amp;lt;b&amp;gt;</text>
+            <text start="4" dur="1">Next</text>
+        </transcript>"#;
+        let captions = Captions::parse(Format::Xml, data).unwrap();
+
+        let cues = captions.cues();
+        assert_eq!(cues.len(), 2);
+        // The text is escaped twice
+        assert_eq!(cues[0].text(), "It's <b>");
+        assert_eq!(cues[0].start(), Duration::from_millis(1500));
+        assert_eq!(cues[0].end(), Duration::from_millis(3750));
+        assert_eq!(cues[1].text(), "Next");
+        assert_eq!(captions.to_text(), "It's <b>\nNext\n");
+    }
+
+    #[test]
+    fn end() {
+        let cue = Cue {
+            start: Duration::MAX,
+            duration: Duration::from_secs(1),
+            text: String::new(),
+            words: Vec::new(),
+        };
+        assert_eq!(cue.end(), Duration::MAX);
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(
+            super::timestamp(Duration::from_millis(3_723_004), ','),
+            "01:02:03,004"
+        );
+        assert_eq!(super::timestamp(Duration::ZERO, '.'), "00:00:00.000");
+    }
+
+    #[test]
+    fn escape() {
+        assert_eq!(super::escape("<i>&</i>"), "&lt;i&gt;&amp;&lt;/i&gt;");
+    }
+}
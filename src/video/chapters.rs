@@ -0,0 +1,241 @@
+//! Chapters of a video.
+
+use std::{fmt::Write, time::Duration};
+
+use crate::{youtube::next::ChapterRenderer, Thumbnail};
+
+/// A chapter of a [`Video`](super::Video).
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use ytextract::video::chapters;
+///
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("nI2e-J6fsuk".parse()?).await?;
+/// let chapters = video.chapters();
+///
+/// for chapter in &chapters {
+///     println!("{:?}: {}", chapter.start(), chapter.title());
+/// }
+///
+/// let metadata = chapters::to_ffmetadata(&chapters);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chapter {
+    title: String,
+    start: Duration,
+    end: Duration,
+    thumbnails: Vec<Thumbnail>,
+}
+
+impl Chapter {
+    /// Create a [`Chapter`] from `start` to `end`
+    pub fn new(title: impl Into<String>, start: Duration, end: Duration) -> Self {
+        Self {
+            title: title.into(),
+            start,
+            end: end.max(start),
+            thumbnails: Vec::new(),
+        }
+    }
+
+    /// The title of a [`Chapter`]
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The time a [`Chapter`] starts
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// The time a [`Chapter`] ends, which is the start of the next one or
+    /// the end of the video
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+
+    /// The [`Thumbnails`](Thumbnail) of a [`Chapter`]. This is empty for
+    /// chapters parsed from the description.
+    pub fn thumbnails(&self) -> &[Thumbnail] {
+        &self.thumbnails
+    }
+}
+
+/// Build the [`Chapters`](Chapter) of a video with the given `duration` from
+/// the markers of YouTube, or from the timestamps in its `description`.
+pub(crate) fn get(
+    markers: Vec<ChapterRenderer>,
+    description: &str,
+    duration: Duration,
+) -> Vec<Chapter> {
+    let starts: Vec<(String, Duration, Vec<Thumbnail>)> = if markers.is_empty() {
+        description_chapters(description)
+    } else {
+        markers
+            .into_iter()
+            .map(|x| {
+                (
+                    x.title.simple_text,
+                    Duration::from_millis(x.time_range_start_millis),
+                    x.thumbnail.thumbnails,
+                )
+            })
+            .collect()
+    };
+
+    let ends: Vec<Duration> = starts
+        .iter()
+        .skip(1)
+        .map(|x| x.1)
+        .chain(std::iter::once(duration))
+        .collect();
+
+    starts
+        .into_iter()
+        .zip(ends)
+        .map(|((title, start, thumbnails), end)| Chapter {
+            title,
+            start,
+            end: end.max(start),
+            thumbnails,
+        })
+        .collect()
+}
+
+/// Parse chapters from the lines of a description that contain a timestamp.
+///
+/// Like YouTube, this requires the first chapter to start at `0:00`, at
+/// least two chapters and ascending timestamps.
+fn description_chapters(description: &str) -> Vec<(String, Duration, Vec<Thumbnail>)> {
+    let chapters: Vec<_> = description
+        .lines()
+        .filter_map(|line| {
+            let (index, start) = line
+                .split_whitespace()
+                .enumerate()
+                .find_map(|(i, token)| Some((i, timestamp(token)?)))?;
+
+            let title = line
+                .split_whitespace()
+                .enumerate()
+                .filter(|(i, _)| *i != index)
+                .map(|(_, x)| x)
+                .collect::<Vec<_>>()
+                .join(" ");
+            let title = title
+                .trim_matches(|c: char| c.is_whitespace() || "-–—|:".contains(c))
+                .to_string();
+
+            Some((title, start, Vec::new()))
+        })
+        .collect();
+
+    let valid = chapters.len() >= 2
+        && chapters[0].1.is_zero()
+        && chapters.windows(2).all(|x| x[0].1 < x[1].1);
+
+    if valid {
+        chapters
+    } else {
+        Vec::new()
+    }
+}
+
+/// Parse a timestamp like `1:23`, `01:23:45` or `(1:23)`
+fn timestamp(token: &str) -> Option<Duration> {
+    let token = token.trim_matches(|c| "()[]".contains(c));
+
+    let parts: Vec<&str> = token.split(':').collect();
+    if !(2..=3).contains(&parts.len())
+        || parts
+            .iter()
+            .any(|x| x.is_empty() || !x.bytes().all(|x| x.is_ascii_digit()))
+        || parts.iter().skip(1).any(|x| x.len() != 2)
+    {
+        return None;
+    }
+
+    let seconds = parts.iter().try_fold(0u64, |acc, x| {
+        acc.checked_mul(60)?.checked_add(x.parse::<u64>().ok()?)
+    })?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// Convert `chapters` into the
+/// [FFmpeg metadata](https://ffmpeg.org/ffmpeg-formats.html#Metadata-1)
+/// format, which can be added to a file with
+/// `ffmpeg -i <INPUT> -i <METADATA> -map_metadata 1 -codec copy <OUTPUT>`.
+pub fn to_ffmetadata(chapters: &[Chapter]) -> String {
+    /// Escape the special characters of a value
+    fn escape(value: &str) -> String {
+        let mut out = String::with_capacity(value.len());
+        for c in value.chars() {
+            if "=;#\\\n".contains(c) {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    let mut out = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        let _ = write!(
+            out,
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            chapter.start.as_millis(),
+            chapter.end.as_millis(),
+            escape(&chapter.title)
+        );
+    }
+    out
+}
+
+/// Convert `chapters` into a [cue sheet](https://en.wikipedia.org/wiki/Cue_sheet_(computing))
+/// for the audio `file`, with one track per chapter.
+pub fn to_cue_sheet(chapters: &[Chapter], file: &str) -> String {
+    /// Quote a value, as cue sheets have no escaping
+    fn quote(value: &str) -> String {
+        format!("\"{}\"", value.replace('"', "'"))
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "FILE {} WAVE", quote(file));
+    for (number, chapter) in (1..).zip(chapters) {
+        // The index is in minutes, seconds and frames of 1/75 seconds
+        let frames = chapter.start.as_millis() * 75 / 1000;
+        let _ = write!(
+            out,
+            "  TRACK {:02} AUDIO\n    TITLE {}\n    INDEX 01 {:02}:{:02}:{:02}\n",
+            number,
+            quote(&chapter.title),
+            frames / 75 / 60,
+            frames / 75 % 60,
+            frames % 75
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(
+            super::timestamp("(1:02:03)"),
+            Some(Duration::from_secs(3723))
+        );
+        assert_eq!(super::timestamp("1:2"), None);
+        // Descriptions are untrusted input
+        assert_eq!(super::timestamp("999999999999999999:00"), None);
+        assert_eq!(super::timestamp("18446744073709551615:00:00"), None);
+    }
+}
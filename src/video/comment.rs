@@ -0,0 +1,211 @@
+//! Comments of a video.
+
+use crate::{
+    youtube::{comments, innertube::Next, parse_subscribers},
+    Client,
+};
+
+/// The order of the comments of a [`Video`](super::Video)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Sort {
+    /// The most popular comments first
+    #[default]
+    Top,
+    /// The most recent comments first
+    Newest,
+}
+
+/// A comment of a [`Video`](super::Video), or a reply to one.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use futures::StreamExt;
+/// use ytextract::video::comment::Sort;
+///
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("nI2e-J6fsuk".parse()?).await?;
+/// let comments = video.comments(Sort::Newest).unwrap();
+/// futures::pin_mut!(comments);
+///
+/// while let Some(comment) = comments.next().await {
+///     let comment = comment?;
+///     println!("{}: {}", comment.author_name(), comment.text());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Comment {
+    renderer: comments::CommentRenderer,
+    replies: Option<String>,
+    client: Client,
+}
+
+impl Comment {
+    /// The id of a [`Comment`]
+    pub fn id(&self) -> &str {
+        &self.renderer.comment_id
+    }
+
+    /// The [`Id`](crate::channel::Id) of the [`Channel`](crate::Channel)
+    /// that wrote a [`Comment`]
+    pub fn author_id(&self) -> Option<crate::channel::Id> {
+        self.renderer
+            .author_endpoint
+            .as_ref()
+            .map(|x| x.browse_endpoint.browse_id)
+    }
+
+    /// The name of the [`Channel`](crate::Channel) that wrote a [`Comment`]
+    pub fn author_name(&self) -> String {
+        self.renderer
+            .author_text
+            .as_ref()
+            .map(|x| x.text())
+            .unwrap_or_default()
+    }
+
+    /// The runs of the text of a [`Comment`], which are split at
+    /// formatting, links and mentions
+    pub fn runs(&self) -> impl Iterator<Item = &str> {
+        self.renderer
+            .content_text
+            .runs
+            .iter()
+            .map(|x| x.text.as_str())
+    }
+
+    /// The text of a [`Comment`]
+    pub fn text(&self) -> String {
+        self.runs().collect()
+    }
+
+    /// The amount of likes a [`Comment`] received
+    pub fn likes(&self) -> u64 {
+        self.renderer
+            .vote_count
+            .as_ref()
+            .and_then(|x| parse_subscribers(&x.text().replace(',', "")))
+            .unwrap_or(0)
+    }
+
+    /// The time a [`Comment`] was published, relative to now, e.g.
+    /// `2 years ago`
+    pub fn published(&self) -> String {
+        self.renderer.published_time_text.text()
+    }
+
+    /// Whether a [`Comment`] was pinned by the creator of the video
+    pub fn is_pinned(&self) -> bool {
+        self.renderer.pinned_comment_badge.is_some()
+    }
+
+    /// Whether a [`Comment`] was hearted by the creator of the video
+    pub fn is_hearted(&self) -> bool {
+        self.renderer.hearted()
+    }
+
+    /// The amount of replies to a [`Comment`]
+    pub fn reply_count(&self) -> u64 {
+        self.renderer.reply_count
+    }
+
+    /// The replies to a [`Comment`]. [`None`] if it has no replies.
+    ///
+    /// A failed request is yielded as an error and ends the stream.
+    pub fn replies(&self) -> Option<impl futures_core::Stream<Item = crate::Result<Comment>>> {
+        Some(stream(self.client.clone(), self.replies.clone()?, None))
+    }
+}
+
+impl std::fmt::Debug for Comment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Comment")
+            .field("id", &self.id())
+            .field("author_id", &self.author_id())
+            .field("author_name", &self.author_name())
+            .field("text", &self.text())
+            .field("likes", &self.likes())
+            .field("published", &self.published())
+            .field("is_pinned", &self.is_pinned())
+            .field("is_hearted", &self.is_hearted())
+            .field("reply_count", &self.reply_count())
+            .finish()
+    }
+}
+
+impl PartialEq for Comment {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for Comment {}
+
+/// The [`Comments`](Comment) of the continuation `token`. If `sort` is
+/// given, the comments are reloaded in that order first.
+pub(crate) fn stream(
+    client: Client,
+    token: String,
+    sort: Option<Sort>,
+) -> impl futures_core::Stream<Item = crate::Result<Comment>> {
+    async_stream::stream! {
+        // The first page is sorted by `Top`
+        let mut sorted = sort.is_none_or(|x| x == Sort::Top);
+        let mut token = Some(token);
+
+        while let Some(current) = token.take() {
+            let response: comments::Root = match client.api.next(Next::Continuation(current)).await {
+                Ok(response) => response,
+                Err(err) => {
+                    yield Err(err);
+                    break;
+                }
+            };
+
+            for item in response.into_items() {
+                if let Some(header) = item.comments_header_renderer {
+                    if !sorted {
+                        sorted = true;
+                        match header.sort(1) {
+                            Some(newest) => {
+                                token = Some(newest);
+                                break;
+                            }
+                            None => {
+                                yield Err(crate::error::Comments::UnsupportedSort(Sort::Newest).into());
+                                return;
+                            }
+                        }
+                    }
+                } else if !sorted
+                    && (item.comment_thread_renderer.is_some() || item.comment_renderer.is_some())
+                {
+                    // The comments arrived without a header to sort them
+                    yield Err(crate::error::Comments::UnsupportedSort(Sort::Newest).into());
+                    return;
+                } else if let Some(thread) = item.comment_thread_renderer {
+                    let replies = thread.replies();
+                    if let Some(comment) = thread.comment {
+                        yield Ok(Comment {
+                            renderer: comment.comment_renderer,
+                            replies,
+                            client: client.clone(),
+                        });
+                    }
+                } else if let Some(renderer) = item.comment_renderer {
+                    yield Ok(Comment {
+                        renderer,
+                        replies: None,
+                        client: client.clone(),
+                    });
+                } else if let Some(continuation) = item.continuation_item_renderer {
+                    token = continuation.token();
+                }
+            }
+        }
+    }
+}
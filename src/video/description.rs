@@ -0,0 +1,338 @@
+//! The description of a video, split into text and links.
+
+use std::time::Duration;
+
+use crate::youtube::next::{DescriptionEndpoint, VideoSecondaryInfoRenderer};
+
+/// A segment of the description of a [`Video`](super::Video).
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use ytextract::video::description::Segment;
+///
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("nI2e-J6fsuk".parse()?).await?;
+///
+/// for segment in video.description_segments() {
+///     if let Segment::Url { url, .. } = segment {
+///         println!("{}", url);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    /// Plain text
+    Text(String),
+
+    /// A link to an external website. The `url` is unwrapped from the
+    /// redirect service of YouTube.
+    Url {
+        /// The text of the link, which may be shortened
+        text: String,
+        /// The target of the link
+        url: String,
+    },
+
+    /// A link to a time in the video itself, e.g. `1:23`
+    Timestamp {
+        /// The text of the link
+        text: String,
+        /// The time the link points to
+        time: Duration,
+    },
+
+    /// A hashtag, e.g. `#music`
+    Hashtag(String),
+
+    /// A mention of a [`Channel`](crate::Channel)
+    Channel {
+        /// The text of the mention, e.g. `@name`
+        text: String,
+        /// The [`Id`](crate::channel::Id) of the mentioned channel
+        id: crate::channel::Id,
+    },
+
+    /// A link to another [`Video`](super::Video)
+    Video {
+        /// The text of the link
+        text: String,
+        /// The [`Id`](super::Id) of the linked video
+        id: super::Id,
+        /// The time the link points to, if any
+        start: Option<Duration>,
+    },
+}
+
+impl Segment {
+    /// The text of a [`Segment`] as shown in the description
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Text(text) | Self::Hashtag(text) => text,
+            Self::Url { text, .. }
+            | Self::Timestamp { text, .. }
+            | Self::Channel { text, .. }
+            | Self::Video { text, .. } => text,
+        }
+    }
+
+    /// Classify a link with the given `text` to `endpoint`, found in the
+    /// description of the video `video`
+    fn link(text: String, endpoint: &DescriptionEndpoint, video: super::Id) -> Self {
+        if let Some(url) = &endpoint.url_endpoint {
+            return Self::Url {
+                text,
+                url: unwrap_redirect(&url.url),
+            };
+        }
+
+        if let Some(watch) = &endpoint.watch_endpoint {
+            if let Ok(id) = watch.video_id.parse::<super::Id>() {
+                let start = watch.start_time_seconds.map(Duration::from_secs);
+                return match start {
+                    Some(time) if id == video => Self::Timestamp { text, time },
+                    _ => Self::Video { text, id, start },
+                };
+            }
+        }
+
+        let url = endpoint
+            .command_metadata
+            .as_ref()
+            .and_then(|x| x.web_command_metadata.url.as_deref());
+
+        if let Some(browse) = &endpoint.browse_endpoint {
+            if let Ok(id) = browse.browse_id.parse() {
+                return Self::Channel { text, id };
+            }
+
+            let hashtag = browse.browse_id == "FEhashtag"
+                || browse
+                    .canonical_base_url
+                    .as_deref()
+                    .or(url)
+                    .is_some_and(|x| x.starts_with("/hashtag/"));
+            if hashtag {
+                return Self::Hashtag(text.trim().to_string());
+            }
+        }
+
+        match url {
+            Some(url) => Self::Url {
+                text,
+                url: unwrap_redirect(url),
+            },
+            None => Self::Text(text),
+        }
+    }
+}
+
+/// Build the [`Segments`](Segment) of the description of the video `video`.
+/// Falls back to the plain `description` if YouTube did not send a
+/// structured one.
+pub(crate) fn get(
+    renderer: &VideoSecondaryInfoRenderer,
+    video: super::Id,
+    description: &str,
+) -> Vec<Segment> {
+    let mut segments = Vec::new();
+
+    if let Some(attributed) = &renderer.attributed_description {
+        // The runs index into the content in UTF-16 code units
+        let content: Vec<u16> = attributed.content.encode_utf16().collect();
+        let slice = |start: usize, end: usize| {
+            String::from_utf16_lossy(&content[start.min(content.len())..end.min(content.len())])
+        };
+
+        let mut runs: Vec<_> = attributed.command_runs.iter().collect();
+        runs.sort_by_key(|x| x.start_index);
+
+        let mut position = 0;
+        for run in runs {
+            let endpoint = match &run.on_tap {
+                Some(x) if run.start_index >= position => &x.innertube_command,
+                _ => continue,
+            };
+
+            push(
+                &mut segments,
+                Segment::Text(slice(position, run.start_index)),
+            );
+            position = run.start_index.saturating_add(run.length);
+            let text = slice(run.start_index, position);
+            push(&mut segments, Segment::link(text, endpoint, video));
+        }
+        push(&mut segments, Segment::Text(slice(position, content.len())));
+    } else if let Some(description) = &renderer.description {
+        for run in &description.runs {
+            let segment = match &run.navigation_endpoint {
+                Some(endpoint) => Segment::link(run.text.clone(), endpoint, video),
+                None => Segment::Text(run.text.clone()),
+            };
+            push(&mut segments, segment);
+        }
+    } else {
+        push(&mut segments, Segment::Text(description.to_string()));
+    }
+
+    segments
+}
+
+/// Push `segment`, merging adjacent text and skipping empty text
+fn push(segments: &mut Vec<Segment>, segment: Segment) {
+    match (segments.last_mut(), segment) {
+        (_, Segment::Text(text)) if text.is_empty() => {}
+        (Some(Segment::Text(last)), Segment::Text(text)) => last.push_str(&text),
+        (_, segment) => segments.push(segment),
+    }
+}
+
+/// Unwrap a `https://www.youtube.com/redirect?q=<URL>` link to its target,
+/// and make links relative to YouTube absolute
+fn unwrap_redirect(url: &str) -> String {
+    let base = url::Url::parse("https://www.youtube.com").expect("Invalid base URL");
+    let parsed = match base.join(url) {
+        Ok(parsed) => parsed,
+        Err(_) => return url.to_string(),
+    };
+
+    let is_youtube = parsed
+        .host_str()
+        .is_some_and(|x| x == "youtube.com" || x.ends_with(".youtube.com"));
+    if is_youtube && parsed.path() == "/redirect" {
+        if let Some((_, target)) = parsed.query_pairs().find(|(key, _)| key == "q") {
+            return target.into_owned();
+        }
+    }
+
+    parsed.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Segment;
+
+    #[test]
+    fn get() {
+        let renderer = serde_json::json!({
+            "owner": { "videoOwnerRenderer": { "thumbnail": { "thumbnails": [] } } },
+            "metadataRowContainer": {
+                "metadataRowContainerRenderer": { "collapsedItemCount": 0 }
+            },
+            // The indices count UTF-16 code units, so the emojis count twice
+            "attributedDescription": {
+                "content": "😀 1:23 link #music @name clip 🎵end",
+                "commandRuns": [
+                    {
+                        "startIndex": 26,
+                        "length": 4,
+                        "onTap": { "innertubeCommand": {
+                            "watchEndpoint": { "videoId": "jNQXAC9IVRw", "startTimeSeconds": 5 }
+                        } }
+                    },
+                    {
+                        "startIndex": 3,
+                        "length": 4,
+                        "onTap": { "innertubeCommand": {
+                            "watchEndpoint": { "videoId": "dQw4w9WgXcQ", "startTimeSeconds": 83 }
+                        } }
+                    },
+                    {
+                        "startIndex": 8,
+                        "length": 4,
+                        "onTap": { "innertubeCommand": {
+                            "urlEndpoint": {
+                                "url": "https://www.youtube.com/redirect?event=video_description&q=https%3A%2F%2Fexample.com%2F%3Fa%3D1"
+                            }
+                        } }
+                    },
+                    // Overlaps the previous run
+                    {
+                        "startIndex": 9,
+                        "length": 2,
+                        "onTap": { "innertubeCommand": { "urlEndpoint": { "url": "/about" } } }
+                    },
+                    {
+                        "startIndex": 13,
+                        "length": 6,
+                        "onTap": { "innertubeCommand": {
+                            "browseEndpoint": { "browseId": "FEhashtag" }
+                        } }
+                    },
+                    {
+                        "startIndex": 20,
+                        "length": 5,
+                        "onTap": { "innertubeCommand": {
+                            "browseEndpoint": { "browseId": "UCuAXFkgsw1L7xaCfnd5JJOw" }
+                        } }
+                    },
+                    // Out of bounds and overflowing
+                    {
+                        "startIndex": usize::MAX - 1,
+                        "length": 5,
+                        "onTap": { "innertubeCommand": {} }
+                    }
+                ]
+            }
+        });
+        let renderer = serde_json::from_value(renderer).unwrap();
+
+        let segments = super::get(&renderer, "dQw4w9WgXcQ".parse().unwrap(), "");
+        assert_eq!(
+            segments,
+            [
+                Segment::Text("😀 ".to_string()),
+                Segment::Timestamp {
+                    text: "1:23".to_string(),
+                    time: Duration::from_secs(83)
+                },
+                Segment::Text(" ".to_string()),
+                Segment::Url {
+                    text: "link".to_string(),
+                    url: "https://example.com/?a=1".to_string()
+                },
+                Segment::Text(" ".to_string()),
+                Segment::Hashtag("#music".to_string()),
+                Segment::Text(" ".to_string()),
+                Segment::Channel {
+                    text: "@name".to_string(),
+                    id: "UCuAXFkgsw1L7xaCfnd5JJOw".parse().unwrap()
+                },
+                Segment::Text(" ".to_string()),
+                Segment::Video {
+                    text: "clip".to_string(),
+                    id: "jNQXAC9IVRw".parse().unwrap(),
+                    start: Some(Duration::from_secs(5))
+                },
+                Segment::Text(" 🎵end".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unwrap_redirect() {
+        assert_eq!(
+            super::unwrap_redirect("https://www.youtube.com/redirect?q=https%3A%2F%2Fexample.com"),
+            "https://example.com"
+        );
+        // Only redirects of YouTube are unwrapped
+        assert_eq!(
+            super::unwrap_redirect("https://example.com/redirect?q=https%3A%2F%2Fexample.org"),
+            "https://example.com/redirect?q=https%3A%2F%2Fexample.org"
+        );
+        assert_eq!(
+            super::unwrap_redirect("/redirect?q=https%3A%2F%2Fexample.com"),
+            "https://example.com"
+        );
+        assert_eq!(
+            super::unwrap_redirect("/watch?v=jNQXAC9IVRw"),
+            "https://www.youtube.com/watch?v=jNQXAC9IVRw"
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! Waiting for upcoming livestreams and premieres to go live, and recording
+//! them.
+
+mod hls;
+mod record;
+
+pub use self::record::{Record, Recording};
+
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+use reqwest::Url;
+
+use super::LiveStatus;
+use crate::{
+    stream::{backoff, is_retryable},
+    youtube::player_response::PlayerStreamingData,
+    Client,
+};
+
+/// How often a failed request is retried in a row before the stream ends
+const RETRIES: u32 = 5;
+
+/// The shortest time to wait between two polls
+const MIN_INTERVAL: Duration = Duration::from_secs(15);
+
+/// The longest time to wait between two polls before a scheduled start
+const MAX_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// An event reported while waiting for a [`Video`](super::Video) to go live.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use futures::StreamExt;
+/// use ytextract::video::live::Event;
+///
+/// let client = ytextract::Client::new();
+///
+/// let events = client.wait_for_live("jfKfPfyJRdk".parse()?);
+/// futures::pin_mut!(events);
+///
+/// while let Some(event) = events.next().await {
+///     match event? {
+///         Event::Status(status) => println!("Status: {:?}", status),
+///         Event::Rescheduled(start) => println!("Rescheduled to {:?}", start),
+///         Event::Available(available) => println!("HLS: {:?}", available.hls_manifest_url()),
+///         Event::Cancelled(reason) => println!("Cancelled: {:?}", reason),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// The [`LiveStatus`] changed. This is always the first event.
+    ///
+    /// The amount of viewers and the start of [`LiveStatus::Live`] are not
+    /// known here.
+    Status(LiveStatus),
+
+    /// The scheduled start of an upcoming livestream or premiere changed
+    Rescheduled(Option<DateTime<Utc>>),
+
+    /// The [`Streams`](crate::Stream) became available. This is the last
+    /// event.
+    Available(Availability),
+
+    /// The livestream or premiere was cancelled, either because it was
+    /// removed with the given reason or because it was turned into a regular
+    /// video without [`Streams`](crate::Stream). This is the last event.
+    Cancelled(Option<crate::error::Youtube>),
+}
+
+/// The streams that are available for a [`Video`](super::Video) that went
+/// live
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Availability {
+    hls_manifest_url: Option<Url>,
+    dash_manifest_url: Option<Url>,
+    adaptive_formats: bool,
+}
+
+impl Availability {
+    fn new(streaming_data: &PlayerStreamingData) -> Option<Self> {
+        let availability = Self {
+            hls_manifest_url: streaming_data.hls_manifest_url.clone(),
+            dash_manifest_url: streaming_data.dash_manifest_url.clone(),
+            adaptive_formats: !streaming_data.adaptive_formats.is_empty(),
+        };
+
+        let available = availability.hls_manifest_url.is_some()
+            || availability.dash_manifest_url.is_some()
+            || availability.adaptive_formats;
+        available.then_some(availability)
+    }
+
+    /// The URL of the HLS manifest of a livestream
+    pub fn hls_manifest_url(&self) -> Option<&Url> {
+        self.hls_manifest_url.as_ref()
+    }
+
+    /// The URL of the DASH manifest of a livestream
+    pub fn dash_manifest_url(&self) -> Option<&Url> {
+        self.dash_manifest_url.as_ref()
+    }
+
+    /// Whether adaptive [`Streams`](crate::Stream) are available, which can
+    /// be requested with [`Client::streams`]
+    pub fn has_adaptive_formats(&self) -> bool {
+        self.adaptive_formats
+    }
+}
+
+/// Poll the player of the video `id` until its streams are available.
+/// Failed requests are yielded and retried with a backoff up to [`RETRIES`]
+/// times, while client errors end the stream.
+pub(crate) fn wait(
+    client: Client,
+    id: super::Id,
+) -> impl futures_core::Stream<Item = crate::Result<Event>> {
+    async_stream::stream! {
+        let mut status: Option<LiveStatus> = None;
+        let mut retry = 0;
+        let mut polls = 0u32;
+
+        loop {
+            let player_response = match client.api.player(id).await.and_then(|x| x.into_std()) {
+                Ok(player_response) => {
+                    retry = 0;
+                    player_response
+                }
+                Err(crate::Error::Youtube(reason)) if status.is_some() => {
+                    yield Ok(Event::Cancelled(Some(reason)));
+                    break;
+                }
+                Err(err) if retry < RETRIES && is_retryable(&err) => {
+                    yield Err(err);
+                    tokio::time::sleep(backoff(retry)).await;
+                    retry = retry.saturating_add(1);
+                    continue;
+                }
+                Err(err) => {
+                    yield Err(err);
+                    break;
+                }
+            };
+
+            let current = LiveStatus::new(&player_response, None, None);
+            let available = player_response
+                .streaming_data
+                .as_ref()
+                .and_then(Availability::new);
+            match (status, current) {
+                (
+                    Some(LiveStatus::Upcoming { scheduled_start: old }),
+                    LiveStatus::Upcoming { scheduled_start: new },
+                ) if old != new => {
+                    yield Ok(Event::Rescheduled(new));
+                }
+                // A premiere that ended before it was polled live is a regular
+                // video with streams, which is not cancelled
+                (Some(LiveStatus::Upcoming { .. }), LiveStatus::NotLive) if available.is_none() => {
+                    yield Ok(Event::Cancelled(None));
+                    break;
+                }
+                // Only changes of the state are reported, not of its details
+                (old, current)
+                    if old.is_none_or(|x| {
+                        std::mem::discriminant(&x) != std::mem::discriminant(&current)
+                    }) =>
+                {
+                    yield Ok(Event::Status(current));
+                }
+                _ => {}
+            }
+            status = Some(current);
+
+            if !matches!(current, LiveStatus::Upcoming { .. }) {
+                if let Some(available) = available {
+                    yield Ok(Event::Available(available));
+                    break;
+                }
+            }
+
+            let wait = match current {
+                LiveStatus::Upcoming {
+                    scheduled_start: Some(start),
+                } => until(start).map(|x| {
+                    polls = 0;
+                    (x / 2).clamp(MIN_INTERVAL, MAX_INTERVAL)
+                }),
+                _ => None,
+            };
+
+            // Back off exponentially if the start is unknown or overdue
+            let wait = wait.unwrap_or_else(|| {
+                polls = polls.saturating_add(1);
+                backoff(polls).max(MIN_INTERVAL)
+            });
+
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The time until `time`, or [`None`] if it is in the past
+fn until(time: DateTime<Utc>) -> Option<Duration> {
+    let now = DateTime::<Utc>::from(SystemTime::now());
+    (time - now).to_std().ok().filter(|x| !x.is_zero())
+}
@@ -0,0 +1,208 @@
+//! Parsing of HLS master and media playlists.
+
+use std::time::Duration;
+
+use reqwest::Url;
+
+/// A variant of a master playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub bandwidth: u64,
+    pub height: Option<u32>,
+    pub url: Url,
+}
+
+/// A segment of a media playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub sequence: u64,
+    pub url: Url,
+}
+
+/// A media playlist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MediaPlaylist {
+    pub target_duration: Duration,
+    pub segments: Vec<Segment>,
+    /// Whether the playlist is complete, because the livestream ended
+    pub end_list: bool,
+}
+
+/// Whether `text` is a master playlist, which lists variants instead of
+/// segments
+pub fn is_master(text: &str) -> bool {
+    text.lines().any(|x| x.starts_with("#EXT-X-STREAM-INF:"))
+}
+
+/// Parse the variants of the master playlist `text`, which was requested
+/// from `base`
+pub fn master(text: &str, base: &Url) -> Option<Vec<Variant>> {
+    let mut lines = lines(text)?;
+    let mut variants = Vec::new();
+
+    while let Some(line) = lines.next() {
+        let attributes = match line.strip_prefix("#EXT-X-STREAM-INF:") {
+            Some(attributes) => attributes,
+            None => continue,
+        };
+
+        let uri = lines.find(|x| !x.starts_with('#'))?;
+        let mut bandwidth = 0;
+        let mut height = None;
+        for (key, value) in self::attributes(attributes) {
+            match key {
+                "BANDWIDTH" => bandwidth = value.parse().ok()?,
+                "RESOLUTION" => {
+                    height = value.split_once('x').and_then(|(_, x)| x.parse().ok());
+                }
+                _ => {}
+            }
+        }
+
+        variants.push(Variant {
+            bandwidth,
+            height,
+            url: base.join(uri).ok()?,
+        });
+    }
+
+    Some(variants)
+}
+
+/// Parse the media playlist `text`, which was requested from `base`
+pub fn media(text: &str, base: &Url) -> Option<MediaPlaylist> {
+    let mut target_duration = Duration::ZERO;
+    let mut sequence = 0;
+    let mut has_info = false;
+    let mut segments = Vec::new();
+    let mut end_list = false;
+
+    for line in lines(text)? {
+        if let Some(value) = line.strip_prefix("#EXT-X-TARGETDURATION:") {
+            target_duration = Duration::from_secs(value.trim().parse().ok()?);
+        } else if let Some(value) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            sequence = value.trim().parse().ok()?;
+        } else if let Some(value) = line.strip_prefix("#EXTINF:") {
+            let (duration, _title) = value.split_once(',').unwrap_or((value, ""));
+            duration.trim().parse::<f64>().ok()?;
+            has_info = true;
+        } else if line == "#EXT-X-ENDLIST" {
+            end_list = true;
+        } else if !line.starts_with('#') {
+            // Every segment has to be preceded by its `#EXTINF`
+            if !std::mem::take(&mut has_info) {
+                return None;
+            }
+            segments.push(Segment {
+                sequence,
+                url: base.join(line).ok()?,
+            });
+            sequence += 1;
+        }
+    }
+
+    Some(MediaPlaylist {
+        target_duration,
+        segments,
+        end_list,
+    })
+}
+
+/// The non-empty lines of a playlist, which has to start with `#EXTM3U`
+fn lines(text: &str) -> Option<impl Iterator<Item = &str>> {
+    let mut lines = text.lines().map(str::trim).filter(|x| !x.is_empty());
+    if lines.next()? != "#EXTM3U" {
+        return None;
+    }
+    Some(lines)
+}
+
+/// Split an attribute list like `BANDWIDTH=1280000,CODECS="avc1,mp4a"` into
+/// its keys and values
+fn attributes(list: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut rest = list;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+
+        let (key, value) = rest.split_once('=')?;
+        let (value, next) = match value.strip_prefix('"') {
+            Some(quoted) => {
+                let (value, next) = quoted.split_once('"')?;
+                (value, next.strip_prefix(',').unwrap_or(next))
+            }
+            None => value.split_once(',').unwrap_or((value, "")),
+        };
+
+        rest = next;
+        Some((key.trim(), value))
+    })
+}
+
+/// The URL of the segment `sequence` of a YouTube livestream, derived from
+/// the URL of another segment by replacing its `sq` parameter. This allows
+/// requesting segments that are no longer in the playlist.
+pub fn with_sequence(url: &Url, sequence: u64) -> Option<Url> {
+    let mut url = url.clone();
+
+    // The segments are replaced in the still percent-encoded path, so that
+    // escapes like `%2F` in the other segments are kept as they are
+    let path = url.path().to_string();
+    let mut segments: Vec<&str> = path.split('/').collect();
+    if let Some(index) = segments.iter().position(|x| *x == "sq") {
+        segments.get(index + 1)?.parse::<u64>().ok()?;
+        let sequence = sequence.to_string();
+        segments[index + 1] = &sequence;
+        url.set_path(&segments.join("/"));
+        return Some(url);
+    }
+
+    // Only the value of `sq` is replaced, the other pairs are kept verbatim
+    let query = url.query()?.to_string();
+    let mut found = false;
+    let pairs: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some(("sq", _)) => {
+                found = true;
+                format!("sq={}", sequence)
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+    if !found {
+        return None;
+    }
+
+    url.set_query(Some(&pairs.join("&")));
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Url;
+
+    #[test]
+    fn with_sequence() {
+        let url: Url = "https://example.com/videoplayback/id/a%2Fb/sq/12/file/seg.ts"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            super::with_sequence(&url, 3).unwrap().as_str(),
+            "https://example.com/videoplayback/id/a%2Fb/sq/3/file/seg.ts"
+        );
+
+        // The other pairs of the query keep their encoding
+        let url: Url = "https://example.com/seg.ts?sig=a%20b%2A&sq=12&n=x%3D"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            super::with_sequence(&url, 3).unwrap().as_str(),
+            "https://example.com/seg.ts?sig=a%20b%2A&sq=3&n=x%3D"
+        );
+
+        let url: Url = "https://example.com/seg.ts?sig=x".parse().unwrap();
+        assert_eq!(super::with_sequence(&url, 3), None);
+    }
+}
@@ -0,0 +1,400 @@
+//! Recording of livestreams from their HLS playlists.
+
+use std::{ops::Range, time::Duration};
+
+use reqwest::{StatusCode, Url};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use super::hls;
+use crate::{
+    error,
+    stream::{backoff, is_retryable},
+    Client,
+};
+
+/// The amount of polls without new segments after which a livestream is
+/// checked for its end
+const IDLE_POLLS: u32 = 5;
+
+/// Options for recording a livestream.
+///
+/// The HLS media playlist of the livestream is followed and its segments are
+/// written in order, which results in a continuous MPEG-TS file. Segments
+/// that could not be downloaded are skipped and reported as gaps in the
+/// [`Recording`].
+///
+/// If the playlist of a [`Video`](crate::Video) expires during the
+/// recording, it is refreshed and the recording continues. A segment that is
+/// still forbidden after [`retries`](Self::retries) refreshes is skipped.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use ytextract::video::live::Record;
+///
+/// let client = ytextract::Client::new();
+///
+/// let mut file = tokio::fs::File::create("stream.ts").await?;
+/// let recording = Record::new()
+///     .max_height(720)
+///     .record(&client, "jfKfPfyJRdk".parse()?, &mut file)
+///     .await?;
+///
+/// println!("Recorded {} segments", recording.segments());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Record {
+    from_start: bool,
+    max_height: Option<u32>,
+    retries: u32,
+}
+
+impl Default for Record {
+    fn default() -> Self {
+        Self {
+            from_start: true,
+            max_height: None,
+            retries: 5,
+        }
+    }
+}
+
+/// Where the playlist of a recording comes from
+enum Source {
+    Video(crate::video::Id),
+    Url(Url),
+}
+
+impl Record {
+    /// Create the default [`Record`] options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether to record from the start of the livestream, if YouTube still
+    /// serves it (DVR), or from the oldest segment in the playlist
+    /// otherwise. If `false`, the recording starts at the live edge.
+    pub fn from_start(mut self, from_start: bool) -> Self {
+        self.from_start = from_start;
+        self
+    }
+
+    /// The maximum height of the recorded variant. The variant with the
+    /// highest bandwidth below this height is recorded.
+    pub fn max_height(mut self, max_height: u32) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+    /// How often a single failed request is retried with a backoff before
+    /// giving up.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Record the livestream of the [`Video`](crate::Video) identified by
+    /// `id` into `writer`, until it ends.
+    pub async fn record<W: AsyncWrite + Unpin>(
+        &self,
+        client: &Client,
+        id: crate::video::Id,
+        writer: &mut W,
+    ) -> crate::Result<Recording> {
+        self.run(client, Source::Video(id), writer).await
+    }
+
+    /// Record the HLS master or media playlist at `url` into `writer`, until
+    /// it ends. An expired `url` can not be refreshed.
+    pub async fn record_url<W: AsyncWrite + Unpin>(
+        &self,
+        client: &Client,
+        url: Url,
+        writer: &mut W,
+    ) -> crate::Result<Recording> {
+        self.run(client, Source::Url(url), writer).await
+    }
+
+    async fn run<W: AsyncWrite + Unpin>(
+        &self,
+        client: &Client,
+        source: Source,
+        writer: &mut W,
+    ) -> crate::Result<Recording> {
+        let (mut playlist_url, mut pending) = self.resolve(client, &source).await?;
+        let mut recording = Recording::default();
+        let mut next = None;
+        let mut idle = 0;
+        let mut failures = 0;
+        let mut refresh = false;
+        // The sequence of the last expired segment and how often the
+        // playlist was refreshed for it
+        let mut refreshes: Option<(u64, u32)> = None;
+
+        loop {
+            let playlist = async {
+                if refresh {
+                    (playlist_url, pending) = self.resolve(client, &source).await?;
+                }
+                match pending.take() {
+                    Some(playlist) => Ok(playlist),
+                    None => playlist(client, &playlist_url).await,
+                }
+            }
+            .await;
+            let playlist = match playlist {
+                Ok(playlist) => {
+                    failures = 0;
+                    refresh = false;
+                    playlist
+                }
+                // Without a manifest, the livestream has ended
+                Err(crate::Error::Stream(error::Stream::MissingManifest)) if refresh => break,
+                // Failed refreshes count as failed requests
+                Err(err) if failures < self.retries => {
+                    log::warn!("Playlist request failed: {}, retrying...", err);
+                    tokio::time::sleep(backoff(failures)).await;
+                    failures += 1;
+                    refresh = matches!(source, Source::Video(_));
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+
+            let wait = playlist.target_duration.max(Duration::from_secs(1));
+            let (first, last) = match (playlist.segments.first(), playlist.segments.last()) {
+                (Some(first), Some(last)) => (first, last.sequence),
+                _ => {
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+            };
+
+            let start = match next {
+                Some(next) => next,
+                None => self.start(client, &playlist).await,
+            };
+
+            let mut expired = false;
+            for sequence in start..=last {
+                // Segments that left the playlist are requested by their `sq`
+                let url = match sequence.checked_sub(first.sequence) {
+                    Some(index) => Some(playlist.segments[index as usize].url.clone()),
+                    None => hls::with_sequence(&first.url, sequence),
+                };
+
+                match url {
+                    Some(url) => match self.segment(client, &url).await {
+                        Ok(bytes) => {
+                            writer.write_all(&bytes).await?;
+                            recording.segments += 1;
+                            recording.bytes += bytes.len() as u64;
+                        }
+                        Err(err) if is_expired(&err) && matches!(source, Source::Video(_)) => {
+                            let count = match refreshes {
+                                Some((expired, count)) if expired == sequence => count,
+                                _ => 0,
+                            };
+                            if count < self.retries {
+                                log::warn!("Playlist expired, refreshing...");
+                                refreshes = Some((sequence, count + 1));
+                                expired = true;
+                                next = Some(sequence);
+                                break;
+                            }
+
+                            log::error!(
+                                "Segment {} is still forbidden after {} refreshes. Skipping...",
+                                sequence,
+                                count
+                            );
+                            recording.gap(sequence);
+                        }
+                        Err(err) => {
+                            log::error!("Segment {} failed: {}. Skipping...", sequence, err);
+                            recording.gap(sequence);
+                        }
+                    },
+                    None => recording.gap(sequence),
+                }
+                next = Some(sequence + 1);
+            }
+
+            if expired {
+                refresh = true;
+                continue;
+            }
+
+            if playlist.end_list {
+                break;
+            }
+
+            if start > last {
+                idle += 1;
+                if idle >= IDLE_POLLS {
+                    idle = 0;
+                    // Check for the end with a new playlist
+                    match source {
+                        Source::Video(_) => refresh = true,
+                        Source::Url(_) => break,
+                    }
+                }
+                tokio::time::sleep(wait).await;
+            } else {
+                idle = 0;
+                tokio::time::sleep(wait / 2).await;
+            }
+        }
+
+        writer.flush().await?;
+        Ok(recording)
+    }
+
+    /// The sequence number of the first segment to record
+    async fn start(&self, client: &Client, playlist: &hls::MediaPlaylist) -> u64 {
+        let (first, last) = match (playlist.segments.first(), playlist.segments.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0,
+        };
+
+        if !self.from_start {
+            return if playlist.end_list {
+                first.sequence
+            } else {
+                last.sequence
+            };
+        }
+
+        // DVR is available if the first segment of the livestream is still
+        // served
+        let dvr = match hls::with_sequence(&first.url, 0) {
+            Some(url) if first.sequence > 0 => get(client, &url).await.is_ok(),
+            _ => false,
+        };
+
+        if dvr {
+            0
+        } else {
+            first.sequence
+        }
+    }
+
+    /// The URL of the media playlist to record, and the playlist itself if
+    /// it was already requested
+    async fn resolve(
+        &self,
+        client: &Client,
+        source: &Source,
+    ) -> crate::Result<(Url, Option<hls::MediaPlaylist>)> {
+        let url = match source {
+            Source::Video(id) => client
+                .api
+                .streams(*id)
+                .await?
+                .streaming_data
+                .hls_manifest_url
+                .ok_or(error::Stream::MissingManifest)?,
+            Source::Url(url) => url.clone(),
+        };
+
+        let text = get(client, &url).await?.text().await?;
+        if !hls::is_master(&text) {
+            let playlist = hls::media(&text, &url).ok_or(error::Stream::InvalidPlaylist)?;
+            return Ok((url, Some(playlist)));
+        }
+
+        let variants = hls::master(&text, &url).ok_or(error::Stream::InvalidPlaylist)?;
+        let max_height = self.max_height.unwrap_or(u32::MAX);
+        let variant = variants
+            .iter()
+            .filter(|x| x.height.is_none_or(|x| x <= max_height))
+            .max_by_key(|x| x.bandwidth)
+            .or_else(|| variants.iter().min_by_key(|x| x.bandwidth))
+            .ok_or(error::Stream::InvalidPlaylist)?;
+
+        Ok((variant.url.clone(), None))
+    }
+
+    /// Request a single segment, retrying it up to `retries` times with a
+    /// backoff unless the error is permanent.
+    async fn segment(&self, client: &Client, url: &Url) -> crate::Result<bytes::Bytes> {
+        let mut retry = 0;
+
+        loop {
+            let err = match get(client, url).await {
+                Ok(response) => match response.bytes().await {
+                    Ok(bytes) => break Ok(bytes),
+                    Err(err) => err.into(),
+                },
+                Err(err) => err,
+            };
+
+            // Expired URLs are client errors, which are not retried either
+            if retry == self.retries || !is_retryable(&err) {
+                break Err(err);
+            } else {
+                log::warn!("Segment {} failed: {}, retrying...", url, err);
+                tokio::time::sleep(backoff(retry)).await;
+                retry += 1;
+            }
+        }
+    }
+}
+
+/// The result of a recording
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recording {
+    segments: u64,
+    bytes: u64,
+    gaps: Vec<Range<u64>>,
+}
+
+impl Recording {
+    /// Add the segment `sequence` to the gaps
+    fn gap(&mut self, sequence: u64) {
+        match self.gaps.last_mut() {
+            Some(gap) if gap.end == sequence => gap.end += 1,
+            _ => self.gaps.push(sequence..sequence + 1),
+        }
+    }
+
+    /// The amount of segments that were written
+    pub fn segments(&self) -> u64 {
+        self.segments
+    }
+
+    /// The amount of bytes that were written
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// The ranges of sequence numbers of the segments that could not be
+    /// downloaded and are missing from the recording
+    pub fn gaps(&self) -> &[Range<u64>] {
+        &self.gaps
+    }
+}
+
+/// Request and parse the media playlist at `url`
+async fn playlist(client: &Client, url: &Url) -> crate::Result<hls::MediaPlaylist> {
+    let text = get(client, url).await?.text().await?;
+    Ok(hls::media(&text, url).ok_or(error::Stream::InvalidPlaylist)?)
+}
+
+async fn get(client: &Client, url: &Url) -> crate::Result<reqwest::Response> {
+    Ok(client
+        .api
+        .http
+        .get(url.clone())
+        .send()
+        .await?
+        .error_for_status()?)
+}
+
+/// Whether `err` means that the URL of a playlist expired
+fn is_expired(err: &crate::Error) -> bool {
+    matches!(err, crate::Error::Request(err) if err.status() == Some(StatusCode::FORBIDDEN))
+}
@@ -0,0 +1,606 @@
+//! Live chat of a livestream, or its replay.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    stream::{backoff, is_retryable},
+    youtube::live_chat::{self, Action, ChatItemRenderer},
+    Client,
+};
+
+/// The time to wait between requests if YouTube does not specify one
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The live chat of a livestream, or the chat replay of a finished one.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use futures::StreamExt;
+/// use ytextract::video::live_chat::Kind;
+///
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("jfKfPfyJRdk".parse()?).await?;
+/// if let Some(chat) = video.live_chat() {
+///     let events = chat.events().take(20);
+///     futures::pin_mut!(events);
+///
+///     while let Some(event) = events.next().await {
+///         let event = event?;
+///         if let Kind::SuperChat(message, amount) = event.kind() {
+///             println!("{} sent {}", message.author().name(), amount.text());
+///         }
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct LiveChat {
+    continuation: String,
+    is_replay: bool,
+    retries: u32,
+    client: Client,
+}
+
+impl LiveChat {
+    pub(crate) fn new(client: Client, continuation: String, is_replay: bool) -> Self {
+        Self {
+            continuation,
+            is_replay,
+            retries: 5,
+            client,
+        }
+    }
+
+    /// Whether a [`LiveChat`] is the replay of a finished livestream
+    pub fn is_replay(&self) -> bool {
+        self.is_replay
+    }
+
+    /// How often a failed request is retried with a backoff before the
+    /// [`Events`](Event) end.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// The [`Events`](Event) of a [`LiveChat`].
+    ///
+    /// For a livestream this polls for new events until the stream ends, for
+    /// a replay this yields all events from the start of the video.
+    ///
+    /// Failed requests are yielded and retried with a backoff up to
+    /// [`retries`](Self::retries) times in a row. Client errors other than
+    /// `429 Too Many Requests` and [`Youtube`](crate::error::Youtube) errors
+    /// end the stream right away.
+    pub fn events(&self) -> impl futures_core::Stream<Item = crate::Result<Event>> {
+        self.events_from(Duration::ZERO)
+    }
+
+    /// The [`Events`](Event) of a [`LiveChat`] starting at `offset` into the
+    /// video. The `offset` is ignored for livestreams.
+    ///
+    /// Errors are handled like in [`LiveChat::events`].
+    pub fn events_from(
+        &self,
+        offset: Duration,
+    ) -> impl futures_core::Stream<Item = crate::Result<Event>> {
+        let client = self.client.clone();
+        let is_replay = self.is_replay;
+        let retries = self.retries;
+        let mut token = Some(self.continuation.clone());
+        let mut offset = offset;
+        let mut retry = 0;
+
+        async_stream::stream! {
+            while let Some(current) = token.take() {
+                let response = if is_replay {
+                    client.api.get_live_chat_replay(current.clone(), offset).await
+                } else {
+                    client.api.get_live_chat(current.clone()).await
+                };
+
+                let response = match response {
+                    Ok(response) => {
+                        retry = 0;
+                        response
+                    }
+                    Err(err) if retry < retries && is_retryable(&err) => {
+                        yield Err(err);
+                        tokio::time::sleep(backoff(retry)).await;
+                        retry = retry.saturating_add(1);
+                        token = Some(current);
+                        continue;
+                    }
+                    Err(err) => {
+                        yield Err(err);
+                        break;
+                    }
+                };
+
+                let contents = match response.continuation_contents {
+                    Some(contents) => contents.live_chat_continuation,
+                    None => break,
+                };
+
+                let next = contents.next().cloned();
+                let empty = contents.actions.is_empty();
+
+                for action in contents.actions {
+                    for event in Event::from_action(action, None) {
+                        if let Some(x) = event.offset {
+                            offset = offset.max(x);
+                        }
+                        yield Ok(event);
+                    }
+                }
+
+                if let Some(next) = next {
+                    // A replay without new actions has reached its end
+                    if is_replay && empty {
+                        break;
+                    }
+
+                    if !is_replay {
+                        let timeout = next
+                            .timeout_ms
+                            .map(Duration::from_millis)
+                            .unwrap_or(DEFAULT_TIMEOUT);
+                        tokio::time::sleep(timeout).await;
+                    }
+
+                    token = Some(next.continuation);
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for LiveChat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LiveChat")
+            .field("is_replay", &self.is_replay)
+            .finish()
+    }
+}
+
+/// An event in a [`LiveChat`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    time: Option<DateTime<Utc>>,
+    offset: Option<Duration>,
+    kind: Kind,
+}
+
+impl Event {
+    /// Convert an `action` into [`Events`](Event), where `offset` is the
+    /// time into the video of a replayed action
+    fn from_action(action: Action, offset: Option<Duration>) -> Vec<Self> {
+        let event = |time, kind| Self { time, offset, kind };
+
+        if let Some(replay) = action.replay_chat_item_action {
+            let offset = replay.video_offset_time_msec.map(Duration::from_millis);
+            return replay
+                .actions
+                .into_iter()
+                .flat_map(|x| Self::from_action(x, offset))
+                .collect();
+        }
+
+        if let Some(add) = action.add_chat_item_action {
+            let item = add.item;
+            let kind = if let Some(x) = item.live_chat_text_message_renderer {
+                Some((
+                    x.timestamp_usec,
+                    Kind::Message(Message::new(&x, x.message.text())),
+                ))
+            } else if let Some(x) = item.live_chat_paid_message_renderer {
+                let amount = Amount::new(x.purchase_amount_text.as_ref());
+                let message = Message::new(&x, x.message.text());
+                Some((x.timestamp_usec, Kind::SuperChat(message, amount)))
+            } else if let Some(x) = item.live_chat_paid_sticker_renderer {
+                let amount = Amount::new(x.purchase_amount_text.as_ref());
+                let label = x
+                    .sticker
+                    .as_ref()
+                    .and_then(|x| x.accessibility.as_ref())
+                    .map(|x| x.accessibility_data.label.clone())
+                    .unwrap_or_default();
+                let message = Message::new(&x, label);
+                Some((x.timestamp_usec, Kind::SuperSticker(message, amount)))
+            } else {
+                item.live_chat_membership_item_renderer.map(|x| {
+                    let header = x
+                        .header_primary_text
+                        .as_ref()
+                        .or(x.header_subtext.as_ref())
+                        .map(|x| x.text())
+                        .unwrap_or_default();
+                    let membership = Membership {
+                        message: Message::new(&x, x.message.text()),
+                        header,
+                    };
+                    (x.timestamp_usec, Kind::Membership(membership))
+                })
+            };
+
+            return kind
+                .map(|(usec, kind)| {
+                    event(usec.and_then(DateTime::<Utc>::from_timestamp_micros), kind)
+                })
+                .into_iter()
+                .collect();
+        }
+
+        let deleted = action
+            .mark_chat_item_as_deleted_action
+            .or(action.remove_chat_item_action)
+            .map(|x| Kind::Deleted(x.target_item_id));
+        let author_deleted = action
+            .mark_chat_items_by_author_as_deleted_action
+            .or(action.remove_chat_item_by_author_action)
+            .map(|x| Kind::AuthorDeleted(x.external_channel_id));
+
+        deleted
+            .or(author_deleted)
+            .map(|kind| event(None, kind))
+            .into_iter()
+            .collect()
+    }
+
+    /// The time an [`Event`] happened. [`None`] for deletions.
+    pub fn time(&self) -> Option<DateTime<Utc>> {
+        self.time
+    }
+
+    /// The time into the video an [`Event`] happened at. Only available for
+    /// replays.
+    pub fn offset(&self) -> Option<Duration> {
+        self.offset
+    }
+
+    /// The [`Kind`] of an [`Event`]
+    pub fn kind(&self) -> &Kind {
+        &self.kind
+    }
+}
+
+/// The kind of an [`Event`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind {
+    /// A text message
+    Message(Message),
+
+    /// A Super Chat, which is a highlighted message bought for an [`Amount`]
+    SuperChat(Message, Amount),
+
+    /// A Super Sticker bought for an [`Amount`]. The text of the
+    /// [`Message`] is the description of the sticker.
+    SuperSticker(Message, Amount),
+
+    /// A new or renewed membership
+    Membership(Membership),
+
+    /// The message with the given id was deleted
+    Deleted(String),
+
+    /// All messages of the [`Channel`](crate::Channel) with the given
+    /// [`Id`](crate::channel::Id) were deleted
+    AuthorDeleted(crate::channel::Id),
+}
+
+/// A message in a [`LiveChat`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    id: String,
+    author: Author,
+    text: String,
+}
+
+impl Message {
+    fn new(renderer: &ChatItemRenderer, text: String) -> Self {
+        Self {
+            id: renderer.id.clone(),
+            author: Author {
+                id: renderer.author_external_channel_id,
+                name: renderer
+                    .author_name
+                    .as_ref()
+                    .map(|x| x.text())
+                    .unwrap_or_default(),
+            },
+            text,
+        }
+    }
+
+    /// The id of a [`Message`], as referenced by [`Kind::Deleted`]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// The [`Author`] of a [`Message`]
+    pub fn author(&self) -> &Author {
+        &self.author
+    }
+
+    /// The text of a [`Message`], with custom emojis replaced by their
+    /// shortcut, e.g. `:yt:`
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// The author of a [`Message`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Author {
+    id: crate::channel::Id,
+    name: String,
+}
+
+impl Author {
+    /// The [`Id`](crate::channel::Id) of the [`Channel`](crate::Channel) of
+    /// an [`Author`]
+    pub fn id(&self) -> crate::channel::Id {
+        self.id
+    }
+
+    /// The name of an [`Author`]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// A membership announced in a [`LiveChat`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Membership {
+    message: Message,
+    header: String,
+}
+
+impl Membership {
+    /// The [`Message`] of a [`Membership`]. The text is empty if the member
+    /// did not write one.
+    pub fn message(&self) -> &Message {
+        &self.message
+    }
+
+    /// The announcement of a [`Membership`], e.g. `Welcome to Channel!` or
+    /// `Member for 6 months`
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+}
+
+/// The amount of money paid for a [`Kind::SuperChat`] or
+/// [`Kind::SuperSticker`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Amount {
+    value: Option<f64>,
+    currency: Option<String>,
+    text: String,
+}
+
+impl Amount {
+    fn new(text: Option<&live_chat::MessageText>) -> Self {
+        let text = text.map(|x| x.text()).unwrap_or_default();
+        let (currency, value) = parse_amount(&text);
+        Self {
+            value,
+            currency,
+            text,
+        }
+    }
+
+    /// The numeric value of an [`Amount`], if it could be parsed
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    /// The [ISO 4217](https://en.wikipedia.org/wiki/ISO_4217) code of the
+    /// currency of an [`Amount`], if it is known
+    pub fn currency(&self) -> Option<&str> {
+        self.currency.as_deref()
+    }
+
+    /// The text of an [`Amount`] as shown by YouTube, e.g. `$5.00`
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// The currency symbols YouTube uses and their ISO 4217 codes. Longer
+/// symbols come first, so that `CA$` is not mistaken for `$`. Symbols shared
+/// by several currencies, like `kr` for SEK, NOK, DKK and ISK, are left out.
+const CURRENCIES: &[(&str, &str)] = &[
+    ("CA$", "CAD"),
+    ("A$", "AUD"),
+    ("NZ$", "NZD"),
+    ("HK$", "HKD"),
+    ("NT$", "TWD"),
+    ("MX$", "MXN"),
+    ("R$", "BRL"),
+    ("$", "USD"),
+    ("€", "EUR"),
+    ("£", "GBP"),
+    ("¥", "JPY"),
+    ("₹", "INR"),
+    ("₩", "KRW"),
+    ("₱", "PHP"),
+    ("₪", "ILS"),
+    ("₫", "VND"),
+    ("₺", "TRY"),
+    ("₽", "RUB"),
+    ("zł", "PLN"),
+];
+
+/// Parse an amount like `$5.00`, `€2,50`, `¥1,000` or `PHP 100.00` into its
+/// currency and value
+fn parse_amount(text: &str) -> (Option<String>, Option<f64>) {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let (symbol, number) = text.split_at(split);
+    let symbol = symbol.trim();
+
+    let currency = CURRENCIES
+        .iter()
+        .find(|(x, _)| *x == symbol)
+        .map(|(_, code)| code.to_string())
+        .or_else(|| {
+            // Other currencies are shown with their code, e.g. `PHP 100.00`
+            Some(symbol)
+                .filter(|x| x.len() == 3 && x.bytes().all(|x| x.is_ascii_uppercase()))
+                .map(String::from)
+        });
+
+    // A separator followed by at most two digits is the decimal separator,
+    // any other separator groups thousands
+    let number = number.trim();
+    let decimal = number
+        .rfind(['.', ','])
+        .filter(|i| number.len() - i - 1 <= 2);
+    let value: String = number
+        .char_indices()
+        .filter_map(|(i, c)| match c {
+            '0'..='9' => Some(c),
+            _ if Some(i) == decimal => Some('.'),
+            _ => None,
+        })
+        .collect();
+
+    (currency, value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse_amount, Event, Kind};
+
+    const AUTHOR: &str = "UCBR8-60-B28hp2BmDPdntcQ";
+
+    fn events(action: &str) -> Vec<Event> {
+        Event::from_action(serde_json::from_str(action).unwrap(), None)
+    }
+
+    #[test]
+    fn amount() {
+        assert_eq!(parse_amount("$5.00"), (Some("USD".into()), Some(5.0)));
+        assert_eq!(parse_amount("CA$10.00"), (Some("CAD".into()), Some(10.0)));
+        assert_eq!(parse_amount("€2,50"), (Some("EUR".into()), Some(2.5)));
+        assert_eq!(parse_amount("¥1,000"), (Some("JPY".into()), Some(1000.0)));
+        assert_eq!(
+            parse_amount("₩1.000.000"),
+            (Some("KRW".into()), Some(1000000.0))
+        );
+        assert_eq!(
+            parse_amount("PHP 100.00"),
+            (Some("PHP".into()), Some(100.0))
+        );
+        assert_eq!(parse_amount("kr 50,00"), (None, Some(50.0)));
+        assert_eq!(parse_amount(""), (None, None));
+    }
+
+    #[test]
+    fn message() {
+        let events = events(&format!(
+            r#"{{
+                "addChatItemAction": {{
+                    "item": {{
+                        "liveChatTextMessageRenderer": {{
+                            "id": "message",
+                            "message": {{ "runs": [
+                                {{ "text": "Hi " }},
+                                {{ "emoji": {{ "emojiId": "yt", "shortcuts": [":yt:"], "isCustomEmoji": true }} }}
+                            ] }},
+                            "authorName": {{ "simpleText": "Author" }},
+                            "authorExternalChannelId": "{}",
+                            "timestampUsec": "1600000000000000"
+                        }}
+                    }}
+                }}
+            }}"#,
+            AUTHOR
+        ));
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].time().unwrap().timestamp(), 1600000000);
+        assert_eq!(events[0].offset(), None);
+        match events[0].kind() {
+            Kind::Message(message) => {
+                assert_eq!(message.id(), "message");
+                assert_eq!(message.text(), "Hi :yt:");
+                assert_eq!(message.author().name(), "Author");
+                assert_eq!(message.author().id(), AUTHOR.parse().unwrap());
+            }
+            kind => panic!("Expected a message, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn super_chat() {
+        let events = events(&format!(
+            r#"{{
+                "addChatItemAction": {{
+                    "item": {{
+                        "liveChatPaidMessageRenderer": {{
+                            "id": "paid",
+                            "authorExternalChannelId": "{}",
+                            "purchaseAmountText": {{ "simpleText": "€2,50" }}
+                        }}
+                    }}
+                }}
+            }}"#,
+            AUTHOR
+        ));
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].time(), None);
+        match events[0].kind() {
+            Kind::SuperChat(message, amount) => {
+                assert_eq!(message.text(), "");
+                assert_eq!(amount.text(), "€2,50");
+                assert_eq!(amount.currency(), Some("EUR"));
+                assert_eq!(amount.value(), Some(2.5));
+            }
+            kind => panic!("Expected a super chat, got {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn replay() {
+        let events = events(&format!(
+            r#"{{
+                "replayChatItemAction": {{
+                    "videoOffsetTimeMsec": "1500",
+                    "actions": [
+                        {{ "removeChatItemAction": {{ "targetItemId": "message" }} }},
+                        {{ "markChatItemsByAuthorAsDeletedAction": {{ "externalChannelId": "{}" }} }}
+                    ]
+                }}
+            }}"#,
+            AUTHOR
+        ));
+
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|x| x.offset() == Some(Duration::from_millis(1500)) && x.time().is_none()));
+        assert_eq!(events[0].kind(), &Kind::Deleted("message".into()));
+        assert_eq!(
+            events[1].kind(),
+            &Kind::AuthorDeleted(AUTHOR.parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(events("{}").is_empty());
+    }
+}
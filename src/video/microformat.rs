@@ -0,0 +1,169 @@
+//! Microformat metadata of a video.
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+
+use crate::youtube::player_response::PlayerMicroformatRenderer;
+
+/// The microformat metadata of a [`Video`](super::Video), as used by YouTube
+/// for search engines and embeds.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("nI2e-J6fsuk".parse()?).await?;
+/// let microformat = video.microformat().await?.unwrap();
+///
+/// println!("Category: {:?}", microformat.category());
+/// println!("Published: {:?}", microformat.publish_date());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Microformat(pub(super) PlayerMicroformatRenderer);
+
+impl Microformat {
+    /// The category of a [`Video`](super::Video), e.g. `Music`
+    pub fn category(&self) -> Option<&str> {
+        self.0.category.as_deref()
+    }
+
+    /// The time a [`Video`](super::Video) was published. Older responses
+    /// only contain the date, in which case the time is midnight UTC.
+    pub fn publish_date(&self) -> Option<DateTime<FixedOffset>> {
+        parse_timestamp(self.0.publish_date.as_deref()?)
+    }
+
+    /// The time a [`Video`](super::Video) was uploaded. Older responses only
+    /// contain the date, in which case the time is midnight UTC.
+    pub fn upload_date(&self) -> Option<DateTime<FixedOffset>> {
+        parse_timestamp(self.0.upload_date.as_deref()?)
+    }
+
+    /// Whether a [`Video`](super::Video) is family safe, if it is known
+    pub fn is_family_safe(&self) -> Option<bool> {
+        self.0.is_family_safe
+    }
+
+    /// Whether a [`Video`](super::Video) is unlisted, if it is known
+    pub fn is_unlisted(&self) -> Option<bool> {
+        self.0.is_unlisted
+    }
+
+    /// The [ISO 3166-1](https://en.wikipedia.org/wiki/ISO_3166-1) codes of
+    /// the countries a [`Video`](super::Video) is available in. Empty if it
+    /// is not known.
+    pub fn available_countries(&self) -> impl Iterator<Item = &str> {
+        self.0.available_countries.iter().map(String::as_str)
+    }
+
+    /// Whether a [`Video`](super::Video) has paid content metadata, e.g. a
+    /// rental or purchase of a movie
+    pub fn has_ypc_metadata(&self) -> bool {
+        self.0.has_ypc_metadata
+    }
+
+    /// The [`LiveBroadcast`] details of a livestream. [`None`] if a
+    /// [`Video`](super::Video) is not a livestream.
+    pub fn live_broadcast(&self) -> Option<LiveBroadcast<'_>> {
+        self.0
+            .live_broadcast_details
+            .as_ref()
+            .map(|x| LiveBroadcast {
+                is_live_now: x.is_live_now,
+                start: x.start_timestamp.as_deref(),
+                end: x.end_timestamp.as_deref(),
+            })
+    }
+
+    /// The [`Embed`] of a [`Video`](super::Video). [`None`] if it is not
+    /// embeddable.
+    pub fn embed(&self) -> Option<Embed<'_>> {
+        self.0.embed.as_ref().map(|x| Embed {
+            url: &x.iframe_url,
+            width: x.width,
+            height: x.height,
+        })
+    }
+}
+
+impl std::fmt::Debug for Microformat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Microformat")
+            .field("category", &self.category())
+            .field("publish_date", &self.publish_date())
+            .field("upload_date", &self.upload_date())
+            .field("is_family_safe", &self.is_family_safe())
+            .field("is_unlisted", &self.is_unlisted())
+            .field(
+                "available_countries",
+                &self.available_countries().collect::<Vec<_>>(),
+            )
+            .field("has_ypc_metadata", &self.has_ypc_metadata())
+            .field("live_broadcast", &self.live_broadcast())
+            .field("embed", &self.embed())
+            .finish()
+    }
+}
+
+/// The broadcast details of a livestream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiveBroadcast<'a> {
+    is_live_now: bool,
+    start: Option<&'a str>,
+    end: Option<&'a str>,
+}
+
+impl LiveBroadcast<'_> {
+    /// Whether the livestream is currently live
+    pub fn is_live_now(&self) -> bool {
+        self.is_live_now
+    }
+
+    /// The time the livestream started. [`None`] if it did not start yet.
+    pub fn start(&self) -> Option<DateTime<FixedOffset>> {
+        parse_timestamp(self.start?)
+    }
+
+    /// The time the livestream ended. [`None`] if it did not end yet.
+    pub fn end(&self) -> Option<DateTime<FixedOffset>> {
+        parse_timestamp(self.end?)
+    }
+}
+
+/// The embed information of a [`Video`](super::Video)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Embed<'a> {
+    url: &'a str,
+    width: Option<u64>,
+    height: Option<u64>,
+}
+
+impl<'a> Embed<'a> {
+    /// The URL of the embeddable player, e.g.
+    /// `https://www.youtube.com/embed/nI2e-J6fsuk`
+    pub fn url(&self) -> &'a str {
+        self.url
+    }
+
+    /// The width of the embedded player
+    pub fn width(&self) -> Option<u64> {
+        self.width
+    }
+
+    /// The height of the embedded player
+    pub fn height(&self) -> Option<u64> {
+        self.height
+    }
+}
+
+/// Parse a timestamp like `2021-04-14T09:00:11-07:00`, or a date like
+/// `2021-04-14`
+pub(crate) fn parse_timestamp(value: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(value).ok().or_else(|| {
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+        Some(date.and_hms_opt(0, 0, 0)?.and_utc().fixed_offset())
+    })
+}
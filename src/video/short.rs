@@ -0,0 +1,110 @@
+//! Shorts-specific metadata of a video.
+
+use crate::youtube::reel::{ReelMultimixAttributionLabelRenderer, ReelPlayerOverlayRenderer};
+
+/// The metadata of a [`Video`](super::Video) that is shown on the
+/// [Shorts](https://www.youtube.com/shorts) page.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("BGQWPY4IigY".parse()?).await?;
+///
+/// if let Some(short) = video.short().await? {
+///     println!("Sound: {:?}", short.sound());
+///     println!("Remix of: {:?}", short.remix_source());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Short {
+    id: super::Id,
+    overlay: ReelPlayerOverlayRenderer,
+}
+
+impl Short {
+    pub(crate) async fn get(client: &crate::Client, id: super::Id) -> crate::Result<Option<Self>> {
+        let response = client.api.reel_item_watch(id).await?;
+
+        if response
+            .replacement_endpoint
+            .is_some_and(|x| x.watch_endpoint.is_some())
+        {
+            return Ok(None);
+        }
+
+        Ok(response
+            .overlay
+            .and_then(|x| x.reel_player_overlay_renderer)
+            .map(|overlay| Self { id, overlay }))
+    }
+
+    /// The [`Id`](super::Id) of a [`Short`]
+    pub fn id(&self) -> super::Id {
+        self.id
+    }
+
+    /// The title of a [`Short`], as shown on the Shorts page
+    pub fn title(&self) -> Option<String> {
+        self.overlay.title()
+    }
+
+    /// The sound or music a [`Short`] uses, e.g. `original sound - Creator`
+    /// or `Song - Artist`
+    pub fn sound(&self) -> Option<String> {
+        self.overlay.sound()
+    }
+
+    /// The [`Remix`] source of a [`Short`]. [`None`] if it is not a remix.
+    pub fn remix_source(&self) -> Option<Remix<'_>> {
+        self.overlay.remix().map(Remix)
+    }
+}
+
+impl std::fmt::Debug for Short {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Short")
+            .field("id", &self.id())
+            .field("title", &self.title())
+            .field("sound", &self.sound())
+            .field("remix_source", &self.remix_source())
+            .finish()
+    }
+}
+
+impl PartialEq for Short {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for Short {}
+
+/// The video a [`Short`] was remixed from
+#[derive(Clone, Copy)]
+pub struct Remix<'a>(&'a ReelMultimixAttributionLabelRenderer);
+
+impl Remix<'_> {
+    /// The attribution text, e.g. `Remixed from @creator`
+    pub fn text(&self) -> String {
+        self.0.text.text()
+    }
+
+    /// The [`Id`](super::Id) of the remixed video, if it is known
+    pub fn id(&self) -> Option<super::Id> {
+        self.0.video_id()
+    }
+}
+
+impl std::fmt::Debug for Remix<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Remix")
+            .field("text", &self.text())
+            .field("id", &self.id())
+            .finish()
+    }
+}
@@ -0,0 +1,293 @@
+//! Storyboards (seek preview sprites) of a video.
+
+use reqwest::Url;
+
+use std::time::Duration;
+
+/// The storyboard of a [`Video`](super::Video), used for previews while
+/// seeking.
+///
+/// A [`Storyboard`] consists of multiple [`Levels`](Level) of increasing
+/// thumbnail size. Each [`Level`] is split into sprite sheets of
+/// [`columns`](Level::columns) x [`rows`](Level::rows) thumbnails.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use std::time::Duration;
+///
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("nI2e-J6fsuk".parse()?).await?;
+/// let storyboard = video.storyboard().unwrap();
+///
+/// let level = storyboard.levels().last().unwrap();
+/// let tile = level.tile(Duration::from_secs(60));
+/// println!("{} at {}x{}", level.sheet_url(tile.sheet), tile.x, tile.y);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Storyboard {
+    levels: Vec<Level>,
+}
+
+impl Storyboard {
+    /// Parse a storyboard spec, e.g.
+    /// `https://i.ytimg.com/sb/<ID>/storyboard3_L$L/$N.jpg?sqp=<SQP>|48#27#100#10#10#0#default#rs$<SIGH>|...`
+    pub(crate) fn parse(spec: &str, duration: Duration) -> Option<Self> {
+        let mut parts = spec.split('|');
+        let template = parts.next()?;
+
+        // Levels that can not be parsed are skipped
+        let levels: Vec<_> = parts
+            .enumerate()
+            .filter_map(|(level, part)| Level::parse(template, level, part, duration))
+            .collect();
+
+        (!levels.is_empty()).then_some(Self { levels })
+    }
+
+    /// The [`Levels`](Level) of a [`Storyboard`], ordered from the smallest
+    /// to the largest thumbnails.
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+}
+
+/// A level of a [`Storyboard`] with thumbnails of a single size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    /// The URL of the sprite sheets, with `$M` in place of the sheet index
+    url: Url,
+    width: u32,
+    height: u32,
+    count: u32,
+    columns: u32,
+    rows: u32,
+    per_sheet: u32,
+    interval: Duration,
+}
+
+impl Level {
+    /// Parse a level of a spec: `width#height#count#columns#rows#interval#name#sigh`
+    fn parse(template: &str, level: usize, part: &str, duration: Duration) -> Option<Self> {
+        let mut fields = part.split('#');
+        let mut number = || fields.next()?.parse::<u32>().ok();
+
+        let width = number()?;
+        let height = number()?;
+        let count = number()?.max(1);
+        let columns = number()?.max(1);
+        let rows = number()?.max(1);
+        let per_sheet = columns.checked_mul(rows)?;
+        let interval = number()?;
+        let name = fields.next()?;
+        let sigh = fields.next();
+
+        // A interval of `0` spreads the thumbnails evenly over the video
+        let interval = match interval {
+            0 => duration / count,
+            interval => Duration::from_millis(interval.into()),
+        };
+
+        let mut url = template
+            .replace("$L", &level.to_string())
+            .replace("$N", name);
+        if let Some(sigh) = sigh {
+            url.push_str(if url.contains('?') {
+                "&sigh="
+            } else {
+                "?sigh="
+            });
+            url.push_str(sigh);
+        }
+
+        Some(Self {
+            url: url.parse().ok()?,
+            width,
+            height,
+            count,
+            columns,
+            rows,
+            per_sheet,
+            interval,
+        })
+    }
+
+    /// The width of a single thumbnail
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height of a single thumbnail
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The total amount of thumbnails of a [`Level`]
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    /// The amount of thumbnails in a row of a sprite sheet
+    pub fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// The amount of thumbnails in a column of a sprite sheet
+    pub fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// The time between two thumbnails
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// The amount of sprite sheets of a [`Level`]
+    pub fn sheets(&self) -> u32 {
+        self.count.div_ceil(self.per_sheet)
+    }
+
+    /// The [`Url`] of the sprite sheet with index `sheet`
+    pub fn sheet_url(&self, sheet: u32) -> Url {
+        let sheet = sheet.to_string();
+        let mut url = self.url.clone();
+        url.set_path(&self.url.path().replace("$M", &sheet));
+        url.set_query(self.url.query().map(|x| x.replace("$M", &sheet)).as_deref());
+        url
+    }
+
+    /// The [`Urls`](Url) of all sprite sheets
+    pub fn sheet_urls(&self) -> impl Iterator<Item = Url> + '_ {
+        (0..self.sheets()).map(move |sheet| self.sheet_url(sheet))
+    }
+
+    /// The [`Tile`] showing the video at `time`
+    pub fn tile(&self, time: Duration) -> Tile {
+        let index = time.as_millis() / self.interval.as_millis().max(1);
+        let index = index.min(u128::from(self.count - 1)) as u32;
+
+        let position = index % self.per_sheet;
+
+        Tile {
+            sheet: index / self.per_sheet,
+            x: (position % self.columns).saturating_mul(self.width),
+            y: (position / self.columns).saturating_mul(self.height),
+        }
+    }
+}
+
+/// The position of a single thumbnail in the sprite sheets of a [`Level`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    /// The index of the sprite sheet, see [`Level::sheet_url`]
+    pub sheet: u32,
+    /// The horizontal offset in pixels of the thumbnail in the sprite sheet
+    pub x: u32,
+    /// The vertical offset in pixels of the thumbnail in the sprite sheet
+    pub y: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Storyboard, Tile};
+
+    const SPEC: &str = "https://i.ytimg.com/sb/ID/storyboard3_L$L/$N.jpg?sqp=abc\
+        |48#27#100#10#10#0#default#rs$AAA\
+        |80#45#50#5#5#2000#M$M#rs$BBB\
+        |invalid";
+
+    #[test]
+    fn parse() {
+        let storyboard = Storyboard::parse(SPEC, Duration::from_secs(200)).unwrap();
+        // The invalid level is skipped
+        let [first, second] = storyboard.levels() else {
+            panic!("expected two levels, got {:?}", storyboard.levels());
+        };
+
+        assert_eq!((first.width(), first.height()), (48, 27));
+        // A interval of 0 spreads the thumbnails over the video
+        assert_eq!(first.interval(), Duration::from_secs(2));
+        assert_eq!(first.sheets(), 1);
+        assert_eq!(
+            first.sheet_url(0).as_str(),
+            "https://i.ytimg.com/sb/ID/storyboard3_L0/default.jpg?sqp=abc&sigh=rs$AAA"
+        );
+
+        assert_eq!(second.interval(), Duration::from_secs(2));
+        assert_eq!(second.sheets(), 2);
+        assert_eq!(
+            second.sheet_urls().map(String::from).collect::<Vec<_>>(),
+            [
+                "https://i.ytimg.com/sb/ID/storyboard3_L1/M0.jpg?sqp=abc&sigh=rs$BBB",
+                "https://i.ytimg.com/sb/ID/storyboard3_L1/M1.jpg?sqp=abc&sigh=rs$BBB",
+            ]
+        );
+
+        assert_eq!(
+            Storyboard::parse("https://i.ytimg.com/", Duration::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn tile() {
+        let storyboard = Storyboard::parse(SPEC, Duration::from_secs(200)).unwrap();
+        let level = &storyboard.levels()[1];
+
+        let tile = |secs| level.tile(Duration::from_secs(secs));
+        assert_eq!(
+            tile(0),
+            Tile {
+                sheet: 0,
+                x: 0,
+                y: 0
+            }
+        );
+        assert_eq!(
+            tile(14),
+            Tile {
+                sheet: 0,
+                x: 160,
+                y: 45
+            }
+        );
+        assert_eq!(
+            tile(60),
+            Tile {
+                sheet: 1,
+                x: 0,
+                y: 45
+            }
+        );
+        // Times after the last thumbnail show the last one
+        assert_eq!(
+            tile(1000),
+            Tile {
+                sheet: 1,
+                x: 320,
+                y: 180
+            }
+        );
+
+        // The offsets of huge thumbnails saturate
+        let storyboard = Storyboard::parse(
+            "https://i.ytimg.com/$M.jpg|4294967295#4294967295#3#3#1#1000#x",
+            Duration::ZERO,
+        )
+        .unwrap();
+        assert_eq!(
+            storyboard.levels()[0].tile(Duration::from_secs(2)),
+            Tile {
+                sheet: 0,
+                x: u32::MAX,
+                y: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Transcripts of a video.
+
+use std::time::Duration;
+
+use crate::{youtube::transcript, Client};
+
+/// The transcript of a [`Video`](super::Video), as shown in the
+/// `Show transcript` panel.
+///
+/// # Example
+///
+/// ```rust
+/// # #[tokio::main] async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = ytextract::Client::new();
+///
+/// let video = client.video("nI2e-J6fsuk".parse()?).await?;
+/// let transcript = video.transcript().await?.unwrap();
+///
+/// for segment in transcript.segments() {
+///     println!("{:?}: {}", segment.start(), segment.text());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Transcript {
+    segments: Vec<Segment>,
+    languages: Vec<Language>,
+    client: Client,
+}
+
+impl Transcript {
+    /// Request the transcript with the `params` of its panel
+    pub(crate) async fn get(client: Client, params: String) -> crate::Result<Option<Self>> {
+        let root = client.api.get_transcript(params).await?;
+        Ok(Self::new(root, client))
+    }
+
+    fn new(root: transcript::Root, client: Client) -> Option<Self> {
+        let panel = root.into_panel()?;
+
+        let segments = panel
+            .body
+            .transcript_segment_list_renderer
+            .initial_segments
+            .into_iter()
+            .filter_map(|x| x.transcript_segment_renderer)
+            .map(|x| Segment {
+                start: x.start_ms,
+                end: x.end_ms,
+                text: x.snippet.map(|x| x.text()).unwrap_or_default(),
+            })
+            .collect();
+
+        let languages = panel
+            .footer
+            .map(|x| {
+                x.transcript_footer_renderer
+                    .language_menu
+                    .sort_filter_sub_menu_renderer
+                    .sub_menu_items
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .map(|x| Language {
+                name: x.title,
+                selected: x.selected,
+                params: x.continuation.reload_continuation_data.continuation,
+            })
+            .collect();
+
+        Some(Self {
+            segments,
+            languages,
+            client,
+        })
+    }
+
+    /// The [`Segments`](Segment) of a [`Transcript`]
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// The [`Languages`](Language) a [`Transcript`] is available in
+    pub fn languages(&self) -> &[Language] {
+        &self.languages
+    }
+
+    /// The [`Language`] of a [`Transcript`], if it is known
+    pub fn language(&self) -> Option<&Language> {
+        self.languages.iter().find(|x| x.selected)
+    }
+
+    /// Request the [`Transcript`] in a different [`Language`], which has to
+    /// be one of [`Transcript::languages`].
+    pub async fn select(&self, language: &Language) -> crate::Result<Option<Self>> {
+        Self::get(self.client.clone(), language.params.clone()).await
+    }
+}
+
+impl std::fmt::Debug for Transcript {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transcript")
+            .field("segments", &self.segments)
+            .field("languages", &self.languages)
+            .finish()
+    }
+}
+
+impl PartialEq for Transcript {
+    fn eq(&self, other: &Self) -> bool {
+        self.segments == other.segments && self.languages == other.languages
+    }
+}
+
+impl Eq for Transcript {}
+
+/// A segment of a [`Transcript`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    start: Duration,
+    end: Duration,
+    text: String,
+}
+
+impl Segment {
+    /// The time a [`Segment`] starts
+    pub fn start(&self) -> Duration {
+        self.start
+    }
+
+    /// The time a [`Segment`] ends
+    pub fn end(&self) -> Duration {
+        self.end
+    }
+
+    /// The text of a [`Segment`]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// A language a [`Transcript`] is available in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    name: String,
+    selected: bool,
+    params: String,
+}
+
+impl Language {
+    /// The human readable name of a [`Language`], e.g.
+    /// `English (auto-generated)`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Whether a [`Language`] is the one of the [`Transcript`] it was taken
+    /// from
+    pub fn is_selected(&self) -> bool {
+        self.selected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{Segment, Transcript};
+    use crate::Client;
+
+    #[test]
+    fn new() {
+        let root = serde_json::json!({
+            "actions": [
+                {},
+                // A different engagement panel
+                { "updateEngagementPanelAction": { "content": {} } },
+                { "updateEngagementPanelAction": { "content": { "transcriptRenderer": { "content": {
+                    "transcriptSearchPanelRenderer": {
+                        "body": { "transcriptSegmentListRenderer": { "initialSegments": [
+                            { "transcriptSegmentRenderer": {
+                                "startMs": "0",
+                                "endMs": "1500",
+                                "snippet": { "runs": [{ "text": "Hello " }, { "text": "world" }] }
+                            } },
+                            { "transcriptSectionHeaderRenderer": {} },
+                            { "transcriptSegmentRenderer": { "startMs": "1500", "endMs": "3000" } }
+                        ] } },
+                        "footer": { "transcriptFooterRenderer": { "languageMenu": {
+                            "sortFilterSubMenuRenderer": { "subMenuItems": [
+                                {
+                                    "title": "English",
+                                    "selected": true,
+                                    "continuation": { "reloadContinuationData": { "continuation": "en" } }
+                                },
+                                {
+                                    "title": "German (auto-generated)",
+                                    "continuation": { "reloadContinuationData": { "continuation": "de" } }
+                                }
+                            ] }
+                        } } }
+                    }
+                } } } } }
+            ]
+        });
+        let root = serde_json::from_value(root).unwrap();
+        let transcript = Transcript::new(root, Client::new()).unwrap();
+
+        assert_eq!(
+            transcript.segments(),
+            [
+                Segment {
+                    start: Duration::ZERO,
+                    end: Duration::from_millis(1500),
+                    text: "Hello world".to_string(),
+                },
+                Segment {
+                    start: Duration::from_millis(1500),
+                    end: Duration::from_millis(3000),
+                    text: String::new(),
+                },
+            ]
+        );
+
+        let languages = transcript.languages();
+        assert_eq!(languages.len(), 2);
+        assert_eq!(transcript.language(), Some(&languages[0]));
+        assert_eq!(languages[1].name(), "German (auto-generated)");
+        assert!(!languages[1].is_selected());
+        assert_eq!(languages[1].params, "de");
+
+        // Without a transcript panel
+        let root = serde_json::from_value(serde_json::json!({ "actions": [] })).unwrap();
+        assert_eq!(Transcript::new(root, Client::new()), None);
+    }
+}
@@ -5,9 +5,14 @@ use std::ops::Deref;
 use serde::Deserialize;
 
 pub mod browse;
+pub mod comments;
 pub mod innertube;
+pub mod live_chat;
 pub mod next;
 pub mod player_response;
+pub mod reel;
+pub mod resolve_url;
+pub mod transcript;
 
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -43,6 +48,16 @@ pub enum Text {
     Runs(TitleRuns),
 }
 
+impl Text {
+    /// The whole text, with all runs joined
+    pub fn text(&self) -> String {
+        match self {
+            Self::SimpleText(simple_text) => simple_text.simple_text.clone(),
+            Self::Runs(runs) => runs.runs.iter().map(|x| x.text.as_str()).collect(),
+        }
+    }
+}
+
 #[derive(Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct SimpleText {
@@ -115,12 +130,14 @@ pub fn parse_subscribers(value: &str) -> Option<u64> {
     if last.is_numeric() {
         value.parse().ok()
     } else {
-        let val = &value[..value.len() - 1];
+        let val = &value[..value.len() - last.len_utf8()];
         let val: f64 = val.parse().ok()?;
         let mul = match last {
             'K' => 1_000.0,
             'M' => 1_000_000.0,
-            modifier => unimplemented!("Unknown modifier '{}'", modifier),
+            'B' => 1_000_000_000.0,
+            // Localized or unknown modifiers, e.g. `万`
+            _ => return None,
         };
 
         Some((val * mul) as u64)
@@ -136,9 +153,16 @@ pub fn parse_length(value: &str) -> std::time::Duration {
     }))
 }
 
-/// Parse a video upload data in the format `[Premiered |Premires ]<MONTH_NAME> <DAY>, <YEAR>`
+/// Parse a video upload date in the format `[<PREFIX> ]<MONTH_NAME> <DAY>, <YEAR>`,
+/// where the prefix is e.g. `Premiered` or `Streamed live on`
 pub fn parse_date(value: &str) -> Option<chrono::NaiveDate> {
-    const PREFIXES: &[&str] = &["Premiered ", "Premieres "];
+    const PREFIXES: &[&str] = &[
+        "Premiered ",
+        "Premieres ",
+        "Streamed live on ",
+        "Started streaming on ",
+        "Scheduled for ",
+    ];
 
     let value = PREFIXES
         .iter()
@@ -148,17 +172,18 @@ pub fn parse_date(value: &str) -> Option<chrono::NaiveDate> {
     chrono::NaiveDate::parse_from_str(value, "%b %e, %Y").ok()
 }
 
-/// Strips the various possible domains of a youtube URL
-pub fn strip_url_prefix(url: &str) -> &str {
-    const PREFIXES: &[&str] = &[
-        "https://www.youtube.com/",
-        "https://m.youtube.com/",
-        "https://youtube.com/",
-        "https://youtu.be/",
-    ];
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
 
-    PREFIXES
-        .iter()
-        .find_map(|prefix| url.strip_prefix(prefix))
-        .unwrap_or(url)
+    #[test]
+    fn parse_date() {
+        let date = NaiveDate::from_ymd_opt(2021, 3, 4);
+        assert_eq!(super::parse_date("Mar 4, 2021"), date);
+        assert_eq!(super::parse_date("Premiered Mar 4, 2021"), date);
+        assert_eq!(super::parse_date("Streamed live on Mar 4, 2021"), date);
+        assert_eq!(super::parse_date("Started streaming on Mar 4, 2021"), date);
+        // Relative dates of ongoing streams can not be parsed
+        assert_eq!(super::parse_date("Started streaming 3 hours ago"), None);
+    }
 }
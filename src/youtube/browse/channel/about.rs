@@ -37,6 +37,7 @@ pub struct ChannelAboutFullMetadataRenderer {
     pub description: SimpleText,
     pub view_count_text: Option<SimpleText>,
     pub country: Option<SimpleText>,
+    #[allow(dead_code)]
     pub joined_date_text: JoinedDateText,
 }
 
@@ -56,11 +57,13 @@ impl ChannelAboutFullMetadataRenderer {
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct JoinedDateText {
+    #[allow(dead_code)]
     pub runs: (Text, Text),
 }
 
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Text {
+    #[allow(dead_code)]
     pub text: String,
 }
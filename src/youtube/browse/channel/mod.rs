@@ -3,6 +3,7 @@ use serde::Deserialize;
 use crate::youtube::{parse_subscribers, Badge, SimpleText, Thumbnails};
 
 pub mod about;
+pub mod shorts;
 
 pub type Result<T> = super::Result<Ok<T>>;
 
@@ -10,6 +11,7 @@ pub type Result<T> = super::Result<Ok<T>>;
 #[serde(rename_all = "camelCase")]
 pub struct Ok<T> {
     pub header: Header,
+    #[allow(dead_code)]
     pub metadata: Metadata,
     pub contents: Contents<T>,
 }
@@ -64,12 +66,14 @@ impl C4TabbedHeaderRenderer {
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Metadata {
+    #[allow(dead_code)]
     pub channel_metadata_renderer: ChannelMetadataRenderer,
 }
 
 #[derive(Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ChannelMetadataRenderer {
+    #[allow(dead_code)]
     pub is_family_safe: bool,
 }
 
@@ -0,0 +1,139 @@
+use serde::Deserialize;
+
+use super::Contents;
+use crate::youtube::{ContinuationItemRenderer, Text, Thumbnails};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+    pub contents: Option<Contents<Content>>,
+    #[serde(default)]
+    pub on_response_received_actions: Vec<Action>,
+}
+
+impl Root {
+    /// The items of the first page, or of a continuation
+    pub fn into_items(self) -> impl Iterator<Item = Item> {
+        let tab = self.contents.into_iter().flat_map(|x| {
+            x.two_column_browse_results_renderer
+                .tabs
+                .into_iter()
+                .find_map(|x| match x {
+                    super::Tab::Some { tab_renderer } => tab_renderer.content.rich_grid_renderer,
+                    super::Tab::None {} => None,
+                })
+                .map(|x| x.contents)
+                .unwrap_or_default()
+        });
+
+        let continuation = self.on_response_received_actions.into_iter().flat_map(|x| {
+            x.append_continuation_items_action
+                .map(|x| x.continuation_items)
+                .unwrap_or_default()
+        });
+
+        tab.chain(continuation)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Content {
+    pub rich_grid_renderer: Option<RichGridRenderer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RichGridRenderer {
+    #[serde(default)]
+    pub contents: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Action {
+    pub append_continuation_items_action: Option<AppendContinuationItemsAction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppendContinuationItemsAction {
+    #[serde(default)]
+    pub continuation_items: Vec<Item>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub rich_item_renderer: Option<RichItemRenderer>,
+    pub continuation_item_renderer: Option<ContinuationItemRenderer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RichItemRenderer {
+    pub content: RichItem,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RichItem {
+    pub reel_item_renderer: Option<ReelItemRenderer>,
+    pub shorts_lockup_view_model: Option<ShortsLockupViewModel>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelItemRenderer {
+    pub video_id: crate::video::Id,
+    pub headline: Text,
+    #[serde(default)]
+    pub thumbnail: Thumbnails,
+    pub view_count_text: Option<Text>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortsLockupViewModel {
+    pub on_tap: OnTap,
+    pub overlay_metadata: OverlayMetadata,
+    pub thumbnail: Option<LockupThumbnail>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnTap {
+    pub innertube_command: InnertubeCommand,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InnertubeCommand {
+    pub reel_watch_endpoint: ReelWatchEndpoint,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelWatchEndpoint {
+    pub video_id: crate::video::Id,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayMetadata {
+    pub primary_text: Option<AttributedString>,
+    pub secondary_text: Option<AttributedString>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributedString {
+    pub content: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LockupThumbnail {
+    #[serde(default)]
+    pub sources: Vec<crate::Thumbnail>,
+}
@@ -0,0 +1,213 @@
+use serde::Deserialize;
+
+use super::{ContinuationEndpoint, NavigationEndpoint, Text};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+    #[serde(default)]
+    pub on_response_received_endpoints: Vec<Endpoint>,
+}
+
+impl Root {
+    pub fn into_items(self) -> impl Iterator<Item = Item> {
+        self.on_response_received_endpoints
+            .into_iter()
+            .filter_map(|x| {
+                x.reload_continuation_items_command
+                    .or(x.append_continuation_items_action)
+            })
+            .flat_map(|x| x.continuation_items)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    pub reload_continuation_items_command: Option<ContinuationItems>,
+    pub append_continuation_items_action: Option<ContinuationItems>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuationItems {
+    #[serde(default)]
+    pub continuation_items: Vec<Item>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Item {
+    pub comments_header_renderer: Option<CommentsHeaderRenderer>,
+    pub comment_thread_renderer: Option<CommentThreadRenderer>,
+    pub comment_renderer: Option<CommentRenderer>,
+    pub continuation_item_renderer: Option<ContinuationItemRenderer>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentsHeaderRenderer {
+    pub sort_menu: Option<SortMenu>,
+}
+
+impl CommentsHeaderRenderer {
+    /// The continuation of the sort option at `index`
+    pub fn sort(&self, index: usize) -> Option<String> {
+        self.sort_menu
+            .as_ref()?
+            .sort_filter_sub_menu_renderer
+            .sub_menu_items
+            .get(index)
+            .map(|x| x.service_endpoint.continuation_command.token.clone())
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SortMenu {
+    pub sort_filter_sub_menu_renderer: SortFilterSubMenuRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SortFilterSubMenuRenderer {
+    #[serde(default)]
+    pub sub_menu_items: Vec<SubMenuItem>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubMenuItem {
+    pub service_endpoint: ContinuationEndpoint,
+}
+
+/// The continuation of comments, or the `Show more replies` button of
+/// replies
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuationItemRenderer {
+    pub continuation_endpoint: Option<ContinuationEndpoint>,
+    pub button: Option<Button>,
+}
+
+impl ContinuationItemRenderer {
+    pub fn token(&self) -> Option<String> {
+        self.continuation_endpoint
+            .as_ref()
+            .or_else(|| Some(&self.button.as_ref()?.button_renderer.command))
+            .map(|x| x.continuation_command.token.clone())
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Button {
+    pub button_renderer: ButtonRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ButtonRenderer {
+    pub command: ContinuationEndpoint,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentThreadRenderer {
+    pub comment: Option<CommentWrapper>,
+    pub replies: Option<Replies>,
+}
+
+impl CommentThreadRenderer {
+    /// The continuation of the replies
+    pub fn replies(&self) -> Option<String> {
+        self.replies
+            .as_ref()?
+            .comment_replies_renderer
+            .contents
+            .iter()
+            .find_map(|x| x.continuation_item_renderer.as_ref()?.token())
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentWrapper {
+    pub comment_renderer: CommentRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Replies {
+    pub comment_replies_renderer: CommentRepliesRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentRepliesRenderer {
+    #[serde(default)]
+    pub contents: Vec<Item>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentRenderer {
+    pub comment_id: String,
+    pub author_text: Option<Text>,
+    pub author_endpoint: Option<NavigationEndpoint>,
+    pub content_text: ContentText,
+    pub published_time_text: Text,
+    pub vote_count: Option<Text>,
+    #[serde(default)]
+    pub reply_count: u64,
+    pub pinned_comment_badge: Option<serde::de::IgnoredAny>,
+    pub action_buttons: Option<ActionButtons>,
+}
+
+impl CommentRenderer {
+    pub fn hearted(&self) -> bool {
+        self.action_buttons
+            .as_ref()
+            .and_then(|x| x.comment_action_buttons_renderer.creator_heart.as_ref())
+            .is_some_and(|x| x.creator_heart_renderer.is_hearted)
+    }
+}
+
+#[derive(Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentText {
+    #[serde(default)]
+    pub runs: Vec<ContentRun>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentRun {
+    #[serde(default)]
+    pub text: String,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionButtons {
+    pub comment_action_buttons_renderer: CommentActionButtonsRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentActionButtonsRenderer {
+    pub creator_heart: Option<CreatorHeart>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatorHeart {
+    pub creator_heart_renderer: CreatorHeartRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatorHeartRenderer {
+    #[serde(default)]
+    pub is_hearted: bool,
+}
@@ -2,7 +2,10 @@ use std::time::Duration;
 
 use serde::Serialize;
 
-use crate::{youtube::player_response, Error};
+use crate::{
+    youtube::{player_response, reel, resolve_url},
+    Error,
+};
 
 const RETRYS: u32 = 5;
 const TIMEOUT: Duration = Duration::from_secs(30);
@@ -52,10 +55,13 @@ struct Client<'a> {
     client_version: &'a str,
 }
 
+#[derive(Clone)]
 pub enum ChannelPage {
     About,
+    Shorts,
 }
 
+#[derive(Clone)]
 pub enum Browse {
     Playlist(crate::playlist::Id),
     Channel {
@@ -73,10 +79,13 @@ pub enum Next {
 #[derive(Clone, Default)]
 pub struct Api {
     pub(crate) http: reqwest::Client,
+    /// Replaces [`BASE_URL`] if set
+    pub(crate) base_url: Option<reqwest::Url>,
 }
 
+#[allow(clippy::needless_borrows_for_generic_args)]
 fn dump(endpoint: &'static str, response: &str) {
-    let _ = std::fs::create_dir(endpoint);
+    let _ = std::fs::create_dir_all(endpoint);
     use std::time::SystemTime;
     let time = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -104,9 +113,14 @@ impl Api {
 
         let request = Request { context, request };
 
+        let base_url = self
+            .base_url
+            .as_ref()
+            .map_or(BASE_URL, |x| x.as_str().trim_end_matches('/'));
+
         let request = self
             .http
-            .post(format!("{}/{}", BASE_URL, endpoint))
+            .post(format!("{}/{}", base_url, endpoint))
             .header("X-Goog-Api-Key", API_KEY)
             .json(&request)
             .timeout(TIMEOUT);
@@ -198,6 +212,24 @@ impl Api {
         self.get("player", request, CONTEXT_ANDROID).await
     }
 
+    pub async fn microformat(
+        &self,
+        id: crate::video::Id,
+    ) -> crate::Result<Option<player_response::PlayerMicroformatRenderer>> {
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request {
+            video_id: crate::video::Id,
+        }
+
+        let request = Request { video_id: id };
+
+        // Only the web client includes the microformat
+        let response: player_response::MicroformatResponse =
+            self.get("player", request, CONTEXT_WEB).await?;
+        Ok(response.microformat.map(|x| x.player_microformat_renderer))
+    }
+
     pub async fn next<T: serde::de::DeserializeOwned>(&self, next: Next) -> crate::Result<T> {
         match next {
             Next::Video(video_id) => {
@@ -225,6 +257,111 @@ impl Api {
         }
     }
 
+    pub async fn get_transcript(
+        &self,
+        params: String,
+    ) -> crate::Result<crate::youtube::transcript::Root> {
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request {
+            params: String,
+        }
+
+        let request = Request { params };
+
+        self.get("get_transcript", request, CONTEXT_WEB).await
+    }
+
+    pub async fn get_live_chat(
+        &self,
+        continuation: String,
+    ) -> crate::Result<crate::youtube::live_chat::Root> {
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request {
+            continuation: String,
+        }
+
+        let request = Request { continuation };
+
+        self.get("live_chat/get_live_chat", request, CONTEXT_WEB)
+            .await
+    }
+
+    pub async fn get_live_chat_replay(
+        &self,
+        continuation: String,
+        offset: Duration,
+    ) -> crate::Result<crate::youtube::live_chat::Root> {
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PlayerState {
+            player_offset_ms: String,
+        }
+
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request {
+            continuation: String,
+            current_player_state: PlayerState,
+        }
+
+        let request = Request {
+            continuation,
+            current_player_state: PlayerState {
+                player_offset_ms: offset.as_millis().to_string(),
+            },
+        };
+
+        self.get("live_chat/get_live_chat_replay", request, CONTEXT_WEB)
+            .await
+    }
+
+    pub async fn reel_item_watch(&self, id: crate::video::Id) -> crate::Result<reel::Root> {
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PlayerRequest {
+            video_id: crate::video::Id,
+        }
+
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request {
+            player_request: PlayerRequest,
+            params: &'static str,
+            disable_player_response: bool,
+        }
+
+        let request = Request {
+            player_request: PlayerRequest { video_id: id },
+            params: "CAUwAg%3D%3D",
+            disable_player_response: true,
+        };
+
+        self.get("reel/reel_item_watch", request, CONTEXT_WEB).await
+    }
+
+    pub async fn resolve_url(&self, url: String) -> crate::Result<resolve_url::Root> {
+        #[derive(Debug, Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request {
+            url: String,
+        }
+
+        let request = Request { url };
+
+        match self
+            .get("navigation/resolve_url", request, CONTEXT_WEB)
+            .await
+        {
+            // Unknown handles and names are reported with a `404`
+            Err(Error::Request(err)) if err.status() == Some(reqwest::StatusCode::NOT_FOUND) => {
+                Err(Error::Youtube(crate::error::Youtube::NotFound))
+            }
+            res => res,
+        }
+    }
+
     pub async fn browse<T: serde::de::DeserializeOwned>(&self, browse: Browse) -> crate::Result<T> {
         #[derive(Debug, Serialize)]
         #[serde(rename_all = "camelCase")]
@@ -243,6 +380,9 @@ impl Api {
                 browse_id: format!("{}", id),
                 params: match page {
                     ChannelPage::About => Some(base64::encode(b"\x12\x05about")),
+                    ChannelPage::Shorts => Some(base64::encode(
+                        b"\x12\x06shorts\xf2\x06\x05\x0a\x03\x9a\x01\x00",
+                    )),
                 },
             },
             Browse::Continuation(continuation) => {
@@ -0,0 +1,192 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+    pub continuation_contents: Option<ContinuationContents>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuationContents {
+    pub live_chat_continuation: LiveChatContinuation,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveChatContinuation {
+    #[serde(default)]
+    pub continuations: Vec<Continuation>,
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+impl LiveChatContinuation {
+    /// The next continuation and the time to wait before requesting it
+    pub fn next(&self) -> Option<&ContinuationData> {
+        self.continuations.iter().find_map(|x| x.data())
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Continuation {
+    pub invalidation_continuation_data: Option<ContinuationData>,
+    pub timed_continuation_data: Option<ContinuationData>,
+    pub reload_continuation_data: Option<ContinuationData>,
+    pub live_chat_replay_continuation_data: Option<ContinuationData>,
+}
+
+impl Continuation {
+    pub fn data(&self) -> Option<&ContinuationData> {
+        self.invalidation_continuation_data
+            .as_ref()
+            .or(self.timed_continuation_data.as_ref())
+            .or(self.reload_continuation_data.as_ref())
+            .or(self.live_chat_replay_continuation_data.as_ref())
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContinuationData {
+    pub continuation: String,
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Action {
+    pub add_chat_item_action: Option<AddChatItemAction>,
+    pub mark_chat_item_as_deleted_action: Option<TargetItem>,
+    pub remove_chat_item_action: Option<TargetItem>,
+    pub mark_chat_items_by_author_as_deleted_action: Option<TargetAuthor>,
+    pub remove_chat_item_by_author_action: Option<TargetAuthor>,
+    pub replay_chat_item_action: Option<ReplayChatItemAction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddChatItemAction {
+    pub item: ChatItem,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetItem {
+    pub target_item_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetAuthor {
+    pub external_channel_id: crate::channel::Id,
+}
+
+#[serde_with::serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayChatItemAction {
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default)]
+    pub video_offset_time_msec: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatItem {
+    pub live_chat_text_message_renderer: Option<ChatItemRenderer>,
+    pub live_chat_paid_message_renderer: Option<ChatItemRenderer>,
+    pub live_chat_paid_sticker_renderer: Option<ChatItemRenderer>,
+    pub live_chat_membership_item_renderer: Option<ChatItemRenderer>,
+}
+
+/// The fields shared by all kinds of chat items
+#[serde_with::serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatItemRenderer {
+    pub id: String,
+    #[serde(default)]
+    pub message: MessageText,
+    pub author_name: Option<MessageText>,
+    pub author_external_channel_id: crate::channel::Id,
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default)]
+    pub timestamp_usec: Option<i64>,
+    pub purchase_amount_text: Option<MessageText>,
+    pub sticker: Option<Sticker>,
+    pub header_primary_text: Option<MessageText>,
+    pub header_subtext: Option<MessageText>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageText {
+    pub simple_text: Option<String>,
+    #[serde(default)]
+    pub runs: Vec<MessageRun>,
+}
+
+impl MessageText {
+    pub fn text(&self) -> String {
+        match &self.simple_text {
+            Some(text) => text.clone(),
+            None => self.runs.iter().map(|x| x.text()).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageRun {
+    pub text: Option<String>,
+    pub emoji: Option<Emoji>,
+}
+
+impl MessageRun {
+    /// The text of a run, with custom emojis replaced by their shortcut
+    pub fn text(&self) -> &str {
+        if let Some(text) = &self.text {
+            return text;
+        }
+
+        match &self.emoji {
+            Some(emoji) if emoji.is_custom_emoji => {
+                emoji.shortcuts.first().unwrap_or(&emoji.emoji_id)
+            }
+            Some(emoji) => &emoji.emoji_id,
+            None => "",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Emoji {
+    pub emoji_id: String,
+    #[serde(default)]
+    pub shortcuts: Vec<String>,
+    #[serde(default)]
+    pub is_custom_emoji: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sticker {
+    pub accessibility: Option<Accessibility>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Accessibility {
+    pub accessibility_data: AccessibilityData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccessibilityData {
+    pub label: String,
+}
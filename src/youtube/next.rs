@@ -1,5 +1,5 @@
 use super::{
-    parse_subscribers, Badge, ChannelNameRuns, ContinuationItemRenderer, SimpleText, Text,
+    parse_subscribers, Badge, ChannelNameRuns, ContinuationItemRenderer, Runs, SimpleText, Text,
     Thumbnails, TitleRun,
 };
 use serde::Deserialize;
@@ -8,6 +8,180 @@ use serde::Deserialize;
 #[serde(rename_all = "camelCase")]
 pub struct Root {
     pub contents: Contents,
+    #[serde(default)]
+    pub engagement_panels: Vec<EngagementPanel>,
+    pub player_overlays: Option<PlayerOverlays>,
+}
+
+impl Root {
+    /// The chapters of the player bar, or of the chapters panel
+    pub fn chapters(&self) -> Vec<ChapterRenderer> {
+        let player_bar = self
+            .player_overlays
+            .as_ref()
+            .and_then(|x| {
+                x.player_overlay_renderer
+                    .decorated_player_bar_renderer
+                    .as_ref()
+            })
+            .and_then(|x| x.decorated_player_bar_renderer.player_bar.as_ref());
+
+        if let Some(player_bar) = player_bar {
+            let markers = player_bar
+                .multi_markers_player_bar_renderer
+                .as_ref()
+                .and_then(|x| {
+                    // Chapters from the description are preferred over
+                    // automatically generated ones
+                    x.markers_map
+                        .iter()
+                        .find(|x| x.key == "DESCRIPTION_CHAPTERS")
+                        .or_else(|| x.markers_map.iter().find(|x| x.key == "AUTO_CHAPTERS"))
+                })
+                .map(|x| &x.value.chapters)
+                .or_else(|| {
+                    player_bar
+                        .chaptered_player_bar_renderer
+                        .as_ref()
+                        .map(|x| &x.chapters)
+                });
+
+            if let Some(markers) = markers.filter(|x| !x.is_empty()) {
+                return markers.iter().map(|x| x.chapter_renderer.clone()).collect();
+            }
+        }
+
+        self.engagement_panels
+            .iter()
+            .filter_map(|x| x.macro_markers())
+            .find(|x| !x.is_empty())
+            .map(|x| x.iter().filter_map(|x| x.chapter()).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerOverlays {
+    pub player_overlay_renderer: PlayerOverlayRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerOverlayRenderer {
+    pub decorated_player_bar_renderer: Option<DecoratedPlayerBarRenderer>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecoratedPlayerBarRenderer {
+    pub decorated_player_bar_renderer: DecoratedPlayerBarRenderer2,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecoratedPlayerBarRenderer2 {
+    pub player_bar: Option<PlayerBar>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerBar {
+    pub multi_markers_player_bar_renderer: Option<MultiMarkersPlayerBarRenderer>,
+    pub chaptered_player_bar_renderer: Option<ChapteredPlayerBarRenderer>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiMarkersPlayerBarRenderer {
+    #[serde(default)]
+    pub markers_map: Vec<Marker>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Marker {
+    pub key: String,
+    pub value: MarkerValue,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkerValue {
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapteredPlayerBarRenderer {
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub chapter_renderer: ChapterRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterRenderer {
+    pub title: SimpleText,
+    pub time_range_start_millis: u64,
+    #[serde(default)]
+    pub thumbnail: Thumbnails,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroMarkersListRenderer {
+    #[serde(default)]
+    pub contents: Vec<MacroMarkersListContent>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroMarkersListContent {
+    pub macro_markers_list_item_renderer: Option<MacroMarkersListItemRenderer>,
+}
+
+impl MacroMarkersListContent {
+    fn chapter(&self) -> Option<ChapterRenderer> {
+        let item = self.macro_markers_list_item_renderer.as_ref()?;
+        let start = item.on_tap.watch_endpoint.start_time_seconds;
+
+        Some(ChapterRenderer {
+            title: SimpleText {
+                simple_text: item.title.text(),
+            },
+            time_range_start_millis: start * 1000,
+            thumbnail: item.thumbnail.clone(),
+        })
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroMarkersListItemRenderer {
+    pub title: Text,
+    #[serde(default)]
+    pub thumbnail: Thumbnails,
+    pub on_tap: OnTap,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnTap {
+    pub watch_endpoint: WatchEndpoint,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchEndpoint {
+    #[serde(default)]
+    pub start_time_seconds: u64,
 }
 
 #[derive(Clone, Deserialize)]
@@ -21,6 +195,22 @@ pub struct Contents {
 pub struct TwoColumnWatchNextResults {
     pub results: Results,
     pub secondary_results: Option<SecondaryResults>,
+    pub conversation_bar: Option<ConversationBar>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationBar {
+    pub live_chat_renderer: Option<LiveChatRenderer>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveChatRenderer {
+    #[serde(default)]
+    pub continuations: Vec<super::live_chat::Continuation>,
+    #[serde(default)]
+    pub is_replay: bool,
 }
 
 #[derive(Clone, Deserialize)]
@@ -84,6 +274,31 @@ pub struct VideoPrimaryInfoRenderer {
     #[serde(default)]
     pub super_title_link: SuperTitleLink,
     pub date_text: SimpleText,
+    pub view_count: Option<ViewCount>,
+    pub upcoming_event_data: Option<UpcomingEventData>,
+}
+
+#[serde_with::serde_as]
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpcomingEventData {
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default)]
+    pub start_time: Option<i64>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewCount {
+    pub video_view_count_renderer: VideoViewCountRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoViewCountRenderer {
+    pub view_count: Option<Text>,
+    #[serde(default)]
+    pub is_live: bool,
 }
 
 impl VideoPrimaryInfoRenderer {
@@ -109,6 +324,29 @@ impl VideoPrimaryInfoRenderer {
         Some(likes)
     }
 
+    /// The scheduled start of an upcoming livestream or premiere
+    pub fn scheduled_start_time(&self) -> Option<i64> {
+        self.upcoming_event_data.as_ref()?.start_time
+    }
+
+    /// The amount of concurrent viewers of a livestream, e.g. from
+    /// `1,234 watching now`
+    pub fn viewers(&self) -> Option<u64> {
+        let renderer = &self.view_count.as_ref()?.video_view_count_renderer;
+        if !renderer.is_live {
+            return None;
+        }
+
+        let text = renderer.view_count.as_ref()?.text();
+        let viewers: String = text
+            .split_whitespace()
+            .next()?
+            .chars()
+            .filter(|x| x.is_ascii_digit())
+            .collect();
+        viewers.parse().ok()
+    }
+
     pub fn hashtags(&self) -> impl Iterator<Item = &str> {
         self.super_title_link.runs.iter().map(|x| x.text.as_str())
     }
@@ -165,7 +403,83 @@ pub struct SuperTitleLink {
 #[serde(rename_all = "camelCase")]
 pub struct VideoSecondaryInfoRenderer {
     pub owner: Owner,
+    #[allow(dead_code)]
     pub metadata_row_container: MetadataRowContainer,
+    pub description: Option<Runs<DescriptionRun>>,
+    pub attributed_description: Option<AttributedDescription>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescriptionRun {
+    pub text: String,
+    pub navigation_endpoint: Option<DescriptionEndpoint>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributedDescription {
+    pub content: String,
+    #[serde(default)]
+    pub command_runs: Vec<CommandRun>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandRun {
+    /// The start of a run in UTF-16 code units
+    pub start_index: usize,
+    /// The length of a run in UTF-16 code units
+    pub length: usize,
+    pub on_tap: Option<CommandRunOnTap>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandRunOnTap {
+    pub innertube_command: DescriptionEndpoint,
+}
+
+/// The target of a link in a description
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescriptionEndpoint {
+    pub command_metadata: Option<CommandMetadata>,
+    pub url_endpoint: Option<UrlEndpoint>,
+    pub watch_endpoint: Option<DescriptionWatchEndpoint>,
+    pub browse_endpoint: Option<DescriptionBrowseEndpoint>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMetadata {
+    pub web_command_metadata: WebCommandMetadata,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebCommandMetadata {
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlEndpoint {
+    pub url: String,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescriptionWatchEndpoint {
+    pub video_id: String,
+    pub start_time_seconds: Option<u64>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DescriptionBrowseEndpoint {
+    pub browse_id: String,
+    pub canonical_base_url: Option<String>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -201,12 +515,14 @@ impl VideoOwnerRenderer {
 #[derive(Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetadataRowContainer {
+    #[allow(dead_code)]
     pub metadata_row_container_renderer: MetadataRowContainerRenderer,
 }
 
 #[derive(Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetadataRowContainerRenderer {
+    #[allow(dead_code)]
     pub collapsed_item_count: i64,
 }
 
@@ -271,6 +587,7 @@ pub struct CompactPlaylistRenderer {
     // channel name
     pub short_byline_text: super::Runs<OptionalChannelNameRun>,
     // length
+    #[allow(dead_code)]
     pub video_count_short_text: SimpleText,
 
     #[serde(default)]
@@ -331,3 +648,82 @@ pub struct OnResponseReceivedAction {
 pub struct AppendContinuationItemsAction {
     pub continuation_items: Vec<RelatedItem>,
 }
+
+/// A engagement panel of the `next` response, e.g. `Show transcript`
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngagementPanel {
+    pub engagement_panel_section_list_renderer: EngagementPanelSectionListRenderer,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngagementPanelSectionListRenderer {
+    pub panel_identifier: Option<String>,
+    pub content: Option<EngagementPanelContent>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EngagementPanelContent {
+    pub continuation_item_renderer: Option<TranscriptContinuationItemRenderer>,
+    pub macro_markers_list_renderer: Option<MacroMarkersListRenderer>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptContinuationItemRenderer {
+    pub continuation_endpoint: TranscriptContinuationEndpoint,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptContinuationEndpoint {
+    pub get_transcript_endpoint: Option<GetTranscriptEndpoint>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTranscriptEndpoint {
+    pub params: String,
+}
+
+impl EngagementPanel {
+    /// The params of the `get_transcript` request, if this is the
+    /// transcript panel
+    pub fn transcript_params(&self) -> Option<&str> {
+        let renderer = &self.engagement_panel_section_list_renderer;
+        if renderer.panel_identifier.as_deref() != Some("engagement-panel-searchable-transcript") {
+            return None;
+        }
+
+        renderer
+            .content
+            .as_ref()?
+            .continuation_item_renderer
+            .as_ref()?
+            .continuation_endpoint
+            .get_transcript_endpoint
+            .as_ref()
+            .map(|x| x.params.as_str())
+    }
+
+    /// The items of a chapters panel
+    pub fn macro_markers(&self) -> Option<&[MacroMarkersListContent]> {
+        let renderer = &self.engagement_panel_section_list_renderer;
+        if !renderer
+            .panel_identifier
+            .as_deref()?
+            .starts_with("engagement-panel-macro-markers")
+        {
+            return None;
+        }
+
+        renderer
+            .content
+            .as_ref()?
+            .macro_markers_list_renderer
+            .as_ref()
+            .map(|x| x.contents.as_slice())
+    }
+}
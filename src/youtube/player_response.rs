@@ -4,7 +4,7 @@ use crate::error::Youtube;
 use reqwest::Url;
 use serde::Deserialize;
 
-use super::Thumbnails;
+use super::{Text, Thumbnails};
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase", untagged)]
@@ -31,6 +31,163 @@ impl<T> Result<T> {
 #[serde(rename_all = "camelCase")]
 pub struct PlayerResponse {
     pub video_details: VideoDetails,
+    pub storyboards: Option<Storyboards>,
+    pub captions: Option<Captions>,
+    pub playability_status: Option<Playability>,
+    pub streaming_data: Option<PlayerStreamingData>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerStreamingData {
+    pub hls_manifest_url: Option<Url>,
+    pub dash_manifest_url: Option<Url>,
+    #[serde(default)]
+    pub adaptive_formats: Vec<FormatSize>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FormatSize {
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Playability {
+    pub live_streamability: Option<LiveStreamability>,
+}
+
+impl Playability {
+    /// The scheduled start of an upcoming livestream or premiere
+    pub fn scheduled_start_time(&self) -> Option<i64> {
+        self.live_streamability
+            .as_ref()?
+            .live_streamability_renderer
+            .offline_slate
+            .as_ref()?
+            .live_stream_offline_slate_renderer
+            .scheduled_start_time
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStreamability {
+    pub live_streamability_renderer: LiveStreamabilityRenderer,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStreamabilityRenderer {
+    pub offline_slate: Option<OfflineSlate>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OfflineSlate {
+    pub live_stream_offline_slate_renderer: LiveStreamOfflineSlateRenderer,
+}
+
+#[serde_with::serde_as]
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveStreamOfflineSlateRenderer {
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default)]
+    pub scheduled_start_time: Option<i64>,
+}
+
+/// The part of a player response of the web client that the android client
+/// does not include
+#[derive(Deserialize)]
+pub struct MicroformatResponse {
+    pub microformat: Option<Microformat>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Microformat {
+    pub player_microformat_renderer: PlayerMicroformatRenderer,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerMicroformatRenderer {
+    pub category: Option<String>,
+    pub publish_date: Option<String>,
+    pub upload_date: Option<String>,
+    pub is_family_safe: Option<bool>,
+    pub is_unlisted: Option<bool>,
+    #[serde(default)]
+    pub available_countries: Vec<String>,
+    #[serde(default)]
+    pub has_ypc_metadata: bool,
+    pub live_broadcast_details: Option<LiveBroadcastDetails>,
+    pub embed: Option<Embed>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveBroadcastDetails {
+    #[serde(default)]
+    pub is_live_now: bool,
+    pub start_timestamp: Option<String>,
+    pub end_timestamp: Option<String>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Embed {
+    pub iframe_url: String,
+    pub width: Option<u64>,
+    pub height: Option<u64>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Captions {
+    pub player_captions_tracklist_renderer: CaptionsTracklistRenderer,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptionsTracklistRenderer {
+    #[serde(default)]
+    pub caption_tracks: Vec<CaptionTrack>,
+    #[serde(default)]
+    pub translation_languages: Vec<TranslationLanguage>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptionTrack {
+    pub base_url: Url,
+    pub name: Text,
+    pub language_code: String,
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub is_translatable: bool,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationLanguage {
+    pub language_code: String,
+    pub language_name: Text,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Storyboards {
+    pub player_storyboard_spec_renderer: Option<StoryboardSpecRenderer>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct StoryboardSpecRenderer {
+    pub spec: String,
 }
 
 #[serde_with::serde_as]
@@ -49,9 +206,16 @@ pub struct VideoDetails {
     pub author: String,
     pub short_description: String,
     #[serde_as(as = "serde_with::DisplayFromStr")]
+    #[serde(default)]
     pub view_count: u64,
 
     pub is_live_content: bool,
+    #[serde(default)]
+    pub is_live: bool,
+    #[serde(default)]
+    pub is_upcoming: bool,
+    #[serde(default)]
+    pub is_post_live_dvr: bool,
 
     pub thumbnail: Thumbnails,
 }
@@ -67,6 +231,7 @@ pub struct StreamPlayerResponse {
 pub struct StreamingData {
     #[serde(default)]
     pub adaptive_formats: Vec<Format>,
+    pub hls_manifest_url: Option<Url>,
 }
 
 #[derive(Deserialize, Clone)]
@@ -88,9 +253,39 @@ pub struct CommonFormat {
     #[serde(default)]
     pub content_length: Option<u64>,
     pub bitrate: u64,
+    pub average_bitrate: Option<u64>,
     #[serde_as(as = "Option<serde_with::DurationMilliSeconds<String>>")]
     #[serde(default, rename = "approxDurationMs")]
     pub duration: Option<Duration>,
+    #[serde(default)]
+    pub quality: String,
+    pub init_range: Option<ByteRange>,
+    pub index_range: Option<ByteRange>,
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default)]
+    pub last_modified: Option<i64>,
+    #[serde(default)]
+    pub high_replication: bool,
+    pub xtags: Option<String>,
+    #[serde(default)]
+    pub drm_families: Vec<String>,
+}
+
+#[serde_with::serde_as]
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ByteRange {
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub start: u64,
+    #[serde_as(as = "serde_with::DisplayFromStr")]
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// The range as a exclusive [`Range`](std::ops::Range)
+    pub fn range(&self) -> std::ops::Range<u64> {
+        self.start..self.end.saturating_add(1)
+    }
 }
 
 #[derive(Deserialize, Clone)]
@@ -107,6 +302,9 @@ pub struct VideoFormat {
     pub height: u64,
     pub fps: u64,
     pub quality_label: String,
+    #[serde(default)]
+    pub projection_type: crate::stream::Projection,
+    pub color_info: Option<crate::stream::ColorInfo>,
 }
 
 #[serde_with::serde_as]
@@ -118,6 +316,9 @@ pub struct AudioFormat {
     pub audio_sample_rate: u64,
     pub audio_quality: String,
     pub audio_channels: u64,
+    #[serde(default)]
+    pub is_drc: bool,
+    pub audio_track: Option<crate::stream::AudioTrack>,
 }
 
 #[derive(Deserialize)]
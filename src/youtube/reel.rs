@@ -0,0 +1,187 @@
+use serde::Deserialize;
+
+use super::Text;
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+    pub overlay: Option<Overlay>,
+    /// Set if the video is not a Short and should be watched normally
+    pub replacement_endpoint: Option<ReplacementEndpoint>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacementEndpoint {
+    pub watch_endpoint: Option<serde::de::IgnoredAny>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Overlay {
+    pub reel_player_overlay_renderer: Option<ReelPlayerOverlayRenderer>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelPlayerOverlayRenderer {
+    pub reel_player_header_supported_renderers: Option<HeaderSupportedRenderers>,
+    pub metapanel: Option<Metapanel>,
+    pub multimix_attribution_label: Option<MultimixAttributionLabel>,
+    pub pivot_button: Option<PivotButton>,
+}
+
+impl ReelPlayerOverlayRenderer {
+    fn header(&self) -> Option<&ReelPlayerHeaderRenderer> {
+        self.reel_player_header_supported_renderers
+            .as_ref()?
+            .reel_player_header_renderer
+            .as_ref()
+    }
+
+    fn metadata_items(&self) -> impl Iterator<Item = &MetadataItem> {
+        self.metapanel
+            .iter()
+            .filter_map(|x| x.reel_metapanel_view_model.as_ref())
+            .flat_map(|x| &x.metadata_items)
+    }
+
+    pub fn title(&self) -> Option<String> {
+        self.header()
+            .and_then(|x| x.reel_title_text.as_ref())
+            .map(Text::text)
+            .or_else(|| {
+                self.metadata_items()
+                    .find_map(|x| x.shorts_video_title_view_model.as_ref())
+                    .map(|x| x.text.content.clone())
+            })
+    }
+
+    pub fn sound(&self) -> Option<String> {
+        self.metadata_items()
+            .find_map(|x| x.reel_sound_metadata_view_model.as_ref())
+            .map(|x| x.sound_metadata.content.clone())
+            .or_else(|| {
+                self.pivot_button
+                    .as_ref()?
+                    .pivot_button_renderer
+                    .as_ref()?
+                    .sound_attribution_title
+                    .as_ref()
+                    .map(Text::text)
+            })
+    }
+
+    pub fn remix(&self) -> Option<&ReelMultimixAttributionLabelRenderer> {
+        self.multimix_attribution_label
+            .as_ref()?
+            .reel_multimix_attribution_label_renderer
+            .as_ref()
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderSupportedRenderers {
+    pub reel_player_header_renderer: Option<ReelPlayerHeaderRenderer>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelPlayerHeaderRenderer {
+    pub reel_title_text: Option<Text>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Metapanel {
+    pub reel_metapanel_view_model: Option<ReelMetapanelViewModel>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelMetapanelViewModel {
+    #[serde(default)]
+    pub metadata_items: Vec<MetadataItem>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataItem {
+    pub shorts_video_title_view_model: Option<ShortsVideoTitleViewModel>,
+    pub reel_sound_metadata_view_model: Option<ReelSoundMetadataViewModel>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortsVideoTitleViewModel {
+    pub text: Content,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelSoundMetadataViewModel {
+    pub sound_metadata: Content,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Content {
+    pub content: String,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultimixAttributionLabel {
+    pub reel_multimix_attribution_label_renderer: Option<ReelMultimixAttributionLabelRenderer>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelMultimixAttributionLabelRenderer {
+    pub text: Text,
+    pub on_tap: Option<OnTap>,
+}
+
+impl ReelMultimixAttributionLabelRenderer {
+    pub fn video_id(&self) -> Option<crate::video::Id> {
+        Some(
+            self.on_tap
+                .as_ref()?
+                .innertube_command
+                .reel_watch_endpoint
+                .as_ref()?
+                .video_id,
+        )
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OnTap {
+    pub innertube_command: InnertubeCommand,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct InnertubeCommand {
+    pub reel_watch_endpoint: Option<ReelWatchEndpoint>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelWatchEndpoint {
+    pub video_id: crate::video::Id,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PivotButton {
+    pub pivot_button_renderer: Option<PivotButtonRenderer>,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PivotButtonRenderer {
+    pub sound_attribution_title: Option<Text>,
+}
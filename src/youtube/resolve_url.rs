@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+    pub endpoint: Option<Endpoint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Endpoint {
+    pub watch_endpoint: Option<WatchEndpoint>,
+    pub reel_watch_endpoint: Option<ReelWatchEndpoint>,
+    pub browse_endpoint: Option<BrowseEndpoint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchEndpoint {
+    pub video_id: Option<crate::video::Id>,
+    pub start_time_seconds: Option<u64>,
+    pub playlist_id: Option<String>,
+    pub index: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReelWatchEndpoint {
+    pub video_id: Option<crate::video::Id>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BrowseEndpoint {
+    /// A channel id like `UC...` or a playlist id prefixed with `VL`
+    pub browse_id: String,
+}
@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::Text;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Root {
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+impl Root {
+    pub fn into_panel(self) -> Option<TranscriptSearchPanelRenderer> {
+        self.actions.into_iter().find_map(|x| {
+            // Other engagement panels have no transcript renderer
+            Some(
+                x.update_engagement_panel_action?
+                    .content
+                    .transcript_renderer?
+                    .content
+                    .transcript_search_panel_renderer,
+            )
+        })
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Action {
+    pub update_engagement_panel_action: Option<UpdateEngagementPanelAction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateEngagementPanelAction {
+    pub content: UpdateContent,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateContent {
+    pub transcript_renderer: Option<TranscriptRenderer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptRenderer {
+    pub content: TranscriptContent,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptContent {
+    pub transcript_search_panel_renderer: TranscriptSearchPanelRenderer,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSearchPanelRenderer {
+    pub body: Body,
+    pub footer: Option<Footer>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Body {
+    pub transcript_segment_list_renderer: TranscriptSegmentListRenderer,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegmentListRenderer {
+    #[serde(default)]
+    pub initial_segments: Vec<InitialSegment>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitialSegment {
+    pub transcript_segment_renderer: Option<TranscriptSegmentRenderer>,
+}
+
+#[serde_with::serde_as]
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegmentRenderer {
+    #[serde_as(as = "serde_with::DurationMilliSeconds<String>")]
+    pub start_ms: Duration,
+    #[serde_as(as = "serde_with::DurationMilliSeconds<String>")]
+    pub end_ms: Duration,
+    pub snippet: Option<Text>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Footer {
+    pub transcript_footer_renderer: TranscriptFooterRenderer,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptFooterRenderer {
+    pub language_menu: LanguageMenu,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageMenu {
+    pub sort_filter_sub_menu_renderer: SortFilterSubMenuRenderer,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SortFilterSubMenuRenderer {
+    #[serde(default)]
+    pub sub_menu_items: Vec<SubMenuItem>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubMenuItem {
+    pub title: String,
+    #[serde(default)]
+    pub selected: bool,
+    pub continuation: SubMenuContinuation,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubMenuContinuation {
+    pub reload_continuation_data: ReloadContinuationData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadContinuationData {
+    pub continuation: String,
+}
@@ -30,6 +30,30 @@ async fn eq() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[tokio::test]
+async fn shorts() -> Result<(), Box<dyn std::error::Error>> {
+    use futures::StreamExt;
+
+    let channel = Client::new()
+        .channel("UCX6OQ3DkcsbYNE6H8uQQuVA".parse()?)
+        .await?;
+
+    let shorts = channel
+        .shorts()
+        .take(50)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(shorts.len(), 50);
+    for short in &shorts {
+        assert!(!short.title().is_empty());
+        assert!(short.thumbnails().next().is_some());
+    }
+
+    Ok(())
+}
+
 mod metadata {
     use ytextract::Client;
 
@@ -0,0 +1,169 @@
+// Not every test uses every helper
+#![allow(dead_code)]
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// A request received by [`serve_with`]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// The value of the `Range` header
+    pub range: Option<String>,
+}
+
+/// A response sent by [`serve_with`]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl Response {
+    /// A response with a `Content-Length` header
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        let body = body.into();
+        Self {
+            status,
+            headers: vec![("Content-Length", body.len().to_string())],
+            body,
+        }
+    }
+
+    /// Add the header `name`
+    pub fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+}
+
+/// Serve HTTP requests on a local port. The `handler` gets the path of a
+/// request and how often that path was requested before, and returns the
+/// status and body of the response.
+pub async fn serve(
+    handler: impl Fn(&str, usize) -> (u16, String) + Send + Sync + 'static,
+) -> reqwest::Url {
+    serve_with(move |request, count| {
+        let (status, body) = handler(&request.path, count);
+        Response::new(status, body)
+    })
+    .await
+}
+
+/// Serve HTTP requests on a local port. The `handler` gets a request and how
+/// often its method and path were requested before.
+pub async fn serve_with(
+    handler: impl Fn(&Request, usize) -> Response + Send + Sync + 'static,
+) -> reqwest::Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let handler = Arc::new(handler);
+    let requests = Arc::new(Mutex::new(HashMap::<(String, String), usize>::new()));
+
+    tokio::spawn(async move {
+        loop {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let handler = handler.clone();
+            let requests = requests.clone();
+
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                let header_end = loop {
+                    if let Some(end) = request.windows(4).position(|x| x == b"\r\n\r\n") {
+                        break end + 4;
+                    }
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    if read == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                };
+
+                let header = String::from_utf8_lossy(&request[..header_end]).into_owned();
+                let mut words = header.split_whitespace();
+                let method = words.next().unwrap().to_string();
+                let path = words.next().unwrap().to_string();
+
+                let header_value = |name: &str| {
+                    header
+                        .lines()
+                        .filter_map(|x| x.split_once(':'))
+                        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                        .map(|(_, value)| value.trim().to_string())
+                };
+
+                // Read the body of a POST request, so closing the socket does
+                // not reset the connection
+                let content_length =
+                    header_value("content-length").map_or(0, |x| x.parse::<usize>().unwrap());
+                while request.len() < header_end + content_length {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    if read == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+
+                let request = Request {
+                    range: header_value("range"),
+                    method,
+                    path,
+                };
+
+                let count = {
+                    let mut requests = requests.lock().unwrap();
+                    let count = requests
+                        .entry((request.method.clone(), request.path.clone()))
+                        .or_default();
+                    *count += 1;
+                    *count - 1
+                };
+
+                let response = handler(&request, count);
+                let mut head = format!("HTTP/1.1 {} X\r\n", response.status);
+                for (name, value) in &response.headers {
+                    head += &format!("{}: {}\r\n", name, value);
+                }
+                head += "Connection: close\r\n\r\n";
+
+                socket.write_all(head.as_bytes()).await.unwrap();
+                if request.method != "HEAD" {
+                    socket.write_all(response.body.as_bytes()).await.unwrap();
+                }
+                socket.shutdown().await.unwrap();
+            });
+        }
+    });
+
+    format!("http://{}/", address).parse().unwrap()
+}
+
+/// A player response of the android client with a single audio stream at
+/// `url` of the itag `itag`
+pub fn stream_response(url: &reqwest::Url, itag: u64) -> String {
+    format!(
+        r#"{{
+            "streamingData": {{
+                "adaptiveFormats": [{{
+                    "url": "{}",
+                    "mimeType": "audio/webm; codecs=\"opus\"",
+                    "itag": {},
+                    "bitrate": 128000,
+                    "quality": "tiny",
+                    "audioSampleRate": "48000",
+                    "audioQuality": "AUDIO_QUALITY_MEDIUM",
+                    "audioChannels": 2
+                }}]
+            }}
+        }}"#,
+        url, itag
+    )
+}
@@ -0,0 +1,350 @@
+mod common;
+
+use std::time::Duration;
+
+use ytextract::{
+    link::{Channel, Resolved, YoutubeUrl},
+    Client,
+};
+
+macro_rules! define_test {
+    ($fn:ident, $url:literal, $expected:expr, $canonical:literal) => {
+        #[test]
+        fn $fn() -> Result<(), Box<dyn std::error::Error>> {
+            let url: YoutubeUrl = $url.parse()?;
+            assert_eq!(url, $expected);
+            assert_eq!(url.to_string(), $canonical);
+            assert_eq!(url.to_string().parse::<YoutubeUrl>()?, url);
+            Ok(())
+        }
+    };
+}
+
+fn video(id: &str, start: Option<u64>) -> YoutubeUrl {
+    YoutubeUrl::Video {
+        id: id.parse().unwrap(),
+        start: start.map(Duration::from_secs),
+        playlist: None,
+        index: None,
+    }
+}
+
+define_test!(
+    watch,
+    "https://www.youtube.com/watch?v=7B2PIVSWtJA",
+    video("7B2PIVSWtJA", None),
+    "https://www.youtube.com/watch?v=7B2PIVSWtJA"
+);
+define_test!(
+    watch_playlist,
+    "https://m.youtube.com/watch?v=7B2PIVSWtJA&list=PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx&index=3&t=1h2m3s",
+    YoutubeUrl::Video {
+        id: "7B2PIVSWtJA".parse()?,
+        start: Some(Duration::from_secs(3723)),
+        playlist: Some("PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx".parse()?),
+        index: Some(3),
+    },
+    "https://www.youtube.com/watch?v=7B2PIVSWtJA&list=PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx&index=3&t=3723s"
+);
+define_test!(
+    short_link,
+    "youtu.be/7B2PIVSWtJA?si=abc&t=42",
+    video("7B2PIVSWtJA", Some(42)),
+    "https://www.youtube.com/watch?v=7B2PIVSWtJA&t=42s"
+);
+define_test!(
+    nocookie,
+    "https://www.youtube-nocookie.com/embed/7B2PIVSWtJA?start=10",
+    video("7B2PIVSWtJA", Some(10)),
+    "https://www.youtube.com/watch?v=7B2PIVSWtJA&t=10s"
+);
+define_test!(
+    live,
+    "https://www.youtube.com/live/jfKfPfyJRdk?feature=share",
+    video("jfKfPfyJRdk", None),
+    "https://www.youtube.com/watch?v=jfKfPfyJRdk"
+);
+define_test!(
+    shorts,
+    "https://youtube.com/shorts/BGQWPY4IigY",
+    video("BGQWPY4IigY", None),
+    "https://www.youtube.com/watch?v=BGQWPY4IigY"
+);
+define_test!(
+    attribution_link,
+    "https://www.youtube.com/attribution_link?a=abc&u=%2Fwatch%3Fv%3D7B2PIVSWtJA%26feature%3Dshare",
+    video("7B2PIVSWtJA", None),
+    "https://www.youtube.com/watch?v=7B2PIVSWtJA"
+);
+define_test!(
+    clip,
+    "https://www.youtube.com/clip/UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb",
+    YoutubeUrl::Clip("UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb".to_string()),
+    "https://www.youtube.com/clip/UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb"
+);
+define_test!(
+    playlist,
+    "https://music.youtube.com/playlist?list=PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx",
+    YoutubeUrl::Playlist("PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx".parse()?),
+    "https://www.youtube.com/playlist?list=PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx"
+);
+define_test!(
+    channel,
+    "https://www.youtube.com/channel/UCdktGrgQlqxPsvHo6cHF0Ng/videos",
+    YoutubeUrl::Channel(Channel::Id("UCdktGrgQlqxPsvHo6cHF0Ng".parse()?)),
+    "https://www.youtube.com/channel/UCdktGrgQlqxPsvHo6cHF0Ng"
+);
+define_test!(
+    handle,
+    "@LinusTechTips",
+    YoutubeUrl::Channel(Channel::Handle("LinusTechTips".to_string())),
+    "https://www.youtube.com/@LinusTechTips"
+);
+define_test!(
+    custom,
+    "https://www.youtube.com/c/LinusTechTips",
+    YoutubeUrl::Channel(Channel::Custom("LinusTechTips".to_string())),
+    "https://www.youtube.com/c/LinusTechTips"
+);
+define_test!(
+    user,
+    "https://www.youtube.com/user/LinusTechTips",
+    YoutubeUrl::Channel(Channel::User("LinusTechTips".to_string())),
+    "https://www.youtube.com/user/LinusTechTips"
+);
+
+#[test]
+fn error() {
+    use ytextract::error::Url;
+
+    assert!(matches!(
+        "https://example.com/watch?v=7B2PIVSWtJA".parse::<YoutubeUrl>(),
+        Err(Url::NotYoutube(_))
+    ));
+    assert!(matches!(
+        "https://www.youtube.com/feed/trending".parse::<YoutubeUrl>(),
+        Err(Url::Unsupported(_))
+    ));
+    assert!(matches!(
+        "https://youtu.be/abc".parse::<YoutubeUrl>(),
+        Err(Url::InvalidId(_))
+    ));
+}
+
+#[test]
+fn overflowing_time() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        "https://youtu.be/7B2PIVSWtJA?t=9999999999999999999h".parse::<YoutubeUrl>()?,
+        video("7B2PIVSWtJA", None)
+    );
+    assert_eq!(
+        "https://youtu.be/7B2PIVSWtJA?t=18446744073709551615s1s".parse::<YoutubeUrl>()?,
+        video("7B2PIVSWtJA", None)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn id_from_url() -> Result<(), Box<dyn std::error::Error>> {
+    let video: ytextract::video::Id = "7B2PIVSWtJA".parse()?;
+    for url in [
+        "https://www.youtube.com/watch?feature=share&v=7B2PIVSWtJA",
+        "https://youtu.be/7B2PIVSWtJA?t=42",
+        "music.youtube.com/watch?v=7B2PIVSWtJA&list=PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx",
+        "https://www.youtube.com/shorts/7B2PIVSWtJA",
+        "watch?v=7B2PIVSWtJA",
+    ] {
+        assert_eq!(url.parse::<ytextract::video::Id>()?, video, "{}", url);
+    }
+
+    let channel: ytextract::channel::Id = "UCdktGrgQlqxPsvHo6cHF0Ng".parse()?;
+    assert_eq!(
+        "https://m.youtube.com/channel/UCdktGrgQlqxPsvHo6cHF0Ng/videos"
+            .parse::<ytextract::channel::Id>()?,
+        channel
+    );
+
+    let playlist: ytextract::playlist::Id = "PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx".parse()?;
+    assert_eq!(
+        "https://www.youtube.com/playlist?list=PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx&si=abc"
+            .parse::<ytextract::playlist::Id>()?,
+        playlist
+    );
+
+    // A URL of another entity or host is no id
+    assert!(
+        "https://www.youtube.com/playlist?list=PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx"
+            .parse::<ytextract::video::Id>()
+            .is_err()
+    );
+    assert!("https://example.com/watch?v=7B2PIVSWtJA"
+        .parse::<ytextract::video::Id>()
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn id_with_query() -> Result<(), Box<dyn std::error::Error>> {
+    let video = Client::new()
+        .video("https://youtu.be/7B2PIVSWtJA?t=42".parse()?)
+        .await?;
+    assert_eq!(video.id(), "7B2PIVSWtJA".parse()?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resolve_without_request() -> Result<(), Box<dyn std::error::Error>> {
+    // A unreachable API proves that no request is made
+    let client = Client::new().with_api_url("http://127.0.0.1:1/".parse()?);
+
+    assert_eq!(
+        client.resolve("youtu.be/7B2PIVSWtJA?t=42").await?,
+        Resolved::Video {
+            id: "7B2PIVSWtJA".parse()?,
+            start: Some(Duration::from_secs(42)),
+            playlist: None,
+            index: None,
+        }
+    );
+    assert_eq!(
+        client
+            .resolve("https://www.youtube.com/channel/UCXuqSBlHAE6Xw-yeJA0Tunw")
+            .await?,
+        Resolved::Channel("UCXuqSBlHAE6Xw-yeJA0Tunw".parse()?)
+    );
+    assert!(matches!(
+        client.resolve("https://example.com/@LinusTechTips").await,
+        Err(ytextract::Error::Url(ytextract::error::Url::NotYoutube(_)))
+    ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resolve_without_video_id() -> Result<(), Box<dyn std::error::Error>> {
+    let api = common::serve(|path, _| match path {
+        "/navigation/resolve_url" => (200, r#"{ "endpoint": { "watchEndpoint": {} } }"#.into()),
+        _ => (404, String::new()),
+    })
+    .await;
+
+    assert!(matches!(
+        Client::new()
+            .with_api_url(api)
+            .resolve("https://www.youtube.com/clip/UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb")
+            .await,
+        Err(ytextract::Error::Url(ytextract::error::Url::Unsupported(_)))
+    ));
+
+    Ok(())
+}
+
+/// Resolve `url` with a API that answers with the `endpoint`
+async fn resolve_to(endpoint: &str, url: &str) -> ytextract::Result<Resolved> {
+    let response = format!(r#"{{ "endpoint": {} }}"#, endpoint);
+    let api = common::serve(move |path, _| match path {
+        "/navigation/resolve_url" => (200, response.clone()),
+        _ => (404, String::new()),
+    })
+    .await;
+
+    Client::new().with_api_url(api).resolve(url).await
+}
+
+#[tokio::test]
+async fn resolve_endpoints() -> Result<(), Box<dyn std::error::Error>> {
+    // The `VL` prefix of a playlist is stripped
+    assert_eq!(
+        resolve_to(
+            r#"{ "browseEndpoint": { "browseId": "VLPLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx" } }"#,
+            "@LinusTechTips",
+        )
+        .await?,
+        Resolved::Playlist("PLHJH2BlYG-EEBtw2y1njWpDukJSTs8Qqx".parse()?)
+    );
+    assert_eq!(
+        resolve_to(
+            r#"{ "browseEndpoint": { "browseId": "UCXuqSBlHAE6Xw-yeJA0Tunw" } }"#,
+            "youtube.com/c/LinusTechTips",
+        )
+        .await?,
+        Resolved::Channel("UCXuqSBlHAE6Xw-yeJA0Tunw".parse()?)
+    );
+    assert_eq!(
+        resolve_to(
+            r#"{ "reelWatchEndpoint": { "videoId": "BGQWPY4IigY" } }"#,
+            "https://www.youtube.com/user/LinusTechTips",
+        )
+        .await?,
+        Resolved::Video {
+            id: "BGQWPY4IigY".parse()?,
+            start: None,
+            playlist: None,
+            index: None,
+        }
+    );
+    assert_eq!(
+        resolve_to(
+            r#"{ "watchEndpoint": { "videoId": "7B2PIVSWtJA", "startTimeSeconds": 10 } }"#,
+            "https://www.youtube.com/clip/UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb",
+        )
+        .await?,
+        Resolved::Clip {
+            id: "UgkxU3Gn1qd6hVvUvNE3WPnbqrbxXjBp5vHb".to_string(),
+            video: "7B2PIVSWtJA".parse()?,
+            start: Some(Duration::from_secs(10)),
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resolve_unsupported() {
+    for endpoint in [
+        "null",
+        r#"{ "reelWatchEndpoint": {} }"#,
+        // Other pages than channels and playlists
+        r#"{ "browseEndpoint": { "browseId": "FEtrending" } }"#,
+    ] {
+        assert!(
+            matches!(
+                resolve_to(endpoint, "@LinusTechTips").await,
+                Err(ytextract::Error::Url(ytextract::error::Url::Unsupported(_)))
+            ),
+            "{}",
+            endpoint
+        );
+    }
+}
+
+#[tokio::test]
+async fn resolve_channel() -> Result<(), Box<dyn std::error::Error>> {
+    let expected = Resolved::Channel("UCXuqSBlHAE6Xw-yeJA0Tunw".parse()?);
+
+    for url in [
+        "@LinusTechTips",
+        "https://www.youtube.com/@LinusTechTips",
+        "youtube.com/c/LinusTechTips",
+        "https://www.youtube.com/user/LinusTechTips",
+    ] {
+        assert_eq!(Client::new().resolve(url).await?, expected);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn resolve_not_found() {
+    assert!(matches!(
+        Client::new()
+            .resolve("https://www.youtube.com/@ThisHandleDoesNotExist3141592653")
+            .await,
+        Err(ytextract::Error::Youtube(
+            ytextract::error::Youtube::NotFound
+        ))
+    ));
+}
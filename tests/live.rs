@@ -0,0 +1,251 @@
+mod common;
+
+use common::serve;
+use futures::StreamExt;
+use ytextract::{
+    video::{
+        live::{Event, Record},
+        LiveStatus,
+    },
+    Client,
+};
+
+/// A player response of the video `jfKfPfyJRdk` with the given details and
+/// streaming data
+fn player_response(details: &str, streaming_data: &str) -> String {
+    format!(
+        r#"{{
+            "playabilityStatus": {{}},
+            "videoDetails": {{
+                "title": "Premiere",
+                "videoId": "jfKfPfyJRdk",
+                "lengthSeconds": "0",
+                "channelId": "UCSJ4gkVC6NrvII8umztf0Ow",
+                "author": "Author",
+                "shortDescription": "",
+                "viewCount": "0",
+                "thumbnail": {{ "thumbnails": [] }},
+                {}
+            }}
+            {}
+        }}"#,
+        details, streaming_data
+    )
+}
+
+#[tokio::test]
+async fn wait_premiere_ended() -> Result<(), Box<dyn std::error::Error>> {
+    // The premiere is upcoming on the first poll and already over, as a
+    // regular video with streams, on the second
+    let url = serve(|path, count| match (path, count) {
+        ("/player", 0) => (
+            200,
+            player_response(r#""isLiveContent": false, "isUpcoming": true"#, ""),
+        ),
+        ("/player", _) => (
+            200,
+            player_response(
+                r#""isLiveContent": false"#,
+                r#", "streamingData": { "adaptiveFormats": [{}] }"#,
+            ),
+        ),
+        _ => (404, String::new()),
+    })
+    .await;
+
+    let client = Client::new().with_api_url(url);
+    let events = client
+        .wait_for_live("jfKfPfyJRdk".parse()?)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    assert_eq!(events.len(), 3);
+    assert_eq!(
+        events[0],
+        Event::Status(LiveStatus::Upcoming {
+            scheduled_start: None
+        })
+    );
+    assert_eq!(events[1], Event::Status(LiveStatus::NotLive));
+    match &events[2] {
+        Event::Available(available) => assert!(available.has_adaptive_formats()),
+        event => panic!("expected the streams to be available, got {:?}", event),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wait_not_found() -> Result<(), Box<dyn std::error::Error>> {
+    // A client error is permanent and ends the stream instead of being retried
+    let url = serve(|_, _| (404, String::new())).await;
+
+    let client = Client::new().with_api_url(url);
+    let events = client
+        .wait_for_live("jfKfPfyJRdk".parse()?)
+        .collect::<Vec<_>>()
+        .await;
+
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], Err(ytextract::Error::Request(_))));
+
+    Ok(())
+}
+
+/// A media playlist with the segments `window`, named by `name`
+fn media_playlist(
+    window: std::ops::Range<u64>,
+    name: impl Fn(u64) -> String,
+    end_list: bool,
+) -> String {
+    let mut playlist = format!(
+        "#EXTM3U\n#EXT-X-VERSION:3\n#EXT-X-TARGETDURATION:1\n#EXT-X-MEDIA-SEQUENCE:{}\n",
+        window.start
+    );
+    for sequence in window {
+        playlist += &format!("#EXTINF:1.0,\n{}\n", name(sequence));
+    }
+    if end_list {
+        playlist += "#EXT-X-ENDLIST\n";
+    }
+    playlist
+}
+
+#[tokio::test]
+async fn record_dvr() -> Result<(), Box<dyn std::error::Error>> {
+    let url = serve(|path, _| match path {
+        "/master.m3u8" => (
+            200,
+            "#EXTM3U\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=100000,RESOLUTION=256x144\n\
+             low/index.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=1000000,RESOLUTION=1280x720,CODECS=\"avc1.4d401f,mp4a.40.2\"\n\
+             high/index.m3u8\n\
+             #EXT-X-STREAM-INF:BANDWIDTH=3000000,RESOLUTION=1920x1080\n\
+             full/index.m3u8\n"
+                .to_string(),
+        ),
+        // The escaped `/` has to survive rewriting the sequence number
+        "/high/index.m3u8" => {
+            let segment = |x| format!("mime/video%2Fmp4/sq/{}/seg.ts", x);
+            (200, media_playlist(3..6, segment, true))
+        }
+        segment => match segment
+            .strip_prefix("/high/mime/video%2Fmp4/sq/")
+            .and_then(|x| x.strip_suffix("/seg.ts"))
+        {
+            Some(sequence) => (200, format!("high{};", sequence)),
+            None => (404, String::new()),
+        },
+    })
+    .await;
+
+    let mut data = Vec::new();
+    let recording = Record::new()
+        .max_height(720)
+        .record_url(&Client::new(), url.join("master.m3u8")?, &mut data)
+        .await?;
+
+    assert_eq!(
+        String::from_utf8(data)?,
+        "high0;high1;high2;high3;high4;high5;"
+    );
+    assert_eq!(recording.segments(), 6);
+    assert!(recording.gaps().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_gaps() -> Result<(), Box<dyn std::error::Error>> {
+    // The playlist slides forward on every request and ends at segment 5
+    let url = serve(|path, count| match path {
+        "/live.m3u8" => {
+            let start = count.min(3) as u64;
+            let playlist = media_playlist(start..start + 3, |x| format!("seg{}.ts", x), start == 3);
+            (200, playlist)
+        }
+        // A client error is permanent, so it is not retried
+        "/seg2.ts" if count == 0 => (404, String::new()),
+        "/seg4.ts" if count == 0 => (500, String::new()),
+        segment => (200, segment.trim_start_matches('/').replace(".ts", ";")),
+    })
+    .await;
+
+    let mut data = Vec::new();
+    let recording = Record::new()
+        .retries(2)
+        .record_url(&Client::new(), url.join("live.m3u8")?, &mut data)
+        .await?;
+
+    assert_eq!(String::from_utf8(data)?, "seg0;seg1;seg3;seg4;seg5;");
+    assert_eq!(recording.segments(), 5);
+    assert_eq!(recording.gaps(), &[std::ops::Range { start: 2, end: 3 }]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_live_edge() -> Result<(), Box<dyn std::error::Error>> {
+    let url = serve(|path, count| match path {
+        "/live.m3u8" => {
+            let start = count.min(3) as u64;
+            let playlist = media_playlist(start..start + 3, |x| format!("seg{}.ts", x), start == 3);
+            (200, playlist)
+        }
+        segment => (200, segment.trim_start_matches('/').replace(".ts", ";")),
+    })
+    .await;
+
+    let mut data = Vec::new();
+    let recording = Record::new()
+        .from_start(false)
+        .record_url(&Client::new(), url.join("live.m3u8")?, &mut data)
+        .await?;
+
+    assert_eq!(String::from_utf8(data)?, "seg2;seg3;seg4;seg5;");
+    assert_eq!(recording.segments(), 4);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn record_forbidden_segment() -> Result<(), Box<dyn std::error::Error>> {
+    // Segment 1 stays forbidden, no matter how often the playlist is refreshed
+    let url = serve(|path, _| match path {
+        "/live.m3u8" => (200, media_playlist(0..3, |x| format!("seg{}.ts", x), true)),
+        "/seg1.ts" => (403, String::new()),
+        segment => (200, segment.trim_start_matches('/').replace(".ts", ";")),
+    })
+    .await;
+    let manifest = url.join("live.m3u8")?;
+    let player = serve(move |path, _| match path {
+        "/player" => (
+            200,
+            format!(
+                r#"{{ "streamingData": {{ "hlsManifestUrl": "{}" }} }}"#,
+                manifest
+            ),
+        ),
+        _ => (404, String::new()),
+    })
+    .await;
+
+    let mut data = Vec::new();
+    let recording = Record::new()
+        .retries(2)
+        .record(
+            &Client::new().with_api_url(player),
+            "jfKfPfyJRdk".parse()?,
+            &mut data,
+        )
+        .await?;
+
+    assert_eq!(String::from_utf8(data)?, "seg0;seg2;");
+    assert_eq!(recording.segments(), 2);
+    assert_eq!(recording.gaps(), &[std::ops::Range { start: 1, end: 2 }]);
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "mux")]
+
+use ytextract::{
+    mux::{Format, Metadata, Mux},
+    stream::{Download, Query, Selector},
+    Client,
+};
+
+async fn mux(format: Format, codec: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let client = Client::new();
+    let video = client.video("9bZkp7q19f0".parse()?).await?;
+
+    let (video_stream, audio_stream) = Selector::video_audio(
+        Query::worst().codec(codec),
+        Query::worst().container(if codec == "avc1" { "mp4" } else { "webm" }),
+    )
+    .select(video.streams().await?)
+    .expect("No selection")
+    .into_parts();
+
+    let mut data = Vec::new();
+    let written = Mux::new()
+        .format(format)
+        .metadata(Metadata::from(&video))
+        .download(
+            &video_stream.expect("No video"),
+            &audio_stream.expect("No audio"),
+            &mut data,
+            Download::new(),
+        )
+        .await?;
+
+    assert_ne!(written, Format::Auto);
+    Ok(data)
+}
+
+#[tokio::test]
+async fn mp4() -> Result<(), Box<dyn std::error::Error>> {
+    let data = mux(Format::Auto, "avc1").await?;
+    assert_eq!(&data[4..8], b"ftyp");
+    Ok(())
+}
+
+#[tokio::test]
+async fn matroska() -> Result<(), Box<dyn std::error::Error>> {
+    let data = mux(Format::Auto, "vp9").await?;
+    assert_eq!(&data[..4], &[0x1A, 0x45, 0xDF, 0xA3]);
+
+    let data = mux(Format::Matroska, "avc1").await?;
+    assert_eq!(&data[..4], &[0x1A, 0x45, 0xDF, 0xA3]);
+    Ok(())
+}
+
+async fn audio(container: &str) -> Result<(Format, Vec<u8>), Box<dyn std::error::Error>> {
+    let client = Client::new();
+    let video = client.video("9bZkp7q19f0".parse()?).await?;
+
+    let (_, audio_stream) = Selector::audio_only(Query::worst().container(container))
+        .select(video.streams().await?)
+        .expect("No selection")
+        .into_parts();
+
+    let mut data = Vec::new();
+    let written = Mux::new()
+        .metadata(Metadata::fetch(&video).await?)
+        .download_audio(&audio_stream.expect("No audio"), &mut data, Download::new())
+        .await?;
+
+    Ok((written, data))
+}
+
+#[tokio::test]
+async fn opus() -> Result<(), Box<dyn std::error::Error>> {
+    let (format, data) = audio("webm").await?;
+    assert_eq!(format, Format::Ogg);
+    assert_eq!(&data[..4], b"OggS");
+    Ok(())
+}
+
+#[tokio::test]
+async fn m4a() -> Result<(), Box<dyn std::error::Error>> {
+    let (format, data) = audio("mp4").await?;
+    assert_eq!(format, Format::M4a);
+    assert_eq!(&data[8..12], b"M4A ");
+    Ok(())
+}
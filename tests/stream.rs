@@ -1,3 +1,5 @@
+mod common;
+
 use ytextract::Client;
 
 macro_rules! define_test {
@@ -71,3 +73,713 @@ mod error {
         Ok(())
     }
 }
+
+#[tokio::test]
+async fn download() -> Result<(), Box<dyn std::error::Error>> {
+    use ytextract::stream::Download;
+
+    let stream = Client::new()
+        .streams("9bZkp7q19f0".parse()?)
+        .await?
+        .filter(|x| x.is_audio())
+        .min_by_key(|x| x.bitrate())
+        .expect("No audio stream found");
+
+    let mut data = Vec::new();
+    let options = Download::new().connections(4).segment_size(256 * 1024);
+    let written = stream.download(&mut data, options).await?;
+
+    assert_eq!(written, stream.content_length().await?);
+    assert_eq!(data.len() as u64, written);
+
+    Ok(())
+}
+
+mod select {
+    use assert_matches::assert_matches;
+    use ytextract::{
+        stream::{Query, Selector},
+        Client,
+    };
+
+    #[tokio::test]
+    async fn best() -> Result<(), Box<dyn std::error::Error>> {
+        let streams = Client::new().streams("9bZkp7q19f0".parse()?).await?;
+        let selection = Selector::new().select(streams).expect("No selection");
+
+        assert!(selection.video().is_some());
+        assert!(selection.audio().is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn constraints() -> Result<(), Box<dyn std::error::Error>> {
+        let streams: Vec<_> = Client::new()
+            .streams("9bZkp7q19f0".parse()?)
+            .await?
+            .collect();
+
+        let selector = Selector::video_only(Query::best().max_height(480).codec("avc1"));
+        let selection = selector.select(streams.clone()).expect("No selection");
+        let video = selection.video().expect("No video");
+        assert!(video.height() <= 480);
+        assert!(video.mime_type().contains("avc1"));
+        assert!(selection.audio().is_none());
+
+        let selector: Selector = "bv[vcodec^=AVC1.4D]".parse()?;
+        let selection = selector.select(streams.clone()).expect("No selection");
+        let video = selection.video().expect("No video");
+        assert!(video.mime_type().to_ascii_lowercase().contains("avc1.4d"));
+
+        let selector: Selector = "bestvideo[height>=100000]/worstaudio".parse()?;
+        let selection = selector.select(streams).expect("No selection");
+        assert!(selection.video().is_none());
+        assert!(selection.audio().is_some());
+
+        Ok(())
+    }
+
+    /// The streams of a player response with hand-built formats
+    async fn formats() -> Result<Vec<ytextract::Stream>, Box<dyn std::error::Error>> {
+        fn video(itag: u64, mime: &str, height: u64, fps: u64, bitrate: u64) -> String {
+            format!(
+                r#"{{
+                    "url": "http://localhost/{0}", "mimeType": "{1}", "itag": {0},
+                    "bitrate": {2}, "quality": "hd", "width": {3}, "height": {4},
+                    "fps": {5}, "qualityLabel": "{4}p"
+                }}"#,
+                itag,
+                mime,
+                bitrate,
+                height * 16 / 9,
+                height,
+                fps
+            )
+        }
+        fn audio(itag: u64, mime: &str, bitrate: u64, sample_rate: u64) -> String {
+            format!(
+                r#"{{
+                    "url": "http://localhost/{}", "mimeType": "{}", "itag": {},
+                    "bitrate": {}, "quality": "tiny", "audioSampleRate": "{}",
+                    "audioQuality": "AUDIO_QUALITY_MEDIUM", "audioChannels": 2
+                }}"#,
+                itag, mime, itag, bitrate, sample_rate
+            )
+        }
+
+        let formats = [
+            video(
+                137,
+                r#"video/mp4; codecs=\"avc1.640028\""#,
+                1080,
+                30,
+                4_000_000,
+            ),
+            video(248, r#"video/webm; codecs=\"vp9\""#, 1080, 30, 3_000_000),
+            video(
+                136,
+                r#"video/mp4; codecs=\"avc1.4d401f\""#,
+                720,
+                30,
+                2_000_000,
+            ),
+            video(
+                298,
+                r#"video/mp4; codecs=\"avc1.4d4020\""#,
+                720,
+                60,
+                3_500_000,
+            ),
+            audio(251, r#"audio/webm; codecs=\"opus\""#, 160_000, 48_000),
+            audio(140, r#"audio/mp4; codecs=\"mp4a.40.2\""#, 128_000, 44_100),
+            audio(249, r#"audio/webm; codecs=\"opus\""#, 50_000, 48_000),
+        ]
+        .join(",");
+        let response = format!(
+            r#"{{ "streamingData": {{ "adaptiveFormats": [{}] }} }}"#,
+            formats
+        );
+        let api = super::common::serve(move |_, _| (200, response.clone())).await;
+
+        Ok(Client::new()
+            .with_api_url(api)
+            .streams("9bZkp7q19f0".parse()?)
+            .await?
+            .collect())
+    }
+
+    /// The itags of the video and audio stream selected by `selector`
+    fn itags(selector: &Selector, streams: &[ytextract::Stream]) -> Option<(u64, u64)> {
+        let selection = selector.select(streams.to_vec())?;
+        Some((
+            selection.video().map_or(0, |x| x.itag()),
+            selection.audio().map_or(0, |x| x.itag()),
+        ))
+    }
+
+    #[tokio::test]
+    async fn ranking() -> Result<(), Box<dyn std::error::Error>> {
+        let streams = formats().await?;
+
+        assert_eq!(itags(&Selector::new(), &streams), Some((137, 251)));
+        assert_eq!(itags(&"worst".parse()?, &streams), Some((136, 249)));
+        // Preferences win over the quality
+        let selector =
+            Selector::video_audio(Query::best().codec("vp9"), Query::best().container("m4a"));
+        assert_eq!(itags(&selector, &streams), Some((248, 140)));
+        let selector = Selector::audio_only(Query::best().target_bitrate(60_000));
+        assert_eq!(itags(&selector, &streams), Some((0, 249)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn filters() -> Result<(), Box<dyn std::error::Error>> {
+        let streams = formats().await?;
+
+        assert_eq!(itags(&"bv[height<=720]".parse()?, &streams), Some((298, 0)));
+        let selector = Selector::video_only(Query::best().max_height(720).max_fps(30));
+        assert_eq!(itags(&selector, &streams), Some((136, 0)));
+        assert_eq!(itags(&"bv[ext=webm]".parse()?, &streams), Some((248, 0)));
+        assert_eq!(
+            itags(&"best[vcodec^=AVC1.4D][acodec=opus]".parse()?, &streams),
+            Some((298, 251))
+        );
+        // `abr` only applies to the audio stream of `best`
+        assert_eq!(
+            itags(&"best[abr<=130]".parse()?, &streams),
+            Some((137, 140))
+        );
+        assert_eq!(itags(&"ba[abr<100]".parse()?, &streams), Some((0, 249)));
+        assert_eq!(itags(&"bv[height>1080]".parse()?, &streams), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse() {
+        assert!("best".parse::<Selector>().is_ok());
+        assert!("bv[height<=1080][ext=mp4]+ba[acodec^=mp4a]/b"
+            .parse::<Selector>()
+            .is_ok());
+        assert!("worstvideo[fps>30][dynamic_range=HDR10]"
+            .parse::<Selector>()
+            .is_ok());
+
+        assert_matches!(
+            "bestfoo".parse::<Selector>(),
+            Err(ytextract::error::Selector::UnknownStream(x)) if x == "bestfoo"
+        );
+        assert_matches!(
+            "bv*[height<=1080]".parse::<Selector>(),
+            Err(ytextract::error::Selector::UnknownStream(x)) if x == "bv*"
+        );
+        assert_matches!(
+            "best[height~1080]".parse::<Selector>(),
+            Err(ytextract::error::Selector::InvalidFilter(_))
+        );
+        assert_matches!(
+            "best[height<=1080".parse::<Selector>(),
+            Err(ytextract::error::Selector::InvalidFilter(_))
+        );
+        assert_matches!(
+            "bv+".parse::<Selector>(),
+            Err(ytextract::error::Selector::Empty)
+        );
+    }
+}
+
+mod media_type {
+    use ytextract::stream::{
+        media_type::{Codec, Container},
+        MediaType,
+    };
+
+    #[test]
+    fn video() {
+        let media_type = MediaType::from("video/mp4; codecs=\"avc1.64001F\"");
+
+        assert!(media_type.is_video());
+        assert_eq!(media_type.container(), &Container::Mp4);
+        assert_eq!(media_type.extension(), "mp4");
+        assert_eq!(
+            media_type.codecs(),
+            &[Codec::Avc {
+                avc3: false,
+                profile: 0x64,
+                constraints: 0x00,
+                level: 0x1F,
+            }]
+        );
+        assert_eq!(media_type.to_string(), "video/mp4; codecs=\"avc1.64001F\"");
+
+        let codec = Codec::from("avc3.4d401f");
+        assert_eq!(codec.name(), "avc3");
+        // The hex digits are formatted in upper case
+        assert_eq!(codec.to_string(), "avc3.4D401F");
+
+        // The case of the hex digits does not matter
+        assert_eq!(Codec::from("avc1.64001f"), Codec::from("avc1.64001F"));
+        assert_ne!(Codec::from("avc1.64001F"), Codec::from("avc3.64001F"));
+    }
+
+    #[test]
+    fn av1() {
+        let media_type = MediaType::from("video/mp4; codecs=\"av01.0.08M.08\"");
+
+        assert_eq!(
+            media_type.codecs(),
+            &[Codec::Av1 {
+                profile: 0,
+                level: 8,
+                high_tier: false,
+                bit_depth: 8
+            }]
+        );
+        assert_eq!(
+            media_type.to_string(),
+            "video/mp4; codecs=\"av01.0.08M.08\""
+        );
+    }
+
+    #[test]
+    fn audio() {
+        let media_type = MediaType::from("audio/mp4; codecs=\"mp4a.40.2\"");
+
+        assert!(media_type.is_audio());
+        assert_eq!(media_type.extension(), "m4a");
+        assert_eq!(
+            media_type.codecs(),
+            &[Codec::Mp4a {
+                object_type: 0x40,
+                audio_object_type: Some(2)
+            }]
+        );
+
+        let media_type = MediaType::from("audio/webm; codecs=\"opus\"");
+        assert_eq!(media_type.container(), &Container::WebM);
+        assert_eq!(media_type.codecs(), &[Codec::Opus]);
+    }
+
+    #[test]
+    fn round_trip() {
+        for codec in [
+            "avc1.64001F",
+            "avc3.4d401f",
+            "av01.0.08M.08",
+            "vp9",
+            "vp09.00.51.08",
+            "vp8",
+            "vp08",
+            "mp4a.40.2",
+            "mp4a.6b",
+            "opus",
+            "vorbis",
+            "bar.1",
+        ] {
+            let parsed = Codec::from(codec);
+            assert_eq!(
+                Codec::from(parsed.to_string().as_str()),
+                parsed,
+                "{}",
+                codec
+            );
+        }
+
+        // `vp08` has no profile and level, so it is written in its short form
+        assert_eq!(Codec::from("vp08").to_string(), "vp8");
+    }
+
+    #[test]
+    fn unknown() {
+        let media_type = MediaType::from("video/x-foo; codecs=\"bar.1, vp9\"");
+
+        assert_eq!(
+            media_type.container(),
+            &Container::Unknown("x-foo".to_string())
+        );
+        assert_eq!(
+            media_type.codecs(),
+            &[
+                Codec::Unknown("bar.1".to_string()),
+                Codec::Vp9 {
+                    profile: None,
+                    level: None,
+                    bit_depth: None
+                }
+            ]
+        );
+    }
+}
+
+#[tokio::test]
+async fn format_metadata() -> Result<(), Box<dyn std::error::Error>> {
+    let streams: Vec<_> = Client::new()
+        .streams("vX2vsvdq8nw".parse()?)
+        .await?
+        .collect();
+
+    for stream in &streams {
+        assert!(stream.itag() > 0);
+        assert!(!stream.quality().is_empty());
+        assert!(stream.init_range().is_some());
+        assert!(stream.index_range().is_some());
+        assert!(stream.last_modified().is_some());
+    }
+
+    assert!(streams.iter().any(|stream| match stream {
+        ytextract::Stream::Video(video) => video.is_hdr() && video.color_info().is_some(),
+        ytextract::Stream::Audio(_) => false,
+    }));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn format_defaults() -> Result<(), Box<dyn std::error::Error>> {
+    // A format without a quality and with a init range up to the last byte
+    let api = common::serve(|_, _| {
+        (
+            200,
+            r#"{
+                "streamingData": {
+                    "adaptiveFormats": [{
+                        "url": "http://localhost/stream",
+                        "mimeType": "audio/webm; codecs=\"opus\"",
+                        "itag": 251,
+                        "bitrate": 128000,
+                        "initRange": { "start": "0", "end": "18446744073709551615" },
+                        "audioSampleRate": "48000",
+                        "audioQuality": "AUDIO_QUALITY_MEDIUM",
+                        "audioChannels": 2
+                    }]
+                }
+            }"#
+            .to_string(),
+        )
+    })
+    .await;
+
+    let stream = Client::new()
+        .with_api_url(api)
+        .streams("9bZkp7q19f0".parse()?)
+        .await?
+        .next()
+        .expect("No stream found");
+
+    assert_eq!(stream.quality(), "");
+    assert_eq!(stream.init_range(), Some(0..u64::MAX));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn audio_tracks() -> Result<(), Box<dyn std::error::Error>> {
+    use ytextract::stream::{Query, Selector};
+
+    // A video with dubbed audio tracks
+    let id = "FE5ehtNmWOQ".parse()?;
+    let client = Client::new();
+
+    let tracks = client.video(id).await?.audio_tracks().await?;
+    assert!(tracks.len() > 1);
+    assert!(tracks.iter().any(|x| x.is_default()));
+
+    let language = tracks
+        .iter()
+        .find(|x| !x.is_default())
+        .expect("No dubbed track")
+        .language()
+        .to_string();
+
+    let selection = Selector::audio_only(Query::best().language(&*language))
+        .select(client.streams(id).await?)
+        .expect("No selection");
+    let track = selection
+        .audio()
+        .and_then(|x| x.audio_track())
+        .expect("No audio track");
+    assert_eq!(track.language(), language);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn expiry() -> Result<(), Box<dyn std::error::Error>> {
+    let stream = Client::new()
+        .streams("9bZkp7q19f0".parse()?)
+        .await?
+        .next()
+        .expect("No stream found");
+
+    assert!(stream.expires_at() > Some(std::time::SystemTime::now()));
+    assert!(!stream.is_expired());
+    assert_eq!(stream.video_id(), "9bZkp7q19f0".parse()?);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn probe() -> Result<(), Box<dyn std::error::Error>> {
+    let stream = Client::new()
+        .streams("9bZkp7q19f0".parse()?)
+        .await?
+        .next()
+        .expect("No stream found");
+
+    let probe = stream.probe().await?;
+    assert_eq!(probe.content_length(), stream.content_length().await?);
+    assert!(probe.accepts_ranges());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn index() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+
+    let streams = Client::new().streams("9bZkp7q19f0".parse()?).await?;
+
+    for stream in streams {
+        let index = stream.index().await?;
+        let segments = index.segments();
+        assert!(!segments.is_empty());
+        assert!(segments[0].range().start >= index.init_range().end);
+
+        for pair in segments.windows(2) {
+            assert_eq!(pair[0].range().end, pair[1].range().start);
+            assert_eq!(pair[0].end(), pair[1].start());
+        }
+
+        let segment = index
+            .segment_at(Duration::from_secs(60))
+            .expect("No segment at 60s");
+        assert!(segment.start() <= Duration::from_secs(60));
+        assert!(segment.end() > Duration::from_secs(60));
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn download_range() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+    use ytextract::stream::Download;
+
+    let streams = Client::new().streams("9bZkp7q19f0".parse()?).await?;
+
+    for stream in streams.filter(|x| x.is_audio()) {
+        let range = Duration::from_secs(60)..Duration::from_secs(90);
+        let index = stream.index().await?;
+        let segments = index.segments_between(range.clone());
+        let expected = index.init_range().end + segments.iter().map(|x| x.size()).sum::<u64>();
+
+        let mut data = Vec::new();
+        let written = stream
+            .download_range(range, &mut data, Download::new())
+            .await?;
+
+        assert_eq!(written, expected);
+        assert_eq!(data.len() as u64, written);
+        assert!(written < stream.content_length().await?);
+    }
+
+    Ok(())
+}
+
+mod refresh {
+    use super::common::{serve, stream_response};
+    use futures::StreamExt;
+    use ytextract::Client;
+
+    #[tokio::test]
+    async fn forbidden() -> Result<(), Box<dyn std::error::Error>> {
+        // The first url is forbidden, the refreshed one is not
+        let media = serve(|path, _| match path {
+            "/new" => (200, "data".to_string()),
+            _ => (403, String::new()),
+        })
+        .await;
+        let (old, new) = (media.join("old")?, media.join("new")?);
+        let api = serve(move |path, count| match (path, count) {
+            ("/player", 0) => (200, stream_response(&old, 251)),
+            ("/player", _) => (200, stream_response(&new, 251)),
+            _ => (404, String::new()),
+        })
+        .await;
+
+        let stream = Client::new()
+            .with_api_url(api)
+            .streams("9bZkp7q19f0".parse()?)
+            .await?
+            .next()
+            .expect("No stream found");
+
+        let mut data = Vec::new();
+        let mut bytes = stream.get().await?;
+        while let Some(chunk) = bytes.next().await {
+            data.extend_from_slice(&chunk?);
+        }
+
+        assert_eq!(data, b"data");
+        assert_eq!(stream.url().path(), "/new");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn unavailable() -> Result<(), Box<dyn std::error::Error>> {
+        // The refreshed player response no longer contains the itag
+        let media = serve(|_, _| (403, String::new())).await;
+        let (old, new) = (media.join("old")?, media.join("new")?);
+        let api = serve(move |path, count| match (path, count) {
+            ("/player", 0) => (200, stream_response(&old, 251)),
+            ("/player", _) => (200, stream_response(&new, 250)),
+            _ => (404, String::new()),
+        })
+        .await;
+
+        let stream = Client::new()
+            .with_api_url(api)
+            .streams("9bZkp7q19f0".parse()?)
+            .await?
+            .next()
+            .expect("No stream found");
+
+        assert!(matches!(
+            stream.get().await,
+            Err(ytextract::Error::Stream(
+                ytextract::error::Stream::Unavailable
+            ))
+        ));
+
+        Ok(())
+    }
+}
+
+mod probe_fallback {
+    use super::common::{serve, serve_with, stream_response, Response};
+    use ytextract::Client;
+
+    async fn stream(media: reqwest::Url) -> Result<ytextract::Stream, Box<dyn std::error::Error>> {
+        let url = media.join("stream")?;
+        let api = serve(move |_, _| (200, stream_response(&url, 251))).await;
+
+        Ok(Client::new()
+            .with_api_url(api)
+            .streams("9bZkp7q19f0".parse()?)
+            .await?
+            .next()
+            .expect("No stream found"))
+    }
+
+    #[tokio::test]
+    async fn head_error() -> Result<(), Box<dyn std::error::Error>> {
+        let media = serve_with(
+            |request, _| match (request.method.as_str(), &request.range) {
+                ("HEAD", _) => Response::new(405, ""),
+                ("GET", Some(range)) if range == "bytes=0-0" => {
+                    Response::new(206, "x").header("Content-Range", "bytes 0-0/1234")
+                }
+                _ => Response::new(400, ""),
+            },
+        )
+        .await;
+
+        let probe = stream(media).await?.probe().await?;
+        assert_eq!(probe.content_length(), 1234);
+        assert!(probe.accepts_ranges());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ignored_range() -> Result<(), Box<dyn std::error::Error>> {
+        // The HEAD has no content length and the GET returns the whole stream
+        let media = serve_with(|request, _| match request.method.as_str() {
+            "HEAD" => Response {
+                status: 200,
+                headers: vec![("Last-Modified", "Tue, 15 Nov 1994 08:12:31 GMT".into())],
+                body: String::new(),
+            },
+            _ => Response::new(200, "x".repeat(1234)),
+        })
+        .await;
+
+        let stream = stream(media).await?;
+        let probe = stream.probe().await?;
+        assert_eq!(probe.content_length(), 1234);
+        assert_eq!(stream.content_length().await?, 1234);
+        assert!(!probe.accepts_ranges());
+        assert_eq!(
+            probe.last_modified().map(|x| x.timestamp()),
+            Some(784_887_151)
+        );
+
+        Ok(())
+    }
+}
+
+mod download_retries {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use super::common::{serve, serve_with, stream_response, Response};
+    use ytextract::{stream::Download, Client};
+
+    /// A stream of 10 bytes, whose `GET` requests are answered by `get`
+    async fn stream(
+        get: impl Fn(usize) -> Response + Send + Sync + 'static,
+    ) -> Result<ytextract::Stream, Box<dyn std::error::Error>> {
+        let media = serve_with(move |request, count| match request.method.as_str() {
+            "HEAD" => Response::new(200, "x".repeat(10)),
+            _ => get(count),
+        })
+        .await;
+        let url = media.join("stream")?;
+        let api = serve(move |_, _| (200, stream_response(&url, 251))).await;
+
+        Ok(Client::new()
+            .with_api_url(api)
+            .streams("9bZkp7q19f0".parse()?)
+            .await?
+            .next()
+            .expect("No stream found"))
+    }
+
+    #[tokio::test]
+    async fn server_error() -> Result<(), Box<dyn std::error::Error>> {
+        let stream = stream(|count| match count {
+            0 => Response::new(500, ""),
+            _ => Response::new(200, "0123456789"),
+        })
+        .await?;
+
+        let mut data = Vec::new();
+        stream.download(&mut data, Download::new()).await?;
+        assert_eq!(data, b"0123456789");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn not_found() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let stream = stream(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Response::new(404, "")
+        })
+        .await?;
+
+        let result = stream.download(&mut Vec::new(), Download::new()).await;
+        assert!(matches!(result, Err(ytextract::Error::Request(_))));
+        // A permanent error is not retried
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+}
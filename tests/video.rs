@@ -1,5 +1,5 @@
 use futures::StreamExt;
-use ytextract::Client;
+use ytextract::{video::LiveStatus, Client};
 
 #[tokio::test]
 async fn get() -> Result<(), Box<dyn std::error::Error>> {
@@ -55,8 +55,22 @@ async fn get() -> Result<(), Box<dyn std::error::Error>> {
     assert!(video.views() >= 1_068_917);
     assert!(video.likes() >= Some(51_745));
     assert!(!video.live());
+    assert_eq!(video.live_status().await?, LiveStatus::NotLive);
     assert!(!video.thumbnails().is_empty());
-    assert_eq!(video.date(), chrono::NaiveDate::from_ymd(2021, 4, 14));
+    assert_eq!(video.date(), chrono::NaiveDate::from_ymd_opt(2021, 4, 14));
+
+    let microformat = video.microformat().await?.expect("No microformat found");
+    assert_eq!(microformat.category(), Some("Science & Technology"));
+    assert_eq!(microformat.is_family_safe(), Some(true));
+    assert_eq!(microformat.is_unlisted(), Some(false));
+    assert!(microformat.available_countries().any(|x| x == "US"));
+    assert!(!microformat.has_ypc_metadata());
+    assert!(microformat.live_broadcast().is_none());
+    assert_eq!(
+        microformat.publish_date().map(|x| x.date_naive()),
+        video.date()
+    );
+    assert!(microformat.embed().is_some());
     assert!(video.hashtags().next().is_none());
 
     let mut streams = video.streams().await?;
@@ -193,3 +207,254 @@ async fn related() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn storyboard() -> Result<(), Box<dyn std::error::Error>> {
+    use std::time::Duration;
+
+    let video = Client::new().video("9bZkp7q19f0".parse()?).await?;
+    let storyboard = video.storyboard().expect("No storyboard found");
+
+    assert!(!storyboard.levels().is_empty());
+    for level in storyboard.levels() {
+        assert!(level.width() > 0 && level.height() > 0);
+        assert_eq!(level.sheet_urls().count() as u32, level.sheets());
+
+        let tile = level.tile(video.duration() + Duration::from_secs(1));
+        assert!(tile.sheet < level.sheets());
+        assert!(tile.x < level.columns() * level.width());
+        assert!(tile.y < level.rows() * level.height());
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn captions() -> Result<(), Box<dyn std::error::Error>> {
+    use ytextract::video::captions::{Format, Kind};
+
+    let video = Client::new().video("9bZkp7q19f0".parse()?).await?;
+    let tracks = video.captions();
+    assert!(!tracks.is_empty());
+
+    let track = tracks
+        .iter()
+        .find(|x| x.kind() == Kind::Manual)
+        .expect("No manual captions found");
+    let captions = track.fetch().await?;
+    assert!(!captions.cues().is_empty());
+    assert!(captions.to_srt().starts_with("1\n"));
+    assert!(captions.to_webvtt().starts_with("WEBVTT"));
+
+    let xml = track.fetch_format(Format::Xml).await?;
+    assert_eq!(xml.cues().len(), captions.cues().len());
+
+    let language = video
+        .translation_languages()
+        .into_iter()
+        .find(|x| x.language() != track.language())
+        .expect("No translation language found");
+    let translated = track
+        .translate(language.language())
+        .expect("Track is not translatable");
+    assert!(!translated.fetch().await?.cues().is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn transcript() -> Result<(), Box<dyn std::error::Error>> {
+    let video = Client::new().video("9bZkp7q19f0".parse()?).await?;
+    let transcript = video.transcript().await?.expect("No transcript found");
+
+    assert!(!transcript.segments().is_empty());
+    for segment in transcript.segments() {
+        assert!(segment.start() <= segment.end());
+    }
+
+    let language = transcript
+        .languages()
+        .iter()
+        .find(|x| !x.is_selected())
+        .expect("No other language found");
+    let other = transcript
+        .select(language)
+        .await?
+        .expect("No transcript found");
+    assert_eq!(other.language().map(|x| x.name()), Some(language.name()));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn description_segments() -> Result<(), Box<dyn std::error::Error>> {
+    use ytextract::video::description::Segment;
+
+    let video = Client::new().video("rfscVS0vtbw".parse()?).await?;
+    let segments = video.description_segments();
+
+    assert!(segments
+        .iter()
+        .any(|x| matches!(x, Segment::Timestamp { .. })));
+    for segment in &segments {
+        if let Segment::Url { url, .. } = segment {
+            assert!(!url.contains("youtube.com/redirect"));
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn chapters() -> Result<(), Box<dyn std::error::Error>> {
+    use ytextract::video::chapters;
+
+    let video = Client::new().video("rfscVS0vtbw".parse()?).await?;
+    let chapters = video.chapters();
+
+    assert!(chapters.len() > 1);
+    assert_eq!(chapters[0].start(), std::time::Duration::ZERO);
+    for pair in chapters.windows(2) {
+        assert_eq!(pair[0].end(), pair[1].start());
+    }
+    assert_eq!(chapters.last().unwrap().end(), video.duration());
+
+    let metadata = chapters::to_ffmetadata(&chapters);
+    assert_eq!(metadata.matches("[CHAPTER]").count(), chapters.len());
+    let cue_sheet = chapters::to_cue_sheet(&chapters, "audio.opus");
+    assert_eq!(cue_sheet.matches("TRACK").count(), chapters.len());
+
+    Ok(())
+}
+
+/// The age in seconds of a relative time like `3 days ago (edited)`
+fn age(published: &str) -> u64 {
+    let mut words = published.split_whitespace();
+    let amount: u64 = words.next().and_then(|x| x.parse().ok()).unwrap_or(0);
+    let unit = match words.next().unwrap_or_default().trim_end_matches('s') {
+        "second" => 1,
+        "minute" => 60,
+        "hour" => 60 * 60,
+        "day" => 24 * 60 * 60,
+        "week" => 7 * 24 * 60 * 60,
+        "month" => 30 * 24 * 60 * 60,
+        "year" => 365 * 24 * 60 * 60,
+        unit => panic!("Unknown unit: '{}'", unit),
+    };
+    amount * unit
+}
+
+#[tokio::test]
+async fn comments() -> Result<(), Box<dyn std::error::Error>> {
+    use ytextract::video::comment::Sort;
+
+    let video = Client::new().video("9bZkp7q19f0".parse()?).await?;
+
+    for sort in [Sort::Top, Sort::Newest] {
+        let comments = video
+            .comments(sort)
+            .expect("No comments found")
+            .take(50)
+            .collect::<Vec<_>>()
+            .await
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+        assert!(!comments.is_empty());
+
+        if sort == Sort::Newest {
+            // The age of every comment, after the pinned one
+            let ages = comments
+                .iter()
+                .filter(|x| !x.is_pinned())
+                .map(|x| age(&x.published()))
+                .collect::<Vec<_>>();
+            assert!(ages.windows(2).all(|x| x[0] <= x[1]), "{:?}", ages);
+        }
+
+        if let Some(comment) = comments.iter().find(|x| x.reply_count() > 0) {
+            let replies = comment
+                .replies()
+                .expect("No replies found")
+                .take(10)
+                .collect::<Vec<_>>()
+                .await
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?;
+            assert!(!replies.is_empty());
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn live_chat() -> Result<(), Box<dyn std::error::Error>> {
+    use ytextract::video::live_chat::Kind;
+
+    let video = Client::new().video("jfKfPfyJRdk".parse()?).await?;
+    assert!(matches!(
+        video.live_status().await?,
+        LiveStatus::Live { .. }
+    ));
+    let chat = video.live_chat().expect("No live chat found");
+    assert!(!chat.is_replay());
+
+    let events = chat
+        .events()
+        .take(10)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    assert!(!events.is_empty());
+    for event in &events {
+        if let Kind::Message(message) = event.kind() {
+            assert!(!message.id().is_empty());
+            assert!(event.time().is_some());
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn wait_for_live() -> Result<(), Box<dyn std::error::Error>> {
+    use futures::TryStreamExt;
+    use ytextract::video::live::Event;
+
+    // Failed requests are retried, so stop at the first error
+    let events = Client::new()
+        .wait_for_live("jfKfPfyJRdk".parse()?)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    assert!(matches!(
+        events.first(),
+        Some(Event::Status(LiveStatus::Live { .. }))
+    ));
+    match events.last() {
+        Some(Event::Available(available)) => assert!(available.hls_manifest_url().is_some()),
+        other => panic!("Expected the streams to be available, got {:?}", other),
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn short() -> Result<(), Box<dyn std::error::Error>> {
+    let video = Client::new()
+        .video("https://www.youtube.com/shorts/BGQWPY4IigY".parse()?)
+        .await?;
+    assert_eq!(video.id(), "BGQWPY4IigY".parse()?);
+    assert!(video.is_short());
+
+    let short = video.short().await?.expect("No short found");
+    assert_eq!(short.id(), video.id());
+    assert!(short.sound().is_some());
+
+    let video = Client::new().video("9bZkp7q19f0".parse()?).await?;
+    assert!(!video.is_short());
+    assert!(video.short().await?.is_none());
+
+    Ok(())
+}